@@ -0,0 +1,66 @@
+//! Read throughput of hot atoms versus regular primitive atoms
+//!
+//! Run with `cargo bench --features hot --bench hot_atoms`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use jotai_rs::{atom, atom_hot, Store};
+use std::sync::Arc;
+use std::thread;
+
+const READERS: usize = 4;
+const READS_PER_READER: usize = 10_000;
+
+fn single_thread_reads(c: &mut Criterion) {
+    let store = Store::new();
+    let regular = atom(42_u64);
+    let hot = atom_hot(42_u64);
+    store.get(regular.as_atom()).unwrap();
+    store.get(hot.as_atom()).unwrap();
+
+    let mut group = c.benchmark_group("get");
+    group.bench_function("rwlock", |b| {
+        b.iter(|| black_box(store.get(regular.as_atom()).unwrap()))
+    });
+    group.bench_function("hot", |b| {
+        b.iter(|| black_box(store.get(hot.as_atom()).unwrap()))
+    });
+    group.finish();
+}
+
+/// `READERS` threads each reading the atom `READS_PER_READER` times
+fn concurrent_reads(store: &Arc<Store>, read: fn(&Store) -> u64) {
+    let handles: Vec<_> = (0..READERS)
+        .map(|_| {
+            let store = store.clone();
+            thread::spawn(move || {
+                for _ in 0..READS_PER_READER {
+                    black_box(read(&store));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+fn multi_thread_reads(c: &mut Criterion) {
+    // Function pointers cannot capture, so the atoms live in statics
+    static REGULAR: once_cell::sync::Lazy<jotai_rs::PrimitiveAtom<u64>> =
+        once_cell::sync::Lazy::new(|| atom(42));
+    static HOT: once_cell::sync::Lazy<jotai_rs::PrimitiveAtom<u64>> =
+        once_cell::sync::Lazy::new(|| atom_hot(42));
+
+    let store = Arc::new(Store::new());
+    let mut group = c.benchmark_group("concurrent_get");
+    group.bench_function("rwlock", |b| {
+        b.iter(|| concurrent_reads(&store, |store| store.get(REGULAR.as_atom()).unwrap()))
+    });
+    group.bench_function("hot", |b| {
+        b.iter(|| concurrent_reads(&store, |store| store.get(HOT.as_atom()).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, single_thread_reads, multi_thread_reads);
+criterion_main!(benches);
@@ -12,8 +12,19 @@
 //! - First-class functions: Read/write functions stored as data
 //! - Type-level programming: Complex type relationships
 
+use crate::error::AtomError;
 use crate::error::Result;
-use crate::types::{AtomId, Getter, OnUnmount, ReadFn, Setter, WriteFn};
+use crate::store::{Store, StoreWriter};
+#[cfg(feature = "async")]
+use crate::types::{AbortSignal, AsyncReadFn, AsyncWriteFn};
+#[cfg(feature = "async")]
+use futures::future::BoxFuture;
+use crate::types::{
+    AtomId, CachePolicy, CommandFn, EqualityFn, FnValue, Getter, HashFn, OnMount, OnUnmount,
+    ReadFn, WriteFn,
+};
+use std::any::{Any, TypeId};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -38,7 +49,7 @@ static ATOM_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 ///
 /// TODO: Phase 1.1 - Implement atomic counter
 /// Hint: Use ATOM_ID_COUNTER.fetch_add(1, Ordering::Relaxed) to atomically increment and return the ID
-fn next_atom_id() -> AtomId {
+pub(crate) fn next_atom_id() -> AtomId {
     ATOM_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
 }
 
@@ -71,9 +82,6 @@ pub struct Atom<T: Clone + Send + Sync + 'static> {
     ///
     /// The read function is called with a Getter that provides access to
     /// other atoms. It should be deterministic based on its dependencies.
-    ///
-    /// TODO: Phase 1.1 - Store read function
-    /// TODO: Phase 2.2 - Support dependency tracking through Getter
     pub(crate) read_fn: ReadFn<T>,
 
     /// Optional debug label for development
@@ -81,6 +89,61 @@ pub struct Atom<T: Clone + Send + Sync + 'static> {
     /// Reference: `jotai/src/vanilla/atom.ts:45`
     pub(crate) debug_label: Option<String>,
 
+    /// Whether the store may write to this atom
+    ///
+    /// Reference: `jotai/src/vanilla/atom.ts` (`isWritableAtom` checks for `write`)
+    ///
+    /// Read-only derived atoms have no write function; setting them through
+    /// a `Setter` fails with `AtomError::NotWritable`.
+    pub(crate) writable: bool,
+
+    /// Optional equality used to detect unchanged recomputations
+    ///
+    /// Stands in for JavaScript's `Object.is`, which Rust values lack.
+    pub(crate) equals: Option<EqualityFn<T>>,
+
+    /// Optional hash used to detect unchanged recomputations
+    ///
+    /// Cheaper than `equals` for large values: only the new value is hashed
+    /// and compared with the stored hash of the current one.
+    pub(crate) value_hash: Option<HashFn<T>>,
+
+    /// How long the store keeps this atom's computed value
+    pub(crate) cache_policy: CachePolicy,
+
+    /// How many times one computation may run the read function before a
+    /// failure is cached (1 unless made with `atom_retrying`)
+    pub(crate) max_attempts: usize,
+
+    /// Dependencies declared up front (see `atom_derived_explicit`)
+    ///
+    /// When set, the store records these instead of the atoms the read
+    /// function was seen reading.
+    pub(crate) declared_dependencies: Option<Arc<[AtomId]>>,
+
+    /// Optional mount callback, set through `WritableAtom::with_on_mount`
+    ///
+    /// Reference: `jotai/src/vanilla/atom.ts:62`
+    ///
+    /// Called when the atom is first subscribed to.
+    /// Can return a cleanup function to be called on unmount.
+    ///
+    /// **FP Pattern**: Closure for lifecycle management
+    ///
+    /// Lives on the base atom so the store can find it from a `&Atom<T>`.
+    pub(crate) on_mount: Option<OnMount>,
+
+    /// Whether stores serve reads from a lock-free cell (see `atom_hot`)
+    #[cfg(feature = "hot")]
+    pub(crate) hot: bool,
+
+    /// Asynchronous read function, set only for atoms made with `atom_async`
+    ///
+    /// `Store::get_async` awaits it; the plain `read_fn` of such an atom
+    /// reports `AtomError::Pending` until a resolved value is cached.
+    #[cfg(feature = "async")]
+    pub(crate) async_read: Option<AsyncReadFn<T>>,
+
     /// Marker for type safety
     _phantom: std::marker::PhantomData<T>,
 }
@@ -96,6 +159,30 @@ impl<T: Clone + Send + Sync + 'static> Atom<T> {
         self.debug_label.as_deref()
     }
 
+    /// Treat recomputed values equal under `equals` as unchanged (builder pattern)
+    ///
+    /// An unchanged recomputation keeps the atom's epoch, so atoms that read
+    /// it are not recomputed and its listeners are not notified.
+    pub fn with_equality<F>(mut self, equals: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool + Send + Sync + 'static,
+    {
+        self.equals = Some(Arc::new(equals));
+        self
+    }
+
+    /// Give the atom an existing ID, simulating ID reuse in tests
+    #[cfg(test)]
+    pub(crate) fn with_id(mut self, id: AtomId) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Whether this atom can be written to (false for read-only derived atoms)
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
     /// Set or update the debug label (builder pattern)
     ///
     /// TODO: Phase 1.1 - Implement builder pattern for debug label
@@ -106,35 +193,173 @@ impl<T: Clone + Send + Sync + 'static> Atom<T> {
         self
     }
 
-    /// Convert atom to string representation
-    ///
-    /// Reference: `jotai/src/vanilla/atom.ts:105-109`
+    /// Call the read function to compute the value
     ///
-    /// ```typescript
-    /// toString() {
-    ///   return import.meta.env?.MODE !== 'production' && this.debugLabel
-    ///     ? key + ':' + this.debugLabel
-    ///     : key
-    /// }
-    /// ```
-    /// TODO: Phase 1.1 - Implement string representation
-    /// Hint: If debug_label exists, format as "atom{id}:{label}", otherwise "atom{id}"
-    pub fn to_string(&self) -> String {
-        match self.debug_label.as_ref() {
-            Some(label) => format!("atom{}:{}", self.id, label),
-            None => format!("atom{}", self.id),
-        }
+    /// This is used internally by the store, which passes a dependency
+    /// tracking `Getter` so every atom read here becomes a dependency.
+    pub(crate) fn read(&self, getter: &dyn Getter) -> Result<T> {
+        (self.read_fn)(getter)
     }
+}
 
-    /// Call the read function to compute the value
-    ///
-    /// This is used internally by the store.
+/// Anything that can be read as an `Atom<T>`
+///
+/// Lets `Store::get`, `Store::sub` and the getters take a `WritableAtom`
+/// (or another atom wrapper) directly instead of `x.as_atom()`.
+///
+/// `CommandAtom` and `ReturningAtom` don't implement it: `Setter::set` also
+/// takes an `AsAtom`, and would store the value without running their write
+/// function. Read them through `.as_atom()`, write them with
+/// `Store::dispatch` and `Store::set_returning`.
+///
+/// ```compile_fail
+/// use jotai_rs::{atom_command, Setter, Store};
+///
+/// let store = Store::new();
+/// let counter = atom_command(0, |writer, state, by: i32| writer.update(state, |n| n + by));
+/// assert_eq!(store.get(counter.as_atom()).unwrap(), 0);
+/// Setter::set(&store, &counter, 5).unwrap();
+/// ```
+///
+/// ```compile_fail
+/// use jotai_rs::{atom_writable_returning, Setter, Store};
+///
+/// let store = Store::new();
+/// let doubled = atom_writable_returning(|_| Ok(0), |_, value: i32| Ok(value * 2));
+/// assert_eq!(store.get(doubled.as_atom()).unwrap(), 0);
+/// Setter::set(&store, &doubled, 7).unwrap();
+/// ```
+///
+/// **Rust Pattern**: Conversion trait borrowing the inner value (like `AsRef`)
+pub trait AsAtom<T: Clone + Send + Sync + 'static> {
+    /// The readable atom
+    fn as_atom(&self) -> &Atom<T>;
+
+    /// The writable atom, with its write function, if this is one
     ///
-    /// TODO: Phase 1.3 - Use in store.get()
-    /// TODO: Phase 1.3 - Pass proper context (Store reference) to read_fn
-    /// Hint: Simply call (self.read_fn)() to invoke the stored function
-    pub(crate) fn read(&self) -> Result<T> {
-        (self.read_fn)()
+    /// Lets `Setter::set` run a writable derived atom's write function
+    /// instead of storing the value over its read.
+    fn as_writable(&self) -> Option<&WritableAtom<T>> {
+        None
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> AsAtom<T> for Atom<T> {
+    fn as_atom(&self) -> &Atom<T> {
+        self
+    }
+}
+
+// Keeps `store.get(&x.as_atom())` compiling: it passes an `&&Atom<T>`
+impl<T: Clone + Send + Sync + 'static, A: AsAtom<T> + ?Sized> AsAtom<T> for &A {
+    fn as_atom(&self) -> &Atom<T> {
+        (**self).as_atom()
+    }
+
+    fn as_writable(&self) -> Option<&WritableAtom<T>> {
+        (**self).as_writable()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> AsAtom<T> for WritableAtom<T> {
+    fn as_atom(&self) -> &Atom<T> {
+        &self.atom
+    }
+
+    fn as_writable(&self) -> Option<&WritableAtom<T>> {
+        Some(self)
+    }
+}
+
+/// Type-erased view of an atom
+///
+/// `Getter` must be object-safe, so it cannot have a generic `get<T>` method.
+/// Instead, typed atoms are passed around as `&dyn AnyAtom` and read through
+/// the store with their concrete type restored inside `read_in`.
+///
+/// **Rust Pattern**: Double dispatch to recover the concrete type
+pub trait AnyAtom: Send + Sync {
+    /// The atom's unique ID
+    fn id(&self) -> AtomId;
+
+    /// The atom's debug label, if any
+    fn debug_label(&self) -> Option<&str>;
+
+    /// Read this atom's value from `store`, boxed for type erasure
+    fn read_in(&self, store: &Store) -> Result<Box<dyn Any + Send + Sync>>;
+
+    /// Run this atom's read function with `getter`, bypassing the store cache
+    fn read_with(&self, getter: &dyn Getter) -> Result<Box<dyn Any + Send + Sync>>;
+
+    /// `TypeId` of the atom's value type
+    fn value_type_id(&self) -> TypeId;
+
+    /// Whether the atom accepts writes (false for read-only derived atoms)
+    fn is_writable(&self) -> bool;
+
+    /// How long the store keeps the atom's computed value
+    fn cache_policy(&self) -> CachePolicy;
+
+    /// The atom's mount callback, if any
+    fn on_mount(&self) -> Option<OnMount>;
+
+    /// Resolve this atom in `store`, awaiting it if it is async
+    #[cfg(feature = "async")]
+    fn read_async_in(
+        &self,
+        store: &Store,
+    ) -> BoxFuture<'static, Result<Box<dyn Any + Send + Sync>>>;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyAtom for Atom<T> {
+    fn id(&self) -> AtomId {
+        self.id
+    }
+
+    fn debug_label(&self) -> Option<&str> {
+        self.debug_label.as_deref()
+    }
+
+    fn read_in(&self, store: &Store) -> Result<Box<dyn Any + Send + Sync>> {
+        store
+            .read_atom_state(self)
+            .map(|value| Box::new(value) as Box<dyn Any + Send + Sync>)
+    }
+
+    fn read_with(&self, getter: &dyn Getter) -> Result<Box<dyn Any + Send + Sync>> {
+        self.read(getter)
+            .map(|value| Box::new(value) as Box<dyn Any + Send + Sync>)
+    }
+
+    fn value_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    fn cache_policy(&self) -> CachePolicy {
+        self.cache_policy
+    }
+
+    fn on_mount(&self) -> Option<OnMount> {
+        self.on_mount.clone()
+    }
+
+    #[cfg(feature = "async")]
+    fn read_async_in(
+        &self,
+        store: &Store,
+    ) -> BoxFuture<'static, Result<Box<dyn Any + Send + Sync>>> {
+        let store = store.handle();
+        let atom = self.clone();
+        Box::pin(async move {
+            store
+                .get_async(&atom)
+                .await
+                .map(|value| Box::new(value) as Box<dyn Any + Send + Sync>)
+        })
     }
 }
 
@@ -147,9 +372,23 @@ impl<T: Clone + Send + Sync + 'static> std::fmt::Debug for Atom<T> {
     }
 }
 
+/// Formats as `atom{id}`, or `atom{id}:{label}` when labelled
+///
+/// Reference: `jotai/src/vanilla/atom.ts:105-109`
+///
+/// ```typescript
+/// toString() {
+///   return import.meta.env?.MODE !== 'production' && this.debugLabel
+///     ? key + ':' + this.debugLabel
+///     : key
+/// }
+/// ```
 impl<T: Clone + Send + Sync + 'static> std::fmt::Display for Atom<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        match self.debug_label.as_ref() {
+            Some(label) => write!(f, "atom{}:{}", self.id, label),
+            None => write!(f, "atom{}", self.id),
+        }
     }
 }
 
@@ -176,26 +415,17 @@ pub struct WritableAtom<T: Clone + Send + Sync + 'static> {
     /// **FP Pattern**: State transformation function
     ///
     /// The write function receives:
-    /// - Getter: to read current state
-    /// - Setter: to update state
+    /// - StoreWriter: to read and update state
     /// - Value: the new value/action
     ///
-    /// TODO: Phase 1.4 - Implement write handling
+    /// `None` for primitive atoms, whose value the store writes directly.
+    ///
     /// TODO: Phase 5.1 - Support complex write patterns
-    pub(crate) write_fn: WriteFn<T>,
+    pub(crate) write_fn: Option<WriteFn<T>>,
 
-    /// Optional mount callback
-    ///
-    /// Reference: `jotai/src/vanilla/atom.ts:62`
-    ///
-    /// Called when the atom is first subscribed to.
-    /// Can return a cleanup function to be called on unmount.
-    ///
-    /// **FP Pattern**: Closure for lifecycle management
-    ///
-    /// Note: Removed Setter parameter for now to avoid dyn compatibility issues
-    /// TODO: Phase 8.1 - Implement onMount lifecycle with proper setter access
-    pub(crate) on_mount: Option<Arc<dyn Fn() -> Option<OnUnmount> + Send + Sync>>,
+    /// Optional asynchronous write effect, awaited by `Store::set_async`
+    #[cfg(feature = "async")]
+    pub(crate) async_write: Option<AsyncWriteFn<T>>,
 }
 
 impl<T: Clone + Send + Sync + 'static> WritableAtom<T> {
@@ -209,13 +439,17 @@ impl<T: Clone + Send + Sync + 'static> WritableAtom<T> {
         self.atom.id()
     }
 
-    /// Call the write function
-    ///
-    /// TODO: Phase 1.4 - Use in store.set()
-    /// TODO: Phase 1.4 - Pass proper context (Store reference) to write_fn
-    /// Hint: Call (self.write_fn)(value) to invoke the stored write function
-    pub(crate) fn write(&self, value: T) -> Result<()> {
-        (self.write_fn)(value)
+    /// Whether a custom write function replaces storing the value directly
+    pub(crate) fn has_write_fn(&self) -> bool {
+        self.write_fn.is_some()
+    }
+
+    /// Call the write function, or store the value for primitive atoms
+    pub(crate) fn write(&self, writer: &StoreWriter<'_>, value: T) -> Result<()> {
+        match &self.write_fn {
+            Some(write) => write(writer, value),
+            None => writer.set(self, value),
+        }
     }
 
     pub fn with_label(mut self, label: impl Into<String>) -> Self {
@@ -224,15 +458,52 @@ impl<T: Clone + Send + Sync + 'static> WritableAtom<T> {
         self
     }
 
-    /// Call the onMount callback if present
+    /// Attach an asynchronous write effect (builder pattern)
     ///
-    /// TODO: Phase 8.1 - Use in store subscription mounting
-    /// Hint: Check if on_mount exists, if so call it and return the result (Option<OnUnmount>)
-    pub(crate) fn on_mount(&self) -> Option<OnUnmount> {
-        match self.on_mount.as_ref() {
-            Some(f) => f(),
-            None => None,
-        }
+    /// `Store::set_async` updates the atom optimistically, then awaits this
+    /// effect with the written value; if it fails the value is rolled back.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let title = atom(String::new()).with_async_write(|title| async move {
+    ///     api::save_title(title).await
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn with_async_write<F, Fut>(mut self, write: F) -> Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        self.async_write = Some(Arc::new(move |value| Box::pin(write(value))));
+        self
+    }
+
+    /// Attach a mount callback (builder pattern)
+    ///
+    /// Reference: `jotai/src/vanilla/atom.ts:62` (onMount)
+    ///
+    /// The store calls `on_mount` when the atom gains its first subscriber
+    /// (directly or through a subscribed dependent), during the flush that
+    /// follows, handing it a `StoreWriter` to seed state with. The returned
+    /// cleanup, if any, runs when the atom is unmounted again.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let prices = atom(Vec::new()).with_on_mount(|set| {
+    ///     let poller = start_polling();
+    ///     set.set(&prices_seed, poller.latest()).ok();
+    ///     Some(Box::new(move || poller.stop()))
+    /// });
+    /// ```
+    pub fn with_on_mount<F>(mut self, on_mount: F) -> Self
+    where
+        F: Fn(&StoreWriter<'_>) -> Option<OnUnmount> + Send + Sync + 'static,
+    {
+        self.atom.on_mount = Some(Arc::new(on_mount));
+        self
     }
 }
 
@@ -241,7 +512,7 @@ impl<T: Clone + Send + Sync + 'static> std::fmt::Debug for WritableAtom<T> {
         f.debug_struct("WritableAtom")
             .field("id", &self.atom.id)
             .field("debug_label", &self.atom.debug_label)
-            .field("has_on_mount", &self.on_mount.is_some())
+            .field("has_on_mount", &self.atom.on_mount.is_some())
             .finish()
     }
 }
@@ -259,6 +530,104 @@ impl<T: Clone + Send + Sync + 'static> std::fmt::Debug for WritableAtom<T> {
 /// ```
 pub type PrimitiveAtom<T> = WritableAtom<T>;
 
+/// Atom read as a value `V` but written with actions `A`
+///
+/// Reference: `jotai/src/vanilla/atom.ts:42-66` (`WritableAtom<Value, Args, Result>`)
+///
+/// `WritableAtom<T>` reads and writes the same type. A command atom keeps
+/// them apart: reading it gives `V`, while `Store::dispatch` hands an action
+/// `A` to its write function. Created with `atom_command`.
+///
+/// **FP Pattern**: Command pattern, state + interpreter of actions
+#[derive(Clone)]
+pub struct CommandAtom<V: Clone + Send + Sync + 'static, A: 'static> {
+    /// The atom holding the readable value
+    pub(crate) state: PrimitiveAtom<V>,
+
+    /// Interprets dispatched actions
+    pub(crate) write_fn: CommandFn<V, A>,
+}
+
+impl<V: Clone + Send + Sync + 'static, A: 'static> CommandAtom<V, A> {
+    /// The readable atom, for `Store::get`, `sub` and derived atoms
+    pub fn as_atom(&self) -> &Atom<V> {
+        self.state.as_atom()
+    }
+
+    /// Get the atom's unique ID
+    pub fn id(&self) -> AtomId {
+        self.state.id()
+    }
+
+    /// Run the write function with `action`
+    pub(crate) fn dispatch(&self, writer: &StoreWriter<'_>, action: A) -> Result<()> {
+        (self.write_fn)(writer, &self.state, action)
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.state = self.state.with_label(label);
+        self
+    }
+}
+
+impl<V: Clone + Send + Sync + 'static, A: 'static> std::fmt::Debug for CommandAtom<V, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandAtom")
+            .field("id", &self.state.atom.id)
+            .field("debug_label", &self.state.atom.debug_label)
+            .finish()
+    }
+}
+
+/// Writable atom whose write returns a result to the caller
+///
+/// Reference: `jotai/src/vanilla/atom.ts:42-66` (`WritableAtom<Value, Args, Result>`)
+///
+/// Reading it gives `T` like any derived atom; `Store::set_returning`
+/// runs its write function and hands back the `R` it produced (Jotai's
+/// `Result` type parameter). Created with `atom_writable_returning`.
+///
+/// **FP Pattern**: Write as a function with an output
+#[derive(Clone)]
+pub struct ReturningAtom<T: Clone + Send + Sync + 'static, R: 'static> {
+    /// The readable atom
+    pub(crate) atom: Atom<T>,
+
+    /// Handles writes, producing the caller's result
+    pub(crate) write_fn: WriteFn<T, R>,
+}
+
+impl<T: Clone + Send + Sync + 'static, R: 'static> ReturningAtom<T, R> {
+    /// The readable atom, for `Store::get`, `sub` and derived atoms
+    pub fn as_atom(&self) -> &Atom<T> {
+        &self.atom
+    }
+
+    /// Get the atom's unique ID
+    pub fn id(&self) -> AtomId {
+        self.atom.id
+    }
+
+    /// Run the write function with `value`
+    pub(crate) fn write(&self, writer: &StoreWriter<'_>, value: T) -> Result<R> {
+        (self.write_fn)(writer, value)
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.atom.debug_label = Some(label.into());
+        self
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static, R: 'static> std::fmt::Debug for ReturningAtom<T, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReturningAtom")
+            .field("id", &self.atom.id)
+            .field("debug_label", &self.atom.debug_label)
+            .finish()
+    }
+}
+
 // ============================================================================
 // ATOM FACTORY FUNCTIONS
 // ============================================================================
@@ -289,33 +658,100 @@ pub type PrimitiveAtom<T> = WritableAtom<T>;
 ///
 /// let count = atom(0);
 /// ```
-///
-/// TODO: Phase 1.1 - Implement primitive atom factory
-/// Hint:
-/// 1. Call next_atom_id() to get a unique ID
-/// 2. Create read_fn that will read from store (for now, just todo!())
-/// 3. Create write_fn that will write to store (for now, just todo!())
-/// 4. Build and return WritableAtom with these functions
-/// Note: The actual read/write logic happens in the store, not here
 pub fn atom<T: Clone + Send + Sync + 'static>(initial_value: T) -> PrimitiveAtom<T> {
-    // For primitive atoms, the store handles read/write directly
-    // These functions should never be called
-    let initial_value = initial_value.clone();
-    let read_fn = Arc::new(move || Ok(initial_value.clone()));
-    let write_fn = Arc::new(|_| unreachable!("Primitive atom write handled by store"));
+    // For primitive atoms, the store handles read/write directly.
+    // The read function only provides the initial value; once cached, the
+    // store's copy is the source of truth (a primitive has no dependencies,
+    // so its cache never goes stale).
+    let read_fn: ReadFn<T> = Arc::new(move |_get| Ok(initial_value.clone()));
 
     PrimitiveAtom {
         atom: Atom {
             id: next_atom_id(),
             read_fn,
             debug_label: None,
+            writable: true,
+            equals: None,
+            cache_policy: CachePolicy::CacheForever,
+            max_attempts: 1,
+            value_hash: None,
+            declared_dependencies: None,
+            on_mount: None,
+            #[cfg(feature = "hot")]
+            hot: false,
+            #[cfg(feature = "async")]
+            async_read: None,
             _phantom: PhantomData,
         },
-        on_mount: None,
-        write_fn,
+        write_fn: None,
+        #[cfg(feature = "async")]
+        async_write: None,
     }
 }
 
+/// Create a primitive string atom whose values can be interned by the store
+///
+/// The atom holds an `Arc<str>`. In a store with interning enabled (see
+/// `Store::enable_interning`), every value written to or computed for an
+/// `Arc<str>` atom is swapped for the store's shared copy, so atoms holding
+/// equal strings share one allocation.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let status = atom_interned("active");
+/// store.enable_interning();
+/// store.set(&status, Arc::from("done"))?;
+/// ```
+pub fn atom_interned(initial_value: &str) -> PrimitiveAtom<Arc<str>> {
+    atom(Arc::from(initial_value))
+}
+
+/// Create a primitive atom whose value is a function
+///
+/// Swap the function with `set` like any other value, and invoke the
+/// current one with `Store::call`. Derived atoms can read it and call it
+/// directly.
+///
+/// **FP Pattern**: Functions as first-class values
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let format_price = atom_fn(Arc::new(|cents: u64| format!("${}.{:02}", cents / 100, cents % 100)));
+/// let label = store.call(format_price.as_atom(), 1999)?;
+/// store.set(&format_price, Arc::new(|cents: u64| format!("{} EUR cents", cents)))?;
+/// ```
+pub fn atom_fn<X: 'static, Y: 'static>(f: FnValue<X, Y>) -> PrimitiveAtom<FnValue<X, Y>> {
+    atom(f)
+}
+
+/// Create a primitive atom optimized for frequent reads
+///
+/// Behaves exactly like `atom(initial_value)`, but each store also keeps the
+/// value in an `arc_swap::ArcSwap` cell. `get` (and reads from derived atoms)
+/// become a lock-free pointer load plus a clone, skipping the per-atom
+/// `RwLock`; `set` swaps in the new value after updating the store state as
+/// usual, so invalidation, listeners and middleware are unaffected.
+///
+/// Best for atoms read far more often than they are written. Reads still
+/// clone the value, so pair it with cheap-to-clone types (or `Arc<T>`).
+///
+/// **Rust Pattern**: Read-copy-update via atomic pointer swap
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let theme = atom_hot(Theme::Dark);
+/// let current = store.get(theme.as_atom())?;
+/// ```
+#[cfg(feature = "hot")]
+pub fn atom_hot<T: Clone + Send + Sync + 'static>(initial_value: T) -> PrimitiveAtom<T> {
+    let mut hot = atom(initial_value);
+    hot.atom.hot = true;
+    hot
+}
+
 /// Create a read-only derived atom
 ///
 /// Reference: `jotai/src/vanilla/atom.ts:82` (read-only atom overload)
@@ -336,31 +772,290 @@ pub fn atom<T: Clone + Send + Sync + 'static>(initial_value: T) -> PrimitiveAtom
 ///
 /// let count = atom(0);
 /// let double = atom_derived(move |get| {
-///     get(&count) * 2
+///     Ok(get.get(count.as_atom())? * 2)
 /// });
 /// ```
 ///
-/// TODO: Phase 2.2 - Implement with dependency tracking
-/// Hint:
-/// 1. Generate a new atom ID
-/// 2. Capture the user's read function (the F parameter)
-/// 3. Create a read_fn closure that will call the user's read function with a Getter
-/// 4. Return an Atom with this read_fn
-/// Note: Dependency tracking happens when the read function calls get() on other atoms
+/// Dependency tracking happens when the read function calls `get` on other
+/// atoms: the store passes a tracking `Getter` for every evaluation.
 pub fn atom_derived<T, F>(read: F) -> Atom<T>
 where
     T: Clone + Send + Sync + 'static,
     F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
 {
-    let read_fn = Arc::new(|| unreachable!());
+    let read_fn: ReadFn<T> = Arc::new(read);
     Atom {
         id: next_atom_id(),
         read_fn,
         debug_label: None,
+        writable: false,
+        equals: None,
+        cache_policy: CachePolicy::CacheForever,
+        max_attempts: 1,
+        value_hash: None,
+        declared_dependencies: None,
+        on_mount: None,
+        #[cfg(feature = "hot")]
+        hot: false,
+        #[cfg(feature = "async")]
+        async_read: None,
         _phantom: PhantomData,
     }
 }
 
+/// Create a derived atom whose read function also sees its previous value
+///
+/// The read function receives the value this atom computed last time in the
+/// same store, or `initial` on the first computation (and after a failed
+/// one). Each store keeps its own previous value, since it is simply the
+/// atom's cached state there.
+///
+/// **FP Pattern**: Fold / scan - state threaded through successive evaluations
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let running_max = atom_derived_stateful(i32::MIN, move |get, max| {
+///     Ok((*max).max(get.get(input.as_atom())?))
+/// });
+/// ```
+pub fn atom_derived_stateful<T, F>(initial: T, read: F) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter, &T) -> Result<T> + Send + Sync + 'static,
+{
+    atom_derived(move |get| {
+        let previous = get.previous::<T>().unwrap_or_else(|| initial.clone());
+        read(get, &previous)
+    })
+}
+
+/// Create an async derived atom whose read function returns a future
+///
+/// Reference: `jotai/src/vanilla/atom.ts:82` (read-only atom returning a promise)
+///
+/// The read function runs synchronously with a tracking getter, so the
+/// atoms it reads before returning the future become dependencies; it also
+/// receives an `AbortSignal` that fires when the evaluation is superseded
+/// or abandoned. Resolve the atom with `Store::get_async`. A plain `get`
+/// returns the last resolved value, or `AtomError::Pending` before the
+/// first one (and again after a dependency changes).
+///
+/// **FP Pattern**: Deferred computation (future as a value)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let user = atom_async(move |get, signal| {
+///     let id = get.get(user_id.as_atom());
+///     async move { fetch_user(id?, signal).await }
+/// });
+/// let user = store.get_async(&user).await?;
+/// ```
+#[cfg(feature = "async")]
+pub fn atom_async<T, F, Fut>(read: F) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter, AbortSignal) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+{
+    let id = next_atom_id();
+    let async_read: AsyncReadFn<T> = Arc::new(move |get, signal| Box::pin(read(get, signal)));
+    Atom {
+        id,
+        read_fn: Arc::new(move |_get| Err(AtomError::Pending { atom_id: id })),
+        debug_label: None,
+        writable: false,
+        equals: None,
+        cache_policy: CachePolicy::CacheForever,
+        max_attempts: 1,
+        value_hash: None,
+        declared_dependencies: None,
+        on_mount: None,
+        #[cfg(feature = "hot")]
+        hot: false,
+        async_read: Some(async_read),
+        _phantom: PhantomData,
+    }
+}
+
+/// Create a primitive atom whose initial value is computed from other atoms
+///
+/// Reference: `jotai/src/vanilla/utils/atomWithDefault.ts` (one-shot variant)
+///
+/// On the first read in a store, `init` runs against that store's atoms and
+/// its result becomes the atom's value. From then on it behaves like a
+/// primitive atom: later changes to the atoms `init` read are ignored, and
+/// `set` replaces the value. Unlike `atomWithDefault`, the atom does not
+/// keep tracking its default until it is first set.
+///
+/// **FP Pattern**: Lazy initialization, evaluated once per store
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let base = atom(2);
+/// let snapshot = atom_computed_default(move |get| Ok(get.get(base.as_atom())? * 10));
+/// assert_eq!(store.get(snapshot.as_atom())?, 20);
+/// ```
+pub fn atom_computed_default<T, F>(init: F) -> PrimitiveAtom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    // Once a value exists, the dependencies recorded by `init` can still
+    // invalidate the atom; the recomputation then keeps the cached value,
+    // and the always-true equality stops it from counting as a change.
+    let read_fn: ReadFn<T> = Arc::new(move |get| match get.previous::<T>() {
+        Some(value) => Ok(value),
+        None => init(get),
+    });
+
+    PrimitiveAtom {
+        atom: Atom {
+            id: next_atom_id(),
+            read_fn,
+            debug_label: None,
+            writable: true,
+            equals: Some(Arc::new(|_: &T, _: &T| true)),
+            cache_policy: CachePolicy::CacheForever,
+            max_attempts: 1,
+            value_hash: None,
+            declared_dependencies: None,
+            on_mount: None,
+            #[cfg(feature = "hot")]
+            hot: false,
+            #[cfg(feature = "async")]
+            async_read: None,
+            _phantom: PhantomData,
+        },
+        write_fn: None,
+        #[cfg(feature = "async")]
+        async_write: None,
+    }
+}
+
+/// Create a derived atom with an explicit caching lifetime
+///
+/// Behaves like `atom_derived`, except for how long the store keeps the
+/// computed value:
+/// - `CachePolicy::NoCache` recomputes on every read
+/// - `CachePolicy::CacheForever` keeps it until a dependency changes
+/// - `CachePolicy::CacheWhileMounted` also drops it when the atom unmounts
+///
+/// **FP Pattern**: Memoization with a configurable lifetime
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let report = atom_with_policy(
+///     move |get| build_report(&get.get(rows.as_atom())?),
+///     CachePolicy::CacheWhileMounted,
+/// );
+/// ```
+pub fn atom_with_policy<T, F>(read: F, policy: CachePolicy) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    let mut atom = atom_derived(read);
+    atom.cache_policy = policy;
+    atom
+}
+
+/// Create a derived atom that detects unchanged values by hash
+///
+/// After each recomputation the store hashes the new value and compares it
+/// with the stored hash of the current one; equal hashes count as no
+/// change, so the epoch stays put and dependents and listeners are not
+/// notified. Only one value is hashed per recomputation, which beats a
+/// full `PartialEq` comparison for large values.
+///
+/// A hash collision would make a real change go unnoticed. With 64-bit
+/// hashes this is vanishingly unlikely, but use `with_equality` where it is
+/// unacceptable.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let visible_rows = atom_derived_hashed(move |get| {
+///     let rows = get.get(rows.as_atom())?;
+///     Ok(rows.into_iter().filter(|row| row.visible).collect::<Vec<_>>())
+/// });
+/// ```
+pub fn atom_derived_hashed<T, F>(read: F) -> Atom<T>
+where
+    T: Clone + Hash + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    let mut atom = atom_derived(read);
+    atom.value_hash = Some(hash_value::<T>);
+    atom
+}
+
+/// Hash a value with the standard library's default hasher
+pub(crate) fn hash_value<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Create a derived atom with dependencies declared up front
+///
+/// The read function still reads through the getter, but the store does
+/// not trust what it saw being read: after each computation the atom's
+/// dependencies are exactly `dependencies`, which drive invalidation and
+/// mounting. Use it when reads are hidden from tracking, e.g. they go
+/// through a store handle or depend on state outside the store, so the
+/// tracked set would miss atoms the value depends on.
+///
+/// Atoms the read function uses but that are not declared do not
+/// invalidate it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let active = atom_derived_explicit(vec![light.id(), dark.id()], move |get| {
+///     let theme = if prefers_dark() { &dark } else { &light };
+///     get.get(theme.as_atom())
+/// });
+/// ```
+pub fn atom_derived_explicit<T, F>(dependencies: Vec<AtomId>, read: F) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    let mut atom = atom_derived(read);
+    atom.declared_dependencies = Some(dependencies.into());
+    atom
+}
+
+/// Create a derived atom whose failed reads are retried
+///
+/// When the read function returns an error, the store runs it again, up to
+/// `max_attempts` runs in total, and only caches the error if every attempt
+/// failed. A success is cached as usual. Each recomputation (e.g. after a
+/// dependency changes) starts again with a full set of attempts.
+///
+/// A `max_attempts` of 0 is treated as 1.
+///
+/// **FP Pattern**: Retry combinator
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let config = atom_retrying(move |get| load_config(&get.get(path.as_atom())?), 3);
+/// ```
+pub fn atom_retrying<T, F>(read: F, max_attempts: usize) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    let mut atom = atom_derived(read);
+    atom.max_attempts = max_attempts.max(1);
+    atom
+}
+
 /// Create a writable derived atom with custom read and write logic
 ///
 /// Reference: `jotai/src/vanilla/atom.ts:76-79` (writable derived atom overload)
@@ -386,41 +1081,49 @@ where
 /// let last = atom("Doe".to_string());
 ///
 /// let full_name = atom_writable(
-///     |get| format!("{} {}", get(&first), get(&last)),
-///     |get, set, value: String| {
+///     |get| Ok(format!("{} {}", get.get(first.as_atom())?, get.get(last.as_atom())?)),
+///     |writer, value: String| {
 ///         let parts: Vec<&str> = value.split(' ').collect();
 ///         if parts.len() == 2 {
-///             set(&first, parts[0].to_string());
-///             set(&last, parts[1].to_string());
+///             writer.set(&first, parts[0].to_string())?;
+///             writer.set(&last, parts[1].to_string())?;
 ///         }
+///         Ok(())
 ///     }
 /// );
 /// ```
 ///
-/// TODO: Phase 5.1 - Implement writable derived atoms
-/// Hint:
-/// 1. Generate a new atom ID
-/// 2. Capture both the read and write functions
-/// 3. Create read_fn that calls the user's read function with Getter
-/// 4. Create write_fn that calls the user's write function with Getter and Setter
-/// 5. Return WritableAtom with both functions
+/// `store.set(&full_name, ...)` runs the write function; listeners are
+/// notified once, after it returns.
 pub fn atom_writable<T, R, W>(read: R, write: W) -> WritableAtom<T>
 where
     T: Clone + Send + Sync + 'static,
     R: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
-    W: Fn(&dyn Getter, &dyn Setter, T) -> Result<()> + Send + Sync + 'static,
+    W: Fn(&StoreWriter<'_>, T) -> Result<()> + Send + Sync + 'static,
 {
-    let read_fn = Arc::new(|| unreachable!());
-    let write_fn = Arc::new(|v| unreachable!());
+    let read_fn: ReadFn<T> = Arc::new(read);
+    let write_fn: WriteFn<T> = Arc::new(write);
     WritableAtom {
         atom: Atom {
             id: next_atom_id(),
             read_fn,
             debug_label: None,
+            writable: true,
+            equals: None,
+            cache_policy: CachePolicy::CacheForever,
+            max_attempts: 1,
+            value_hash: None,
+            declared_dependencies: None,
+            on_mount: None,
+            #[cfg(feature = "hot")]
+            hot: false,
+            #[cfg(feature = "async")]
+            async_read: None,
             _phantom: PhantomData,
         },
-        write_fn,
-        on_mount: None,
+        write_fn: Some(write_fn),
+        #[cfg(feature = "async")]
+        async_write: None,
     }
 }
 
@@ -437,22 +1140,170 @@ where
 ///
 /// **FP Pattern**: Action-only atoms (like commands/effects)
 ///
-/// TODO: Phase 5.3 - Implement write-only atoms
-pub fn atom_write_only<T, W>(initial_value: T, _write: W) -> WritableAtom<T>
+/// Reading it clones `initial_value`; for an atom with no value at all, use
+/// `atom_action`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let add = atom_write_only(0, move |writer, amount: i32| {
+///     writer.update(&total, |total| total + amount)
+/// });
+/// store.set(&add, 5)?;
+/// ```
+pub fn atom_write_only<T, W>(initial_value: T, write: W) -> WritableAtom<T>
 where
     T: Clone + Send + Sync + 'static,
-    W: Fn(&dyn Getter, &dyn Setter, T) -> Result<()> + Send + Sync + 'static,
+    W: Fn(&StoreWriter<'_>, T) -> Result<()> + Send + Sync + 'static,
 {
-    let write_fn = Arc::new(|_| unreachable!("Write-only atom write handled by store"));
+    let write_fn: WriteFn<T> = Arc::new(write);
     WritableAtom {
         atom: Atom {
             id: next_atom_id(),
-            read_fn: Arc::new(move || Ok(initial_value.clone())), // Clone on each call
+            read_fn: Arc::new(move |_get| Ok(initial_value.clone())), // Clone on each call
             debug_label: None,
+            writable: true,
+            equals: None,
+            cache_policy: CachePolicy::CacheForever,
+            max_attempts: 1,
+            value_hash: None,
+            declared_dependencies: None,
+            on_mount: None,
+            #[cfg(feature = "hot")]
+            hot: false,
+            #[cfg(feature = "async")]
+            async_read: None,
             _phantom: PhantomData,
         },
-        write_fn,
-        on_mount: None,
+        write_fn: Some(write_fn),
+        #[cfg(feature = "async")]
+        async_write: None,
+    }
+}
+
+/// Create an action atom: written with `A`, holding no value
+///
+/// Reference: `jotai/src/vanilla/atom.ts:84-88` (`atom(null, write)`)
+///
+/// ```typescript
+/// const incrementAtom = atom(null, (get, set, by: number) => {
+///   set(countAtom, get(countAtom) + by)
+/// })
+/// ```
+///
+/// `Store::set(&action, args)` runs `write`, which reads and writes other
+/// atoms through the `StoreWriter`. Reading the action itself fails with
+/// `AtomError::NotReadable`: there is no stored value, and `args` is never
+/// kept.
+///
+/// **FP Pattern**: Effect as a value (a named, dispatchable write)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let increment = atom_action(move |writer, by: i32| writer.update(&count, |n| n + by));
+/// store.set(&increment, 2)?;
+/// ```
+pub fn atom_action<A, W>(write: W) -> WritableAtom<A>
+where
+    A: Clone + Send + Sync + 'static,
+    W: Fn(&StoreWriter<'_>, A) -> Result<()> + Send + Sync + 'static,
+{
+    let id = next_atom_id();
+    let write_fn: WriteFn<A> = Arc::new(write);
+    WritableAtom {
+        atom: Atom {
+            id,
+            read_fn: Arc::new(move |_get| Err(AtomError::NotReadable { atom_id: id })),
+            debug_label: None,
+            writable: true,
+            equals: None,
+            cache_policy: CachePolicy::CacheForever,
+            max_attempts: 1,
+            value_hash: None,
+            declared_dependencies: None,
+            on_mount: None,
+            #[cfg(feature = "hot")]
+            hot: false,
+            #[cfg(feature = "async")]
+            async_read: None,
+            _phantom: PhantomData,
+        },
+        write_fn: Some(write_fn),
+        #[cfg(feature = "async")]
+        async_write: None,
+    }
+}
+
+/// Create an atom read as `V` and written with actions of type `A`
+///
+/// Reference: `jotai/src/vanilla/atom.ts:84-88` (`atom(initialValue, write)`
+/// with write args differing from the value)
+///
+/// The atom starts at `initial_value`. `Store::dispatch(&atom, action)`
+/// calls `write` with a `StoreWriter`, the atom holding the value (so the
+/// write can read and set it, like `set(thisAtom, ...)` in Jotai) and the
+/// action. Setting the value directly is not possible: all changes go
+/// through actions.
+///
+/// **FP Pattern**: Command pattern (actions interpreted by the write function)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let todos = atom_command(Vec::new(), |writer, todos, action: TodoAction| match action {
+///     TodoAction::Add(todo) => writer.update(todos, |list| [list, vec![todo]].concat()),
+///     TodoAction::Clear => writer.set(todos, Vec::new()),
+/// });
+/// store.dispatch(&todos, TodoAction::Add(todo))?;
+/// let list = store.get(todos.as_atom())?;
+/// ```
+pub fn atom_command<V, A, W>(initial_value: V, write: W) -> CommandAtom<V, A>
+where
+    V: Clone + Send + Sync + 'static,
+    A: 'static,
+    W: Fn(&StoreWriter<'_>, &WritableAtom<V>, A) -> Result<()> + Send + Sync + 'static,
+{
+    CommandAtom {
+        state: atom(initial_value),
+        write_fn: Arc::new(write),
+    }
+}
+
+/// Create a writable derived atom whose write returns a value
+///
+/// Like `atom_writable`, but `write` produces an `R` that
+/// `Store::set_returning` returns to the caller, e.g. the ID of a record
+/// the write created.
+///
+/// **FP Pattern**: Write as a function with an output
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let create_user = atom_writable_returning(
+///     move |get| get.get(last_name.as_atom()),
+///     move |writer, name: String| {
+///         let id = next_id();
+///         writer.update(&users, |mut users| { users.push((id, name.clone())); users })?;
+///         writer.set(&last_name, name)?;
+///         Ok(id)
+///     },
+/// );
+/// let id = store.set_returning(&create_user, "Ada".to_string())?;
+/// ```
+pub fn atom_writable_returning<T, R, Rd, W>(read: Rd, write: W) -> ReturningAtom<T, R>
+where
+    T: Clone + Send + Sync + 'static,
+    R: 'static,
+    Rd: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+    W: Fn(&StoreWriter<'_>, T) -> Result<R> + Send + Sync + 'static,
+{
+    let mut atom = atom_derived(read);
+    atom.writable = true;
+    ReturningAtom {
+        atom,
+        write_fn: Arc::new(write),
     }
 }
 
@@ -569,7 +1420,7 @@ mod tests {
 
     #[test]
     fn test_atom_display_trait() {
-        // Test that Display trait uses to_string()
+        // Test that Display and to_string() agree
         let atom1 = atom(100);
         let atom2 = atom(200).with_label("test");
 
@@ -596,7 +1447,7 @@ mod tests {
     fn test_primitive_atom_creation() {
         // Test creating primitive atoms with different types
         let _int_atom = atom(42);
-        let _float_atom = atom(3.14);
+        let _float_atom = atom(2.5);
         let _bool_atom = atom(true);
         let _string_atom = atom(String::from("hello"));
         let _vec_atom = atom(vec![1, 2, 3]);
@@ -604,23 +1455,101 @@ mod tests {
         // If we got here without panicking, primitive atoms work
     }
 
-    // NOTE: Tests for atom_derived, atom_writable, and atom_write_only are
-    // disabled because they require dyn-compatible Getter/Setter traits.
-    // These will be testable in Phase 2 when we implement the Store.
+    // Read functions take `&dyn Getter`: `Getter` is object-safe
+    // (`get_erased`), and the typed `get` lives on `dyn Getter` itself.
 
-    // TODO: Phase 2.2 - Re-enable these tests when Store is implemented
-    // #[test]
-    // fn test_derived_atom_creation() { ... }
-    // #[test]
-    // fn test_writable_atom_creation() { ... }
-    // #[test]
-    // fn test_write_only_atom_creation() { ... }
-    // #[test]
-    // fn test_derived_atom_has_unique_id() { ... }
-    // #[test]
-    // fn test_derived_atom_with_label() { ... }
-    // #[test]
-    // fn test_writable_atom_with_label() { ... }
+    #[test]
+    fn test_derived_atom_creation() {
+        let base = atom(21);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        assert!(!doubled.is_writable());
+
+        let store = Store::new();
+        assert_eq!(store.get(&doubled).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_derived_atom_has_unique_id() {
+        let a = atom_derived(|_get| Ok(1));
+        let b = atom_derived(|_get| Ok(1));
+        assert_ne!(a.id(), b.id());
+    }
+
+    #[test]
+    fn test_derived_atom_with_label() {
+        let base = atom("ada".to_string());
+        let source = base.clone();
+        let upper = atom_derived(move |get| Ok(get.get(source.as_atom())?.to_uppercase()))
+            .with_label("upper");
+        assert_eq!(upper.debug_label(), Some("upper"));
+
+        let store = Store::new();
+        assert_eq!(store.get(&upper).unwrap(), "ADA");
+    }
+
+    // Write functions take a concrete `StoreWriter` instead of `&dyn Setter`,
+    // whose generic `set` is not object-safe.
+
+    #[test]
+    fn test_writable_atom_creation() {
+        let celsius = atom(0.0_f64);
+        let fahrenheit = {
+            let (read, write) = (celsius.clone(), celsius.clone());
+            atom_writable(
+                move |get| Ok(get.get(read.as_atom())? * 9.0 / 5.0 + 32.0),
+                move |writer, value: f64| writer.set(&write, (value - 32.0) * 5.0 / 9.0),
+            )
+        };
+        assert!(fahrenheit.as_atom().is_writable());
+
+        let store = Store::new();
+        assert_eq!(store.get(fahrenheit.as_atom()).unwrap(), 32.0);
+
+        // The write routes through the store to the source atom
+        store.set(&fahrenheit, 212.0).unwrap();
+        assert_eq!(store.get(celsius.as_atom()).unwrap(), 100.0);
+        assert_eq!(store.get(fahrenheit.as_atom()).unwrap(), 212.0);
+    }
+
+    #[test]
+    fn test_write_only_atom_creation() {
+        let total = atom(0);
+        let target = total.clone();
+        let add = atom_write_only(0, move |writer, amount: i32| {
+            writer.update(&target, |total| total + amount)
+        });
+
+        let store = Store::new();
+        store.set(&add, 5).unwrap();
+        store.set(&add, 2).unwrap();
+        assert_eq!(store.get(total.as_atom()).unwrap(), 7);
+        // The action atom's own value is its initial value
+        assert_eq!(store.get(add.as_atom()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_action_atom_writes_and_is_not_readable() {
+        let count = atom(0);
+        let target = count.clone();
+        let increment = atom_action(move |writer, by: i32| writer.update(&target, |n| n + by));
+
+        let store = Store::new();
+        store.set(&increment, 2).unwrap();
+        store.set(&increment, 3).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 5);
+        match store.get(increment.as_atom()) {
+            Err(AtomError::NotReadable { atom_id }) => assert_eq!(atom_id, increment.id()),
+            other => panic!("expected NotReadable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_writable_atom_with_label() {
+        let writable = atom_writable(|_get| Ok(1), |_writer, _value: i32| Ok(()))
+            .with_label("writable");
+        assert_eq!(writable.as_atom().debug_label(), Some("writable"));
+    }
 
     // ========================================================================
     // Phase 1.1: Atom Cloning and Ownership
@@ -753,15 +1682,35 @@ mod tests {
         // Atoms should have no onMount callback by default
         let atom1 = atom(42);
 
-        // We can't directly check on_mount (it's private), but we can verify
-        // it doesn't panic when accessed internally
-        let result = atom1.on_mount();
-        assert!(result.is_none());
+        // The store finds the callback through the type-erased atom
+        assert!(AnyAtom::on_mount(atom1.as_atom()).is_none());
+    }
+
+    #[test]
+    fn test_with_label_and_with_on_mount_chain() {
+        let unmounts = Arc::new(AtomicUsize::new(0));
+        let counter = unmounts.clone();
+        let ticker = atom(0).with_label("ticker").with_on_mount(move |_set| {
+            let counter = counter.clone();
+            Some(Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }))
+        });
+        assert_eq!(ticker.as_atom().debug_label(), Some("ticker"));
+        assert!(ticker.as_atom().on_mount.is_some());
+
+        // The cleanup runs exactly once per unmount
+        let store = Store::new();
+        let unsub = store.sub(ticker.as_atom(), || {});
+        assert_eq!(unmounts.load(Ordering::SeqCst), 0);
+        unsub();
+        assert_eq!(unmounts.load(Ordering::SeqCst), 1);
+        store.set(&ticker, 1).unwrap();
+        assert_eq!(unmounts.load(Ordering::SeqCst), 1);
     }
 
     // TODO: Phase 1.3 - Add tests for atom read function with Store
     // TODO: Phase 1.4 - Add tests for atom write function with Store
     // TODO: Phase 2.2 - Add tests for derived atoms with dependencies
     // TODO: Phase 5.1 - Add tests for writable derived atoms
-    // TODO: Phase 8.1 - Add tests for onMount lifecycle
 }
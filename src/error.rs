@@ -42,8 +42,6 @@ pub enum AtomError {
     /// Circular dependency detected
     ///
     /// Reference: `jotai/src/vanilla/internals.ts` (cycle detection in DFS)
-    ///
-    /// TODO: Phase 4.1 - Implement cycle detection in topological sort
     #[error("Circular dependency detected involving atom {atom_id}")]
     CircularDependency {
         atom_id: usize,
@@ -69,13 +67,17 @@ pub enum AtomError {
     },
 
     /// Atom is not writable (no write function)
-    ///
-    /// TODO: Phase 1.4 - Check writability before attempting set
     #[error("Atom {atom_id} is read-only and cannot be written to")]
     NotWritable {
         atom_id: usize,
     },
 
+    /// Atom is write-only (an action atom, with no value to read)
+    #[error("Atom {atom_id} is write-only and cannot be read")]
+    NotReadable {
+        atom_id: usize,
+    },
+
     /// Promise/async operation failed
     ///
     /// TODO: Phase 6 - Handle async errors
@@ -85,6 +87,17 @@ pub enum AtomError {
         message: String,
     },
 
+    /// Async atom has not resolved yet
+    ///
+    /// Reference: reading a pending promise in Jotai (Suspense)
+    ///
+    /// Returned by a synchronous read of an async atom with no resolved
+    /// value; `Store::get_async` waits for the value instead.
+    #[error("Async atom {atom_id} has not resolved yet")]
+    Pending {
+        atom_id: usize,
+    },
+
     /// Promise/async operation was cancelled
     ///
     /// Reference: AbortSignal handling in Jotai
@@ -28,7 +28,7 @@
 //! ## Example Usage
 //!
 //! ```rust,ignore
-//! use jotai_rs::{atom, Store};
+//! use jotai_rs::{atom, atom_derived, Store};
 //!
 //! // Create a store
 //! let store = Store::new();
@@ -44,7 +44,7 @@
 //! assert_eq!(store.get(&count), 5);
 //!
 //! // Create derived atom
-//! let double = atom(|get| get(&count) * 2);
+//! let double = atom_derived(move |get| Ok(get.get(count.as_atom())? * 2));
 //! assert_eq!(store.get(&double), 10);
 //!
 //! // Subscribe to changes
@@ -59,22 +59,64 @@ pub mod store;
 pub mod types;
 pub mod error;
 pub mod utils;
+pub mod macros;
 
 // Internal implementation (not public API)
 mod internals;
 
 // Re-export commonly used types
-pub use atom::{Atom, PrimitiveAtom, WritableAtom, atom};
-pub use store::Store;
-pub use types::{AtomId, EpochNumber, Getter, Setter};
+pub use atom::{
+    AnyAtom, AsAtom, Atom, CommandAtom, PrimitiveAtom, ReturningAtom, WritableAtom, atom,
+    atom_action, atom_command, atom_computed_default, atom_derived, atom_derived_explicit,
+    atom_derived_hashed, atom_derived_stateful, atom_fn, atom_interned, atom_retrying,
+    atom_with_policy, atom_writable_returning,
+};
+pub use store::{FlushMode, Store, StoreWriter, WriteList};
+pub use types::{
+    AtomId, CachePolicy, ChangeEvent, EpochNumber, ErasedOverride, FnValue, Getter, Middleware,
+    MountedInfo, Next, Notification, PanicInfo, PanicSite, SetContext, Setter, SnapshotEntry,
+    StoreSnapshot, Subscription, SubscriptionSet, Unsubscribe,
+};
 pub use error::{AtomError, Result};
+// `atoms!` is exported at the crate root by `#[macro_export]`
 
 // Re-export utility functions
 pub use utils::{
-    atom_family::atom_family,
-    select_atom::select_atom,
+    atom_backed::{atom_backed, AtomBackend},
+    atom_env::{atom_env, EnvAtom},
+    atom_family::{atom_family, atom_family_lru, AtomFamily, FamilyEvent},
+    atom_with_history::{atom_with_history, HistoryAtom, HistoryIter},
+    atom_with_refresh::{atom_with_refresh, RefreshAtom},
+    dirty_atom::dirty_atom,
+    freeze_atom::freeze_atom,
+    loadable::{loadable, Loadable},
+    map_entry_atom::map_entry_atom,
+    reduce_list_atom::reduce_list_atom,
+    select_atom::{select_atom, select_atom_with_prev, try_select_atom},
+    split_atom::split_atom,
+    zip_atoms::zip_atoms,
 };
 
+// Async atoms (requires the `async` feature)
+#[cfg(feature = "async")]
+pub use atom::atom_async;
+#[cfg(feature = "async")]
+pub use types::AbortSignal;
+#[cfg(feature = "async")]
+pub use utils::join_async::join_async;
+#[cfg(feature = "async")]
+pub use utils::race_atom::race_atom;
+#[cfg(feature = "async")]
+pub use utils::select_atom::select_atom_async;
+
+// Persisted atoms (requires the `storage` feature)
+#[cfg(feature = "storage")]
+pub use utils::atom_with_storage::{atom_with_storage, MemoryStorage, Storage};
+
+// Lock-free hot atoms (requires the `hot` feature)
+#[cfg(feature = "hot")]
+pub use atom::atom_hot;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,66 @@
+//! Declarative helpers for building groups of atoms
+//!
+//! Jotai has no equivalent: in TypeScript an object literal of `atom(...)`
+//! calls is already terse. Here each atom would otherwise need its own
+//! `let` binding and `.with_label(...)` call.
+//!
+//! ## Functional Programming Patterns
+//! - Code generation (macros expand to plain constructor calls)
+
+/// Build a struct of primitive atoms, one per field
+///
+/// Each `name: initial` pair becomes a field `name` holding
+/// `atom(initial).with_label("name")`, so the field name doubles as the
+/// atom's debug label. The struct is defined by the macro and its field
+/// types are inferred from the initial values; it derives `Clone` and
+/// `Debug`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atoms, Store};
+///
+/// let form = atoms! {
+///     count: 0,
+///     name: "".to_string(),
+/// };
+/// let store = Store::new();
+/// store.set(&form.count, 1)?;
+/// assert_eq!(form.name.as_atom().debug_label(), Some("name"));
+/// ```
+#[macro_export]
+macro_rules! atoms {
+    ($($field:ident : $initial:expr),+ $(,)?) => {{
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Debug)]
+        struct Atoms<$($field),+> {
+            $($field: $field),+
+        }
+
+        Atoms {
+            $($field: $crate::atom::atom($initial).with_label(stringify!($field))),+
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Store;
+
+    #[test]
+    fn test_atoms_macro_builds_labelled_atoms() {
+        let form = atoms! {
+            count: 0,
+            name: "".to_string(),
+        };
+        let store = Store::new();
+
+        store.set(&form.count, 3).unwrap();
+        store.set(&form.name, "jotai".to_string()).unwrap();
+        assert_eq!(store.get(form.count.as_atom()).unwrap(), 3);
+        assert_eq!(store.get(form.name.as_atom()).unwrap(), "jotai");
+
+        assert_eq!(form.count.as_atom().debug_label(), Some("count"));
+        assert_eq!(form.name.as_atom().debug_label(), Some("name"));
+    }
+}
@@ -11,15 +11,41 @@
 //! - Monadic patterns: Getter/Setter provide controlled state access
 
 use dashmap::DashMap;
-use parking_lot::{Mutex, RwLock};
-use std::any::Any;
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use parking_lot::{Mutex, ReentrantMutex, RwLock};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread::ThreadId;
+#[cfg(feature = "profiling")]
+use std::time::Duration;
+use std::time::Instant;
 
-use crate::atom::{self, Atom, WritableAtom};
+use crate::atom::{AnyAtom, AsAtom, Atom, CommandAtom, ReturningAtom, WritableAtom};
 use crate::error::{AtomError, Result};
-use crate::internals::{AtomState, Mounted};
-use crate::types::{AtomId, EpochNumber, Getter, Listener, Setter, Unsubscribe};
+use crate::internals::{
+    AnyAtomState, AtomState, DependencyTracker, Mounted, OverrideGetter, TopologicalSorter,
+    ValueSetter,
+};
+#[cfg(feature = "async")]
+use crate::types::AbortSignal;
+use crate::types::{
+    AtomId, CachePolicy, ChangeEvent, EpochNumber, EqualityFn, ErasedOverride, FnValue, Getter,
+    Listener, ListenerId, Middleware, MountedInfo, Next, Notification, PanicHandler, PanicInfo,
+    PanicSite, SetContext, Setter, SnapshotEntry, StoreSnapshot, Subscription, Unsubscribe,
+};
+use crate::utils::atom_env::EnvAtom;
+use crate::utils::atom_with_history::HistoryAtom;
+use crate::utils::atom_with_refresh::RefreshAtom;
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, FutureExt, Shared, WeakShared};
+
+/// Shared, type-erased state of one atom
+pub(crate) type SharedAtomState = Arc<RwLock<Box<dyn AnyAtomState>>>;
+
+/// Queue of one-shot lifecycle callbacks run at the end of a flush
+pub(crate) type CallbackQueue = Arc<Mutex<Vec<Box<dyn FnOnce() + Send>>>>;
 
 /// The Store manages all atom state and coordinates updates
 ///
@@ -28,22 +54,34 @@ use crate::types::{AtomId, EpochNumber, Getter, Listener, Setter, Unsubscribe};
 /// The Store contains several key data structures:
 /// - `atom_states`: Maps atom IDs to their current state (value, dependencies, epoch)
 /// - `mounted`: Maps atom IDs to subscription info (only for subscribed atoms)
+/// - `dependents`: Reverse dependency index used to propagate invalidation
 /// - `invalidated`: Set of atoms that need recomputation
 /// - `changed`: Set of atoms that changed and need listener notification
 ///
+/// Every field is shared behind an `Arc`, so `handle()` can hand out another
+/// view of the same store (e.g. for the closure returned by `sub`).
+///
 /// **FP Pattern**: Encapsulation of mutable state with pure interface
 pub struct Store {
     /// Map of atom IDs to their current state
     ///
-    /// Uses `Box<dyn Any>` for type erasure since we need to store heterogeneous types.
-    /// Each value is actually an `AtomState<T>` but type-erased.
+    /// Uses `Box<dyn AnyAtomState>` for type erasure since we need to store
+    /// heterogeneous types. Each value is actually an `AtomState<T>`; epochs
+    /// and dependencies are readable without knowing `T`.
+    ///
+    /// **Rust Pattern**: Type erasure with trait objects
+    pub(crate) atom_states: Arc<DashMap<AtomId, SharedAtomState>>,
+
+    /// Type-erased atom configs, registered the first time an atom is computed
     ///
-    /// **Rust Pattern**: Type erasure with Any
+    /// Lets the store recompute a mounted atom knowing only its ID.
+    pub(crate) atom_configs: Arc<DashMap<AtomId, Arc<dyn AnyAtom>>>,
+
+    /// Reverse dependency index: atom ID -> atoms whose last read used it
     ///
-    /// TODO: Phase 1.2 - Initialize this map
-    /// TODO: Phase 1.3 - Read from this map in get()
-    /// TODO: Phase 1.4 - Update this map in set()
-    pub(crate) atom_states: DashMap<AtomId, Arc<RwLock<Box<dyn Any + Send + Sync>>>>,
+    /// Maintained whenever a derived atom is (re)computed, regardless of
+    /// mounting, so `set` can invalidate every transitive dependent.
+    pub(crate) dependents: Arc<DashMap<AtomId, HashSet<AtomId>>>,
 
     /// Map of mounted (subscribed) atoms to their subscription info
     ///
@@ -51,31 +89,144 @@ pub struct Store {
     /// This enables automatic garbage collection of unused atoms.
     ///
     /// **FP Pattern**: Lazy mounting pattern
+    pub(crate) mounted: Arc<DashMap<AtomId, Arc<RwLock<Mounted>>>>,
+
+    /// Per-atom gates held while an atom's read function runs
+    ///
+    /// A thread that finds an atom already being computed waits on its gate
+    /// and then reuses the result, so concurrent reads run the read function
+    /// once. Reentrant, so a read function reading itself on the same thread
+    /// does not deadlock. Entries are dropped once no thread holds them.
+    pub(crate) computing: Arc<DashMap<AtomId, Arc<ReentrantMutex<()>>>>,
+
+    /// Atoms whose read functions are running, per thread, outermost first
     ///
-    /// TODO: Phase 3.1 - Track mounted atoms
-    /// TODO: Phase 3.2 - Add/remove on subscribe/unsubscribe
-    pub(crate) mounted: DashMap<AtomId, Arc<RwLock<Mounted>>>,
+    /// A read that finds its atom already on its thread's stack is a cycle
+    /// and fails with `AtomError::CircularDependency` instead of recursing.
+    pub(crate) read_stacks: Arc<Mutex<HashMap<ThreadId, Vec<AtomId>>>>,
 
     /// Set of atoms that have been invalidated and need recomputation
     ///
-    /// TODO: Phase 2.3 - Mark atoms as invalidated when dependencies change
-    /// TODO: Phase 4.1 - Use in topological sort
+    /// Invalidated atoms keep their last value in `atom_states`; they are
+    /// recomputed lazily on the next read.
     pub(crate) invalidated: Arc<RwLock<HashSet<AtomId>>>,
 
     /// Set of atoms that changed (for listener notification)
-    ///
-    /// TODO: Phase 3.3 - Collect changed atoms during updates
     pub(crate) changed: Arc<RwLock<HashSet<AtomId>>>,
 
-    /// Pending mount callbacks
+    /// Atoms that invalidation skips while a `set_except` is running
+    pub(crate) invalidation_exclusions: Arc<RwLock<HashSet<AtomId>>>,
+
+    /// Pending mount callbacks, run by the next flush after listeners
+    pub(crate) mount_callbacks: CallbackQueue,
+
+    /// Pending unmount cleanups, run by the next flush after listeners
+    pub(crate) unmount_callbacks: CallbackQueue,
+
+    /// Threads currently inside `flush_callbacks`
+    ///
+    /// A listener that writes to the store re-enters flush on the same
+    /// thread; that nested call returns early and the outer loop picks up
+    /// the new changes instead.
+    pub(crate) flushing: Arc<Mutex<HashSet<ThreadId>>>,
+
+    /// Set middleware, in registration order
+    pub(crate) middleware: Arc<RwLock<Vec<Middleware>>>,
+
+    /// Handler for panics in user closures, if one is installed
+    pub(crate) panic_handler: Arc<RwLock<Option<PanicHandler>>>,
+
+    /// Atoms notified by the most recent flush (see `last_flush_order`)
+    #[cfg(debug_assertions)]
+    pub(crate) last_flush_order: Arc<Mutex<Vec<AtomId>>>,
+
+    /// Whether operations flush immediately or wait for `flush()`
+    pub(crate) flush_mode: Arc<RwLock<FlushMode>>,
+
+    /// Nesting depth of the `batch` calls running on each thread
+    ///
+    /// A thread's writes don't flush while it is inside a batch; other
+    /// threads are unaffected.
+    pub(crate) batch_depth: Arc<Mutex<HashMap<ThreadId, usize>>>,
+
+    /// `NoCache` atoms already computed during the current batch or flush
+    ///
+    /// Their cached value is reused until the batch or flush ends (or a
+    /// dependency changes), so a side-effecting read runs once per flush.
+    pub(crate) flush_memo: Arc<Mutex<HashSet<AtomId>>>,
+
+    /// Abort signals of in-flight async evaluations, by atom
+    #[cfg(feature = "async")]
+    pub(crate) aborts: Arc<DashMap<AtomId, AbortSignal>>,
+
+    /// In-flight async evaluations that concurrent `get_async` calls join
+    #[cfg(feature = "async")]
+    pub(crate) in_flight: Arc<DashMap<AtomId, InFlight>>,
+
+    /// Per-type equality checks registered with `register_eq`
     ///
-    /// TODO: Phase 8.1 - Execute after flush
-    pub(crate) mount_callbacks: Arc<Mutex<Vec<Box<dyn FnOnce() + Send>>>>,
+    /// Each value is an `EqualityFn<T>` keyed by `TypeId::of::<T>()`.
+    pub(crate) equalities: Arc<RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+
+    /// Value types registered with `register_snapshot_type`
+    pub(crate) snapshot_codecs: Arc<RwLock<HashMap<TypeId, Arc<SnapshotCodec>>>>,
+
+    /// Shared copies of `Arc<str>` values, `None` while interning is disabled
+    pub(crate) interner: Arc<RwLock<Option<HashSet<Arc<str>>>>>,
+
+    /// Accumulated read-function timings per atom
+    #[cfg(feature = "profiling")]
+    pub(crate) timings: Arc<Mutex<HashMap<AtomId, RecomputeTiming>>>,
+
+    /// Lock-free value cells of hot atoms, each a `HotCell<T>`
+    #[cfg(feature = "hot")]
+    pub(crate) hot_cells: Arc<DashMap<AtomId, Arc<dyn Any + Send + Sync>>>,
+
+    /// Worker threads running `sub_offloaded` listeners, started on first use
+    pub(crate) offload_pool: Arc<OnceLock<OffloadPool>>,
+
+    /// Most read-only derived values kept cached (`usize::MAX`: no limit)
+    pub(crate) derived_cache_limit: Arc<AtomicUsize>,
+
+    /// Read order of cached read-only derived atoms, for eviction
+    pub(crate) derived_lru: Arc<Mutex<DerivedLru>>,
 
-    /// Pending unmount callbacks
+    /// Atoms frozen by `materialize`: never evicted, dropped or recomputed
+    pub(crate) materialized: Arc<RwLock<HashSet<AtomId>>>,
+
+    /// Undo entries of the transactions running on each thread, innermost last
     ///
-    /// TODO: Phase 8.1 - Execute after flush
-    pub(crate) unmount_callbacks: Arc<Mutex<Vec<Box<dyn FnOnce() + Send>>>>,
+    /// Each frame holds one entry per atom written during that transaction,
+    /// restoring the value the atom had before its first write.
+    pub(crate) transactions: Arc<Mutex<HashMap<ThreadId, Vec<UndoFrame>>>>,
+
+    /// Recent atom changes, `None` while history is disabled
+    pub(crate) change_log: Arc<Mutex<Option<ChangeLog>>>,
+}
+
+/// A hot atom's latest value
+#[cfg(feature = "hot")]
+type HotCell<T> = arc_swap::ArcSwap<T>;
+
+/// When the store flushes (recomputes mounted atoms, notifies listeners and
+/// runs mount/unmount callbacks) after `set`, `sub` and unsubscribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlushMode {
+    /// Flush synchronously inside each operation (Jotai's behavior)
+    #[default]
+    Sync,
+
+    /// Defer all flushing until `Store::flush` is called
+    Manual,
+}
+
+/// Accumulated recompute timings of one atom
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RecomputeTiming {
+    count: usize,
+    total: Duration,
+    max: Duration,
 }
 
 impl Store {
@@ -91,16 +242,89 @@ impl Store {
     ///   return { get: storeGet, set: storeSet, sub: storeSub }
     /// }
     /// ```
-    ///
-    /// TODO: Phase 1.2 - Initialize all data structures
     pub fn new() -> Self {
         Store {
-            atom_states: DashMap::new(),
-            mounted: DashMap::new(),
+            atom_states: Arc::new(DashMap::new()),
+            atom_configs: Arc::new(DashMap::new()),
+            dependents: Arc::new(DashMap::new()),
+            mounted: Arc::new(DashMap::new()),
+            computing: Arc::new(DashMap::new()),
+            read_stacks: Arc::new(Mutex::new(HashMap::new())),
             invalidated: Arc::new(RwLock::new(HashSet::new())),
             changed: Arc::new(RwLock::new(HashSet::new())),
+            invalidation_exclusions: Arc::new(RwLock::new(HashSet::new())),
             mount_callbacks: Arc::new(Mutex::new(Vec::new())),
             unmount_callbacks: Arc::new(Mutex::new(Vec::new())),
+            flushing: Arc::new(Mutex::new(HashSet::new())),
+            middleware: Arc::new(RwLock::new(Vec::new())),
+            panic_handler: Arc::new(RwLock::new(None)),
+            #[cfg(debug_assertions)]
+            last_flush_order: Arc::new(Mutex::new(Vec::new())),
+            flush_mode: Arc::new(RwLock::new(FlushMode::Sync)),
+            batch_depth: Arc::new(Mutex::new(HashMap::new())),
+            flush_memo: Arc::new(Mutex::new(HashSet::new())),
+            #[cfg(feature = "async")]
+            aborts: Arc::new(DashMap::new()),
+            #[cfg(feature = "async")]
+            in_flight: Arc::new(DashMap::new()),
+            equalities: Arc::new(RwLock::new(HashMap::new())),
+            snapshot_codecs: Arc::new(RwLock::new(HashMap::new())),
+            interner: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "profiling")]
+            timings: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "hot")]
+            hot_cells: Arc::new(DashMap::new()),
+            offload_pool: Arc::new(OnceLock::new()),
+            derived_cache_limit: Arc::new(AtomicUsize::new(usize::MAX)),
+            derived_lru: Arc::new(Mutex::new(DerivedLru::default())),
+            materialized: Arc::new(RwLock::new(HashSet::new())),
+            transactions: Arc::new(Mutex::new(HashMap::new())),
+            change_log: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Another handle to this same store
+    ///
+    /// All fields are shared, so writes through the handle are visible here.
+    /// Used by closures that outlive the borrow of `self` (unsubscribe).
+    pub(crate) fn handle(&self) -> Store {
+        Store {
+            atom_states: self.atom_states.clone(),
+            atom_configs: self.atom_configs.clone(),
+            dependents: self.dependents.clone(),
+            mounted: self.mounted.clone(),
+            computing: self.computing.clone(),
+            read_stacks: self.read_stacks.clone(),
+            invalidated: self.invalidated.clone(),
+            changed: self.changed.clone(),
+            invalidation_exclusions: self.invalidation_exclusions.clone(),
+            mount_callbacks: self.mount_callbacks.clone(),
+            unmount_callbacks: self.unmount_callbacks.clone(),
+            flushing: self.flushing.clone(),
+            middleware: self.middleware.clone(),
+            panic_handler: self.panic_handler.clone(),
+            #[cfg(debug_assertions)]
+            last_flush_order: self.last_flush_order.clone(),
+            flush_mode: self.flush_mode.clone(),
+            batch_depth: self.batch_depth.clone(),
+            flush_memo: self.flush_memo.clone(),
+            #[cfg(feature = "async")]
+            aborts: self.aborts.clone(),
+            #[cfg(feature = "async")]
+            in_flight: self.in_flight.clone(),
+            equalities: self.equalities.clone(),
+            snapshot_codecs: self.snapshot_codecs.clone(),
+            interner: self.interner.clone(),
+            #[cfg(feature = "profiling")]
+            timings: self.timings.clone(),
+            #[cfg(feature = "hot")]
+            hot_cells: self.hot_cells.clone(),
+            offload_pool: self.offload_pool.clone(),
+            derived_cache_limit: self.derived_cache_limit.clone(),
+            derived_lru: self.derived_lru.clone(),
+            materialized: self.materialized.clone(),
+            transactions: self.transactions.clone(),
+            change_log: self.change_log.clone(),
         }
     }
 
@@ -124,39 +348,349 @@ impl Store {
     ///
     /// **FP Pattern**: Lazy evaluation, memoization
     ///
-    /// TODO: Phase 1.3 - Basic implementation for primitive atoms
-    /// TODO: Phase 2.1 - Add dependency tracking
-    /// TODO: Phase 2.4 - Add epoch-based cache checking
     /// TODO: Phase 6.1 - Handle promises/async
-    pub fn get<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T> {
-        // TODO: Phase 1.3 - Implement basic get
-        // Steps:
-        // 1. Check if atom_states has this atom
-        // 2. If not, initialize with default/uncomputed state
-        // 3. Check if value is cached
-        // 4. If not, call atom.read() with a Getter implementation
-        // 5. Store the result in atom_states
-        // 6. Return the value
-        if let Some(state_arc) = self.atom_states.get(&atom.id) {
+    pub fn get<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>) -> Result<T> {
+        self.read_atom_state(atom.as_atom())
+    }
+
+    /// Read an atom's cached value, even if its dependencies have changed
+    ///
+    /// Skips the freshness check: an invalidated atom returns the value it
+    /// computed last instead of recomputing. Only an atom with no cached
+    /// value yet is computed. Stale atoms are brought up to date by the next
+    /// `get` or, when mounted, by the store's `recompute_invalidated` pass.
+    ///
+    /// **FP Pattern**: Eventual consistency - trade freshness for cost
+    pub fn get_stale_ok<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+    ) -> Result<T> {
+        let atom = atom.as_atom();
+        let cached = self.state_of(atom.id).and_then(|state_arc| {
             let lock = state_arc.read();
-            if let Some(atom_state) = lock.downcast_ref::<AtomState<T>>() {
-                if let Some(ref result) = atom_state.value {
-                    return result.clone();
-                }
+            lock.downcast_ref::<AtomState<T>>()?.value.clone()
+        });
+
+        match cached {
+            Some(result) => result,
+            None => self.get(atom),
+        }
+    }
+
+    /// Read an atom, falling back to `default` on any error
+    ///
+    /// Never fails: a read error, a cached error or an uninitialized atom all
+    /// yield `default`. Use `get` when the cause of a failure matters.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let page_size = store.get_or(&configured_page_size, 20);
+    /// ```
+    pub fn get_or<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>, default: T) -> T {
+        self.get(atom).unwrap_or(default)
+    }
+
+    /// Compute an atom as if some atoms held other values, without writing them
+    ///
+    /// Each override stands in for its atom's value during this call only.
+    /// The store is only read, never written: an atom whose cached value is
+    /// fresh and was not computed (transitively) from an overridden atom is
+    /// taken from the cache; every other atom is evaluated with the
+    /// overrides visible, and the result is returned, not cached.
+    ///
+    /// "Computed from" follows the dependencies recorded by each atom's last
+    /// real computation, so read functions must be deterministic in the
+    /// values they read (as all derived atoms should be). Atoms evaluated
+    /// here are not memoized within the call, so an atom read along several
+    /// paths runs its read function once per path.
+    ///
+    /// **FP Pattern**: Local environment override (like `Reader.local`)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let if_doubled = store.get_with_overrides(
+    ///     &total_cost,
+    ///     &[ErasedOverride::new(quantity.as_atom(), 2 * store.get(quantity.as_atom())?)],
+    /// )?;
+    /// ```
+    pub fn get_with_overrides<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+        overrides: &[ErasedOverride],
+    ) -> Result<T> {
+        let atom = atom.as_atom();
+        let overrides: HashMap<AtomId, &ErasedOverride> = overrides
+            .iter()
+            .map(|entry| (entry.atom_id(), entry))
+            .collect();
+        let affected = Mutex::new(HashMap::new());
+
+        let value = self.read_overridden(atom, &overrides, &affected)?;
+        value
+            .downcast::<T>()
+            .map(|value| *value)
+            .map_err(|_| AtomError::type_mismatch::<T>(atom.id, "unknown"))
+    }
+
+    /// Read one atom for `get_with_overrides`
+    pub(crate) fn read_overridden(
+        &self,
+        atom: &dyn AnyAtom,
+        overrides: &HashMap<AtomId, &ErasedOverride>,
+        affected: &Mutex<HashMap<AtomId, bool>>,
+    ) -> Result<Box<dyn Any + Send + Sync>> {
+        if let Some(entry) = overrides.get(&atom.id()) {
+            return Ok(entry.value_boxed());
+        }
+        if let Some(value) = self.peek_fresh(atom) {
+            if !self.depends_on_override(atom.id(), overrides, affected) {
+                return Ok(value);
             }
         }
 
-        let v = atom.read()?;
-        self.atom_states.insert(
-            atom.id,
-            Arc::new(RwLock::new(Box::new(AtomState {
-                epoch: 1,
-                value: Some(Ok(v.clone())),
-                dependencies: HashMap::new(),
-                pending_promises: HashSet::new(),
-            }))),
-        );
-        Ok(v)
+        let _frame = self.enter_read(atom.id())?;
+        atom.read_with(&OverrideGetter {
+            store: self,
+            reading_atom: atom.id(),
+            overrides,
+            affected,
+        })
+    }
+
+    /// An atom's cached value, if it is fresh, without computing anything
+    fn peek_fresh(&self, atom: &dyn AnyAtom) -> Option<Box<dyn Any + Send + Sync>> {
+        if atom.cache_policy() == CachePolicy::NoCache
+            || self.invalidated.read().contains(&atom.id())
+        {
+            return None;
+        }
+        self.state_of(atom.id())?.read().value_erased()
+    }
+
+    /// Whether an atom's recorded dependencies reach an overridden atom
+    fn depends_on_override(
+        &self,
+        atom_id: AtomId,
+        overrides: &HashMap<AtomId, &ErasedOverride>,
+        affected: &Mutex<HashMap<AtomId, bool>>,
+    ) -> bool {
+        if overrides.contains_key(&atom_id) {
+            return true;
+        }
+        if let Some(&known) = affected.lock().get(&atom_id) {
+            return known;
+        }
+        // Provisional answer, so a cycle cannot recurse forever
+        affected.lock().insert(atom_id, false);
+
+        let dependencies: Vec<AtomId> = self
+            .state_of(atom_id)
+            .map(|state| state.read().dependencies().keys().copied().collect())
+            .unwrap_or_default();
+        let result = dependencies
+            .into_iter()
+            .any(|dependency| self.depends_on_override(dependency, overrides, affected));
+        affected.lock().insert(atom_id, result);
+        result
+    }
+
+    /// Read a function atom and invoke its current function with `arg`
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let doubler = atom_fn(Arc::new(|x: i32| x * 2));
+    /// assert_eq!(store.call(doubler.as_atom(), 21)?, 42);
+    /// ```
+    pub fn call<X: 'static, Y: 'static>(
+        &self,
+        atom: &impl AsAtom<FnValue<X, Y>>,
+        arg: X,
+    ) -> Result<Y> {
+        let f = self.get(atom)?;
+        Ok(f(arg))
+    }
+
+    /// Re-read an env atom's variable, returning whether its value changed
+    ///
+    /// When the newly parsed value (or the default) differs from the atom's
+    /// current value in this store, it is written like a `set`, so
+    /// dependents are invalidated and listeners notified.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// std::env::set_var("LOG_LEVEL", "debug");
+    /// store.reload_env(&log_level)?;
+    /// ```
+    pub fn reload_env<T>(&self, atom: &EnvAtom<T>) -> Result<bool>
+    where
+        T: FromStr + Clone + PartialEq + Send + Sync + 'static,
+    {
+        atom.reload(self)
+    }
+
+    /// Force a refreshable atom to recompute
+    ///
+    /// Reference: `jotai/src/vanilla/utils/atomWithRefresh.ts` (writing with no arguments)
+    ///
+    /// Invalidates the atom as if a dependency had changed: a mounted atom
+    /// recomputes and notifies its listeners right away, an unmounted one
+    /// recomputes on its next read.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.refresh(&user_profile)?; // refetch
+    /// ```
+    pub fn refresh<T: Clone + Send + Sync + 'static>(&self, atom: &RefreshAtom<T>) -> Result<()> {
+        atom.refresh(self)
+    }
+
+    /// Iterate over a history atom's values recorded after `since_epoch`
+    ///
+    /// Yields `(epoch, value)` pairs, oldest first. `since_epoch` is matched
+    /// exactly against the recorded epochs: when it is the epoch of a
+    /// recorded entry, iteration starts after that entry; otherwise (e.g. 0,
+    /// or an entry already evicted) every recorded entry is yielded.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut seen = 0;
+    /// for (epoch, value) in store.history_iter(&price, seen) {
+    ///     replay(value);
+    ///     seen = epoch;
+    /// }
+    /// ```
+    pub fn history_iter<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &HistoryAtom<T>,
+        since_epoch: EpochNumber,
+    ) -> impl Iterator<Item = (EpochNumber, T)> {
+        atom.iter(self, since_epoch)
+    }
+
+    /// Read an atom, separating the atom's own error from store errors
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (`atomState.v` vs `atomState.e`)
+    ///
+    /// The outer `Result` reports problems with the store itself (the atom
+    /// has no usable state, or its state holds a different type), while the
+    /// inner `Result` is whatever the atom computed - including a cached error
+    /// from its read function.
+    ///
+    /// **FP Pattern**: Nested Result (Either of Either) for error categories
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// match store.get_result(&parsed)? {
+    ///     Ok(value) => println!("value: {}", value),
+    ///     Err(e) => println!("atom failed to compute: {}", e),
+    /// }
+    /// ```
+    pub fn get_result<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+    ) -> Result<Result<T>> {
+        let atom = atom.as_atom();
+        if !self.atom_states.contains_key(&atom.id) || self.invalidated.read().contains(&atom.id) {
+            // Bring the atom up to date; a failed read is cached and reported below
+            let _ = self.get(atom);
+        }
+
+        let state_arc = self
+            .atom_states
+            .get(&atom.id)
+            .map(|entry| entry.value().clone())
+            .ok_or(AtomError::Uninitialized { atom_id: atom.id })?;
+        let lock = state_arc.read();
+        let state = lock
+            .downcast_ref::<AtomState<T>>()
+            .ok_or_else(|| AtomError::type_mismatch::<T>(atom.id, "unknown"))?;
+
+        state
+            .value
+            .clone()
+            .ok_or(AtomError::Uninitialized { atom_id: atom.id })
+    }
+
+    /// Visit each item of a collection atom without cloning the collection
+    ///
+    /// The atom is brought up to date first (recomputing it if needed), then
+    /// `f` is called for every item while the atom's state is read-locked.
+    /// `f` must not write to the store, since the lock is held throughout.
+    ///
+    /// **FP Pattern**: Internal iteration over borrowed data
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut total = 0;
+    /// store.for_each(todos.as_atom(), |todo| total += todo.estimate)?;
+    /// ```
+    pub fn for_each<I, F>(&self, atom: &impl AsAtom<Vec<I>>, mut f: F) -> Result<()>
+    where
+        I: Clone + Send + Sync + 'static,
+        F: FnMut(&I),
+    {
+        self.with_value(atom.as_atom(), |items| items.iter().for_each(&mut f))
+    }
+
+    /// Read a fixed-size array atom by reference instead of cloning it
+    ///
+    /// Arrays of any length are valid atom values when their items are
+    /// `Clone + Send + Sync`, but `get` copies the whole array. This brings
+    /// the atom up to date and hands `f` a reference to the cached array,
+    /// returning whatever `f` computes. As with `for_each`, `f` runs under
+    /// the atom's read lock and must not write to the store.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let samples = atom([0i32; 256]);
+    /// let peak = store.borrow_array(samples.as_atom(), |s| s.iter().copied().max())?;
+    /// ```
+    pub fn borrow_array<T, const N: usize, R>(
+        &self,
+        atom: &impl AsAtom<[T; N]>,
+        f: impl FnOnce(&[T; N]) -> R,
+    ) -> Result<R>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.with_value(atom.as_atom(), f)
+    }
+
+    /// Run `f` on an atom's cached value while holding its read lock
+    ///
+    /// Computes the atom first if it has no fresh value; the result is then
+    /// borrowed from the store rather than cloned out of it.
+    fn with_value<T, R>(&self, atom: &Atom<T>, f: impl FnOnce(&T) -> R) -> Result<R>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if self.skips_cache(atom) || !self.has_fresh_value(atom.id) {
+            // Computing produces the value once; the cached copy is borrowed below
+            self.read_atom_state(atom)?;
+        }
+
+        let state_arc = self
+            .state_of(atom.id)
+            .ok_or(AtomError::Uninitialized { atom_id: atom.id })?;
+        let lock = state_arc.read();
+        let state = lock
+            .downcast_ref::<AtomState<T>>()
+            .ok_or_else(|| AtomError::type_mismatch::<T>(atom.id, "unknown"))?;
+
+        match &state.value {
+            Some(Ok(value)) => Ok(f(value)),
+            Some(Err(error)) => Err(error.clone()),
+            None => Err(AtomError::Uninitialized { atom_id: atom.id }),
+        }
     }
 
     /// Update an atom's value
@@ -181,374 +715,6231 @@ impl Store {
     /// 6. Notifies listeners of changed atoms
     ///
     /// **FP Pattern**: State transformation, cascading updates
-    ///
-    /// TODO: Phase 1.4 - Basic implementation for primitive atoms
-    /// TODO: Phase 2.3 - Add invalidation of dependents
-    /// TODO: Phase 4.2 - Add recomputation loop
-    /// TODO: Phase 3.3 - Add listener notification
     pub fn set<T: Clone + Send + Sync + 'static>(
         &self,
         atom: &WritableAtom<T>,
         value: T,
     ) -> Result<()> {
-        // Phase 1.4 - Basic set implementation for primitive atoms
-        // For primitive atoms, we directly update the state without calling write_fn
-        // (write_fn is for derived/writable atoms in later phases)
-
-        // 1. Initialize state if it doesn't exist
-        if !self.atom_states.contains_key(&atom.id()) {
-            let initial_state: AtomState<T> = AtomState {
-                epoch: 0,
-                value: None,
-                dependencies: HashMap::new(),
-                pending_promises: HashSet::new(),
-            };
-            self.atom_states
-                .insert(atom.id(), Arc::new(RwLock::new(Box::new(initial_state))));
-        }
-
-        // 2. Update the value and increment epoch
-        if let Some(state_arc) = self.atom_states.get(&atom.id()) {
-            let mut lock = state_arc.write();
-            if let Some(state) = lock.downcast_mut::<AtomState<T>>() {
-                state.value = Some(Ok(value));
-                state.epoch += 1;
-            }
-        }
-
-        // 3. Mark atom as changed (for listener notification in Phase 3)
-        self.changed.write().insert(atom.id());
+        // Primitive atoms store the value; others run their write function
+        let result = self.write_atom_state(atom, value);
 
-        // TODO: Phase 2.3 - Invalidate dependents
-        // TODO: Phase 3.3 - Flush callbacks
+        // Recompute mounted dependents and notify listeners
+        self.flush_if_sync();
 
-        Ok(())
+        result
     }
 
-    /// Subscribe to atom changes
+    /// Set an atom with an updater function of its current value
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (storeSub function ~line 1000)
+    /// Reference: `jotai/src/vanilla/atom.ts:65` (`SetStateAction` updater form)
     ///
-    /// ```typescript
-    /// const storeSub = (atom: AnyAtom, listener: () => void) => {
-    ///   mountAtom(atom, listener)
-    ///   flushCallbacks()
-    ///   const unsubscribe = () => {
-    ///     unmountAtom(atom, listener)
-    ///     flushCallbacks()
-    ///   }
-    ///   return unsubscribe
-    /// }
-    /// ```
+    /// Reads the current value, applies `updater` and writes the result as a
+    /// single write, so the epoch is incremented once. Returns
+    /// `AtomError::Uninitialized` (or whatever error the read produced) when
+    /// the atom has no value to update.
     ///
-    /// This function:
-    /// 1. Mounts the atom (creates Mounted entry)
-    /// 2. Recursively mounts dependencies
-    /// 3. Adds the listener to the Mounted entry
-    /// 4. Calls atom's onMount callback if present
-    /// 5. Returns an unsubscribe function
+    /// **FP Pattern**: Higher-order function (`prev => next`)
     ///
-    /// **FP Pattern**: Higher-order function returns cleanup function
+    /// # Example
     ///
-    /// TODO: Phase 3.2 - Implement subscription system
-    /// TODO: Phase 3.4 - Implement recursive mounting
-    /// TODO: Phase 8.1 - Call onMount lifecycle
-    pub fn sub<F>(
-        &self,
-        atom: &Atom<impl Clone + Send + Sync + 'static>,
-        listener: F,
-    ) -> Unsubscribe
+    /// ```rust,ignore
+    /// let count = atom(0);
+    /// store.set_with(&count, |prev| prev + 1)?;
+    /// assert_eq!(store.get(count.as_atom())?, 1);
+    /// ```
+    pub fn set_with<T, F>(&self, atom: &WritableAtom<T>, updater: F) -> Result<()>
     where
-        F: Fn() + Send + Sync + 'static,
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(T) -> T,
     {
-        // TODO: Phase 3.2 - Implement subscription
-        // Steps:
-        // 1. Mount the atom
-        // 2. Add listener to mounted entry
-        // 3. Flush any pending callbacks
-        // 4. Return unsubscribe function that:
-        //    - Removes listener
-        //    - Unmounts if no more listeners
-        //    - Calls cleanup if present
-
-        todo!("Store::sub - Phase 3.2")
+        let current = self.get(atom.as_atom())?;
+        self.set(atom, updater(current))
     }
 
-    /// Ensure an atom has state initialized
+    /// Set an atom unless the value equals its current one
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (ensureAtomState function)
+    /// Reference: `Object.is` check in `jotai/src/vanilla/internals.ts` (setAtomStateValueOrPromise)
     ///
-    /// TODO: Phase 1.3 - Implement state initialization
-    pub(crate) fn ensure_atom_state<T: Clone + Send + Sync + 'static>(
-        &self,
-        atom: &Atom<T>,
-    ) -> Result<()> {
-        // TODO: Create AtomState if it doesn't exist
-        // Call unstable_onInit if present
-        let atom_state = AtomState {
-            epoch: 1,
-            value: Some(atom.read()),
-            dependencies: HashMap::new(),
-            pending_promises: HashSet::new(),
-        };
-
-        Ok(())
+    /// Compares `value` with the atom's current value using `PartialEq`.
+    /// When they are equal nothing happens: no epoch bump, no invalidation,
+    /// no notification. Otherwise (including when the atom has no value
+    /// yet, or its read fails) it is written like `set`. Returns whether a
+    /// write happened.
+    ///
+    /// `set` itself compares nothing, so value types need not be `PartialEq`;
+    /// see also `register_eq` for making every write of a type compare.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Re-sending the same query doesn't refetch
+    /// store.set_if_changed(&query, input.text())?;
+    /// ```
+    pub fn set_if_changed<T>(&self, atom: &WritableAtom<T>, value: T) -> Result<bool>
+    where
+        T: Clone + PartialEq + Send + Sync + 'static,
+    {
+        if self
+            .get(atom.as_atom())
+            .is_ok_and(|current| current == value)
+        {
+            return Ok(false);
+        }
+        self.set(atom, value).map(|()| true)
     }
 
-    /// Read atom state, computing if necessary
+    /// Mutate an atom's value in place
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (readAtomState function)
+    /// Read-modify-write without cloning the value out and back in: the
+    /// atom's state is locked once, `f` mutates the cached value, the epoch
+    /// is incremented once, dependents are invalidated and listeners are
+    /// notified as after `set`. Every call counts as a change; registered
+    /// equality checks are not consulted.
     ///
-    /// This is the core function that:
-    /// - Checks cache validity
-    /// - Calls read function if needed
-    /// - Tracks dependencies
+    /// Falls back to reading, mutating a copy and `set` when the in-place
+    /// path does not apply: the atom has a write function, middleware or
+    /// interning is active, or the atom has no fresh value yet (or holds an
+    /// error, which the read then returns).
     ///
-    /// TODO: Phase 1.3 - Implement
-    pub(crate) fn read_atom_state<T: Clone + Send + Sync + 'static>(
-        &self,
-        atom: &Atom<T>,
-    ) -> Result<T> {
-        self.get(atom)
+    /// **FP Pattern**: Controlled mutation behind a functional interface
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let hits = atom(Vec::new());
+    /// store.update(&hits, |hits| hits.push(request_id))?;
+    /// ```
+    pub fn update<T, F>(&self, atom: &WritableAtom<T>, f: F) -> Result<()>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(&mut T),
+    {
+        let in_place = !atom.has_write_fn()
+            && self.middleware.read().is_empty()
+            && self.interner.read().is_none()
+            && self.has_fresh_value(atom.id())
+            && self.state_of(atom.id()).is_some_and(|state| {
+                state
+                    .read()
+                    .downcast_ref::<AtomState<T>>()
+                    .is_some_and(|state| matches!(state.value, Some(Ok(_))))
+            });
+        if !in_place {
+            let mut value = self.get(atom.as_atom())?;
+            f(&mut value);
+            return self.set(atom, value);
+        }
+
+        let atom_id = atom.id();
+        self.record_rollback::<T>(atom_id);
+        let mutated = self
+            .state_of(atom_id)
+            .and_then(|state| {
+                state
+                    .write()
+                    .downcast_mut::<AtomState<T>>()
+                    .map(|state| state.mutate_value(f))
+            })
+            .unwrap_or(false);
+        if !mutated {
+            return Err(AtomError::Uninitialized { atom_id });
+        }
+        self.record_change(atom_id);
+        #[cfg(feature = "hot")]
+        self.publish_hot::<T>(atom_id);
+
+        self.changed.write().insert(atom_id);
+        self.invalidate_dependents(atom_id);
+        self.flush_if_sync();
+        Ok(())
     }
 
-    /// Write atom state
+    /// Set an atom without invalidating the listed atoms
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (writeAtomState function)
+    /// Like `set`, but invalidation stops at every atom in `except`: they
+    /// keep their cached values, are not recomputed and their listeners are
+    /// not notified during this set, and neither are atoms reachable only
+    /// through them. Atoms that also depend on the change through some other
+    /// path are invalidated as usual. Useful for breaking feedback loops,
+    /// e.g. when an excluded atom's own effect caused the write.
     ///
-    /// TODO: Phase 1.4 - Implement
-    pub(crate) fn write_atom_state<T: Clone + Send + Sync + 'static>(
+    /// The exclusion covers the write (including writes made by the atom's
+    /// write function) and the flush that follows. The excluded atoms are
+    /// not marked stale afterwards: they recompute once a later change
+    /// invalidates them.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // The editor produced this text; don't echo it back into the editor
+    /// store.set_except(&text, edited, &[editor_view.id()])?;
+    /// ```
+    pub fn set_except<T: Clone + Send + Sync + 'static>(
         &self,
         atom: &WritableAtom<T>,
         value: T,
+        except: &[AtomId],
     ) -> Result<()> {
-        atom.write(value.clone())?;
-        // TODO: Call atom.write() with getter/setter
-        // TODO: Update state
-        // TODO: Increment epoch
-        if let Some(state_arc) = self.atom_states.get(&atom.id()) {
-            let mut lock = state_arc.write();
-            if let Some(state) = lock.downcast_mut::<AtomState<T>>() {
-                state.epoch += 1;
-                let mut r = self.changed.write();
-                r.insert(atom.id());
-                state.value = Some(Ok(value));
-                // self.invalidate_dependents(atom.id());
-                // self.flush_callbacks();
-            }
+        let added: Vec<AtomId> = {
+            let mut exclusions = self.invalidation_exclusions.write();
+            except
+                .iter()
+                .copied()
+                .filter(|atom_id| exclusions.insert(*atom_id))
+                .collect()
+        };
+
+        let result = self.write_atom_state(atom, value);
+        self.flush_if_sync();
+
+        // Atoms already excluded by an enclosing set_except stay excluded
+        let mut exclusions = self.invalidation_exclusions.write();
+        for atom_id in added {
+            exclusions.remove(&atom_id);
         }
 
-        Ok(())
+        result
     }
 
-    /// Invalidate all atoms that depend on the given atom
+    /// Set an atom whose write returns a value, returning that value
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (invalidateDependents function)
+    /// Runs the atom's write function with `value`, flushes like `set`,
+    /// then returns what the write function produced.
     ///
-    /// Uses breadth-first search to mark all transitive dependents as invalidated.
+    /// # Example
     ///
-    /// TODO: Phase 2.3 - Implement
-    pub(crate) fn invalidate_dependents(&self, atom_id: AtomId) {
-        // TODO: BFS through dependents
-        // TODO: Mark all as invalidated
-        todo!("invalidate_dependents - Phase 2.3")
+    /// ```rust,ignore
+    /// let id = store.set_returning(&create_record, Record::new("draft"))?;
+    /// ```
+    pub fn set_returning<T, R>(&self, atom: &ReturningAtom<T, R>, value: T) -> Result<R>
+    where
+        T: Clone + Send + Sync + 'static,
+        R: 'static,
+    {
+        let result = self
+            .catch_panic(atom.id(), PanicSite::Write, || {
+                atom.write(&StoreWriter::new(self, atom.id()), value)
+            })
+            .unwrap_or_else(|info| Err(AtomError::write_error(info.atom_id, info.message)));
+        self.flush_if_sync();
+        result
     }
 
-    /// Recompute all invalidated atoms in topological order
+    /// Dispatch an action to a command atom
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (recomputeInvalidatedAtoms function)
+    /// Reference: `jotai/src/vanilla/internals.ts` (storeSet with write args)
     ///
-    /// Uses DFS-based topological sort to determine recomputation order.
+    /// Runs the atom's write function with `action`, then flushes like `set`.
     ///
-    /// TODO: Phase 4.1 - Implement topological sort
-    /// TODO: Phase 4.2 - Implement recomputation loop
-    pub(crate) fn recompute_invalidated(&self) -> Result<()> {
-        // TODO: Topological sort of invalidated atoms
-        // TODO: Recompute in dependency order
-        // TODO: Track which actually changed
-        todo!("recompute_invalidated - Phase 4")
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.dispatch(&todos, TodoAction::Remove(0))?;
+    /// ```
+    pub fn dispatch<V, A>(&self, atom: &CommandAtom<V, A>, action: A) -> Result<()>
+    where
+        V: Clone + Send + Sync + 'static,
+        A: 'static,
+    {
+        let result = self
+            .catch_panic(atom.id(), PanicSite::Write, || {
+                atom.dispatch(&StoreWriter::new(self, atom.id()), action)
+            })
+            .unwrap_or_else(|info| Err(AtomError::write_error(info.atom_id, info.message)));
+        self.flush_if_sync();
+        result
     }
 
-    /// Flush pending callbacks (mount, unmount, listeners)
+    /// Set an atom optimistically and await its asynchronous write effect
     ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (flushCallbacks function)
+    /// The new value is written (and listeners notified) right away, then
+    /// the atom's `with_async_write` effect is awaited. If the effect fails,
+    /// the previous value is restored and the error returned. The rollback is
+    /// skipped when the atom was written again in the meantime, so a newer
+    /// value is never clobbered by an older failure.
     ///
-    /// Loops until no more changes occur.
+    /// Atoms without an async write effect behave like `set`.
     ///
-    /// TODO: Phase 3.3 - Implement callback flushing
-    pub(crate) fn flush_callbacks(&self) {
-        // TODO: Loop until stable
-        // TODO: Call all listeners for changed atoms
-        // TODO: Execute mount/unmount callbacks
-        todo!("flush_callbacks - Phase 3.3")
-    }
+    /// **FP Pattern**: Optimistic update with compensating action
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Err(e) = store.set_async(&title, "Draft".to_string()).await {
+    ///     eprintln!("save failed, title restored: {}", e);
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn set_async<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &WritableAtom<T>,
+        value: T,
+    ) -> Result<()> {
+        let previous = self.get(atom.as_atom()).ok();
+        self.set(atom, value.clone())?;
+
+        let Some(write) = atom.async_write.clone() else {
+            return Ok(());
+        };
+        let optimistic_epoch = self.epoch_of(atom.id());
+
+        match write(value).await {
+            Ok(()) => Ok(()),
+            Err(error) => {
+                if let Some(previous) = previous {
+                    if self.epoch_of(atom.id()) == optimistic_epoch {
+                        self.set(atom, previous)?;
+                    }
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Install a handler for panics in user closures
+    ///
+    /// Without a handler, a panicking read function, write function or
+    /// listener unwinds through the store to the caller. With one, the store
+    /// catches the panic, passes a `PanicInfo` to `handler` and carries on:
+    ///
+    /// - a panicking read function caches an `AtomError::ReadError` for its
+    ///   atom, like a read function returning an error
+    /// - a panicking write function makes the `set` (or `dispatch`) return
+    ///   an `AtomError::WriteError`
+    /// - a panicking listener is skipped; the other listeners still run
+    ///
+    /// The process's panic hook still runs (by default printing the panic
+    /// to stderr). Replaces any previous handler.
+    ///
+    /// **FP Pattern**: Error boundary
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.set_panic_handler(|info| {
+    ///     log::error!("atom {} panicked in {:?}: {}", info.atom_id, info.site, info.message);
+    /// });
+    /// ```
+    pub fn set_panic_handler<F>(&self, handler: F)
+    where
+        F: Fn(PanicInfo) + Send + Sync + 'static,
+    {
+        *self.panic_handler.write() = Some(Arc::new(handler));
+    }
+
+    /// Run a user closure, catching a panic if a panic handler is installed
+    ///
+    /// On a caught panic the handler is called and the `PanicInfo` returned.
+    /// Without a handler the closure runs unguarded.
+    fn catch_panic<R>(
+        &self,
+        atom_id: AtomId,
+        site: PanicSite,
+        f: impl FnOnce() -> R,
+    ) -> std::result::Result<R, PanicInfo> {
+        let Some(handler) = self.panic_handler.read().clone() else {
+            return Ok(f());
+        };
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "panic with a non-string payload".to_string());
+            let info = PanicInfo {
+                atom_id,
+                site,
+                message,
+            };
+            handler(info.clone());
+            info
+        })
+    }
+
+    /// Register a middleware that wraps every `set`
+    ///
+    /// Middleware run in registration order. Each receives a `SetContext`
+    /// and a `next` continuation: it may inspect or modify the value, call
+    /// `next(ctx)` to continue, or return early (e.g. with an error) to
+    /// reject the write.
+    ///
+    /// **FP Pattern**: Function composition of continuations
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.use_middleware(|ctx, next| {
+    ///     println!("set {:?}", ctx.label);
+    ///     next(ctx)
+    /// });
+    /// ```
+    pub fn use_middleware<F>(&self, middleware: F)
+    where
+        F: Fn(SetContext, Next<'_>) -> Result<()> + Send + Sync + 'static,
+    {
+        self.middleware.write().push(Arc::new(middleware));
+    }
+
+    /// Run the middleware chain for a set, ending with the actual write
+    pub(crate) fn apply_set<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &Atom<T>,
+        value: T,
+    ) -> Result<()> {
+        self.register_config(atom);
+        let middleware = self.middleware.read().clone();
+        if middleware.is_empty() {
+            self.write_value(atom.id, value);
+            return Ok(());
+        }
+
+        let atom_id = atom.id;
+        let write = move |ctx: SetContext| {
+            let value = ctx
+                .value
+                .downcast::<T>()
+                .map_err(|_| AtomError::type_mismatch::<T>(atom_id, "unknown"))?;
+            self.write_value(atom_id, *value);
+            Ok(())
+        };
+        let ctx = SetContext {
+            atom_id,
+            label: atom.debug_label.clone(),
+            value: Box::new(value),
+        };
+        run_middleware(&middleware, ctx, &write)
+    }
+
+    /// Write a value, bump its epoch and invalidate its dependents
+    ///
+    /// A value equal to the current one under the comparator registered for
+    /// `T` (see `register_eq`) is dropped without counting as a change.
+    fn write_value<T: Clone + Send + Sync + 'static>(&self, atom_id: AtomId, value: T) {
+        let value = self.intern(value);
+        let equals = self.registered_eq::<T>();
+        self.record_rollback::<T>(atom_id);
+        let state_arc = self.ensure_atom_state::<T>(atom_id);
+        if let Some(state) = state_arc.write().downcast_mut::<AtomState<T>>() {
+            if let (Some(equals), Some(Ok(current))) = (&equals, &state.value) {
+                if equals(current, &value) {
+                    return;
+                }
+            }
+            state.set_value(value);
+        }
+        self.record_change(atom_id);
+        #[cfg(feature = "hot")]
+        self.publish_hot::<T>(atom_id);
+
+        // Mark atom as changed and invalidate everything that read it
+        self.changed.write().insert(atom_id);
+        self.invalidate_dependents(atom_id);
+    }
+
+    /// Register the default equality check for every atom of type `T`
+    ///
+    /// Reference: `Object.is` comparison in `jotai/src/vanilla/internals.ts`
+    ///
+    /// Both `set` and recomputation consult it: a new value equal to the
+    /// current one keeps the old epoch, so dependents are not invalidated
+    /// and listeners are not notified. An atom's own `with_equality` check
+    /// takes precedence. Registering again for the same type replaces the
+    /// previous comparator.
+    ///
+    /// Useful for domain types without `PartialEq`, or for tolerances:
+    ///
+    /// ```rust,ignore
+    /// store.register_eq::<f64, _>(|a, b| (a - b).abs() < 1e-9);
+    /// ```
+    pub fn register_eq<T, F>(&self, equals: F)
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(&T, &T) -> bool + Send + Sync + 'static,
+    {
+        let equals: EqualityFn<T> = Arc::new(equals);
+        self.equalities
+            .write()
+            .insert(TypeId::of::<T>(), Arc::new(equals));
+    }
+
+    /// The comparator registered for `T`, if any
+    fn registered_eq<T: 'static>(&self) -> Option<EqualityFn<T>> {
+        self.equalities
+            .read()
+            .get(&TypeId::of::<T>())?
+            .downcast_ref::<EqualityFn<T>>()
+            .cloned()
+    }
+
+    /// Enable string interning for `Arc<str>` atom values
+    ///
+    /// From now on, every `Arc<str>` value written to or computed in this
+    /// store is replaced by a shared copy from a store-level table, so equal
+    /// strings held by different atoms share one allocation. Values cached
+    /// before interning was enabled are left as they are.
+    ///
+    /// **Rust Pattern**: Flyweight via `Arc` sharing
+    pub fn enable_interning(&self) {
+        self.interner.write().get_or_insert_with(HashSet::new);
+    }
+
+    /// Start recording atom changes, keeping the most recent `capacity`
+    ///
+    /// Reference: `useAtomsDebugValue` / time travel in `jotai-devtools`
+    ///
+    /// Every time an atom gets a new epoch (a write, an in-place update, a
+    /// recomputation producing a different value, or a transaction rollback)
+    /// a `ChangeEvent` is appended to a ring buffer; once it holds
+    /// `capacity` events, the oldest is dropped. Values are type-erased, so
+    /// events carry IDs and epochs, plus the encoded value for types
+    /// registered with `register_snapshot_type`. Enabling again starts a
+    /// fresh, empty log.
+    ///
+    /// While disabled (the default), recording costs one uncontended lock
+    /// per change.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.enable_history(1_000);
+    /// store.set(&count, 1)?;
+    /// for event in store.history() {
+    ///     println!("atom{} -> epoch {}", event.atom_id, event.epoch);
+    /// }
+    /// ```
+    pub fn enable_history(&self, capacity: usize) {
+        *self.change_log.lock() = Some(ChangeLog {
+            capacity,
+            events: VecDeque::with_capacity(capacity),
+        });
+    }
+
+    /// Stop recording atom changes and drop the recorded ones
+    pub fn disable_history(&self) {
+        *self.change_log.lock() = None;
+    }
+
+    /// Recorded atom changes, oldest first (empty while history is disabled)
+    pub fn history(&self) -> Vec<ChangeEvent> {
+        self.change_log
+            .lock()
+            .as_ref()
+            .map(|log| log.events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Append the atom's current epoch (and encodable value) to the history
+    fn record_change(&self, atom_id: AtomId) {
+        if self.change_log.lock().is_none() {
+            return;
+        }
+        let Some(state) = self.state_of(atom_id) else {
+            return;
+        };
+        let event = {
+            let state = state.read();
+            let value = self
+                .snapshot_codecs
+                .read()
+                .get(&state.value_type_id())
+                .and_then(|codec| {
+                    state
+                        .value_erased()
+                        .and_then(|value| (codec.encode)(&*value))
+                });
+            ChangeEvent {
+                atom_id,
+                epoch: state.epoch(),
+                at: Instant::now(),
+                value,
+            }
+        };
+        if let Some(log) = self.change_log.lock().as_mut() {
+            log.push(event);
+        }
+    }
+
+    /// Swap an `Arc<str>` value for the interned copy (other types pass through)
+    fn intern<T: 'static>(&self, mut value: T) -> T {
+        let Some(text) = (&mut value as &mut dyn Any).downcast_mut::<Arc<str>>() else {
+            return value;
+        };
+        if let Some(table) = self.interner.write().as_mut() {
+            match table.get(text) {
+                Some(shared) => *text = shared.clone(),
+                None => {
+                    table.insert(text.clone());
+                }
+            }
+        }
+        value
+    }
+
+    /// Subscribe to atom changes
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (storeSub function ~line 1000)
+    ///
+    /// ```typescript
+    /// const storeSub = (atom: AnyAtom, listener: () => void) => {
+    ///   mountAtom(atom, listener)
+    ///   flushCallbacks()
+    ///   const unsubscribe = () => {
+    ///     unmountAtom(atom, listener)
+    ///     flushCallbacks()
+    ///   }
+    ///   return unsubscribe
+    /// }
+    /// ```
+    ///
+    /// This function:
+    /// 1. Mounts the atom (creates Mounted entry)
+    /// 2. Recursively mounts dependencies
+    /// 3. Adds the listener to the Mounted entry
+    /// 4. Calls atom's onMount callback if present
+    /// 5. Returns an unsubscribe function
+    ///
+    /// **FP Pattern**: Higher-order function returns cleanup function
+    pub fn sub<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn() + Send + Sync + 'static,
+    {
+        let atom = atom.as_atom();
+        let listener_id = self.mount_atom(atom, Arc::new(listener));
+        self.flush_if_sync();
+
+        let store = self.handle();
+        let atom_id = atom.id;
+        Box::new(move || {
+            store.unmount_atom(atom_id, listener_id);
+            store.flush_if_sync();
+        })
+    }
+
+    /// Subscribe to atom changes, unsubscribing when the guard is dropped
+    ///
+    /// Same as `sub`, but the unsubscribe function is wrapped in a
+    /// `Subscription`, so a forgotten call cannot leak the listener.
+    ///
+    /// **FP Pattern**: Resource scope (bracket / RAII)
+    pub fn subscribe<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Subscription
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn() + Send + Sync + 'static,
+    {
+        Subscription::new(self.sub(atom, listener))
+    }
+
+    /// Subscribe to atom changes, receiving the new value
+    ///
+    /// Like `sub`, but the listener is handed the atom's value after each
+    /// change instead of reading it itself. Changes whose read fails are not
+    /// delivered.
+    ///
+    /// **FP Pattern**: Observer pattern with pushed values
+    pub fn sub_with_value<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let atom = atom.as_atom();
+        let store = self.handle();
+        let target = atom.clone();
+        self.sub(atom, move || {
+            if let Ok(value) = store.get(&target) {
+                listener(value);
+            }
+        })
+    }
+
+    /// Subscribe and receive the current value immediately
+    ///
+    /// The atom is mounted first (so its dependencies are tracked), then the
+    /// listener is called synchronously with the current value before this
+    /// method returns. Afterwards it behaves like `sub_with_value`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let unsub = store.sub_immediate(count.as_atom(), |value| render(value));
+    /// ```
+    pub fn sub_immediate<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let listener = Arc::new(listener);
+        let on_change = listener.clone();
+        let unsubscribe = self.sub_with_value(atom, move |value| on_change(value));
+
+        if let Ok(value) = self.get(atom) {
+            listener(value);
+        }
+
+        unsubscribe
+    }
+
+    /// Subscribe to atom changes, receiving the previous and new values
+    ///
+    /// The subscription remembers the last value it saw, starting from the
+    /// atom's value at subscription time, and hands the listener
+    /// `(Some(previous), new)` on every change. Changes whose read fails are
+    /// skipped and do not replace the remembered value.
+    ///
+    /// **FP Pattern**: Observer pattern with pushed diffs
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let unsub = store.sub_diff(items.as_atom(), |old, new| {
+    ///     let added = new.len() - old.map_or(0, |old| old.len());
+    ///     println!("{} items added", added);
+    /// });
+    /// ```
+    pub fn sub_diff<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(Option<T>, T) + Send + Sync + 'static,
+    {
+        let last = Arc::new(Mutex::new(self.get(atom).ok()));
+        self.sub_diff_from(atom.as_atom(), last, Arc::new(listener))
+    }
+
+    /// Like `sub_diff`, but first delivers `(None, current)` immediately
+    ///
+    /// The atom is mounted before the current value is read, as with
+    /// `sub_immediate`.
+    pub fn sub_diff_immediate<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(Option<T>, T) + Send + Sync + 'static,
+    {
+        let listener = Arc::new(listener);
+        let last = Arc::new(Mutex::new(None));
+        let unsubscribe = self.sub_diff_from(atom.as_atom(), last.clone(), listener.clone());
+
+        if let Ok(value) = self.get(atom) {
+            *last.lock() = Some(value.clone());
+            listener(None, value);
+        }
+
+        unsubscribe
+    }
+
+    /// Subscribe through a channel: the current value plus a receiver of changes
+    ///
+    /// Returns the atom's value now and a `Receiver` that yields the new
+    /// value after each change (changes whose read fails are skipped). Hand
+    /// the receiver to another thread or bridge it into an async runtime.
+    ///
+    /// There is no unsubscribe function: dropping the receiver ends the
+    /// subscription. The store notices at the next change, when sending
+    /// fails, and unsubscribes then.
+    ///
+    /// Fails if the current value cannot be read; nothing is subscribed then.
+    ///
+    /// **FP Pattern**: Observer pattern adapted to message passing
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (initial, changes) = store.watch_channel(count.as_atom())?;
+    /// std::thread::spawn(move || {
+    ///     for value in changes {
+    ///         println!("count is now {}", value);
+    ///     }
+    /// });
+    /// ```
+    pub fn watch_channel<T>(&self, atom: &impl AsAtom<T>) -> Result<(T, mpsc::Receiver<T>)>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let current = self.get(atom)?;
+        let (sender, receiver) = mpsc::channel();
+
+        let unsubscribe: Arc<Mutex<Option<Unsubscribe>>> = Arc::new(Mutex::new(None));
+        let slot = unsubscribe.clone();
+        let unsub = self.sub_with_value(atom, move |value| {
+            if sender.send(value).is_err() {
+                // The receiver is gone
+                if let Some(unsubscribe) = slot.lock().take() {
+                    unsubscribe();
+                }
+            }
+        });
+        *unsubscribe.lock() = Some(unsub);
+
+        Ok((current, receiver))
+    }
+
+    /// Diff subscription remembering the last seen value in `last`
+    fn sub_diff_from<T, F>(
+        &self,
+        atom: &Atom<T>,
+        last: Arc<Mutex<Option<T>>>,
+        listener: Arc<F>,
+    ) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(Option<T>, T) + Send + Sync + 'static,
+    {
+        let store = self.handle();
+        let target = atom.clone();
+        self.sub(atom, move || {
+            if let Ok(value) = store.get(&target) {
+                let previous = last.lock().replace(value.clone());
+                listener(previous, value);
+            }
+        })
+    }
+
+    /// Subscribe with a bounded notification queue
+    ///
+    /// Each change notification is queued for this subscription and the
+    /// listener drains the queue one `Notification` at a time. When changes
+    /// arrive faster than the listener handles them (e.g. other threads keep
+    /// writing while it runs), at most `capacity` notifications are kept: the
+    /// oldest ones are dropped, and the next delivered notification reports
+    /// how many were lost in `dropped`.
+    ///
+    /// A `capacity` of 0 is treated as 1.
+    ///
+    /// **FP Pattern**: Backpressure via bounded buffer
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let unsub = store.sub_bounded(count.as_atom(), 16, |notification| {
+    ///     if notification.dropped > 0 {
+    ///         eprintln!("missed {} updates", notification.dropped);
+    ///     }
+    /// });
+    /// ```
+    pub fn sub_bounded<T, F>(
+        &self,
+        atom: &impl AsAtom<T>,
+        capacity: usize,
+        listener: F,
+    ) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(Notification) + Send + Sync + 'static,
+    {
+        let atom = atom.as_atom();
+        let queue = Arc::new(Mutex::new(BoundedQueue::new(capacity)));
+        let store = self.handle();
+        let atom_id = atom.id;
+
+        self.sub(atom, move || {
+            {
+                let mut queue = queue.lock();
+                queue.push(store.epoch_of(atom_id).unwrap_or(0));
+                if queue.delivering {
+                    // The thread already delivering will drain this one
+                    return;
+                }
+                queue.delivering = true;
+            }
+
+            loop {
+                let next = {
+                    let mut queue = queue.lock();
+                    match queue.pop() {
+                        Some(notification) => notification,
+                        None => {
+                            queue.delivering = false;
+                            return;
+                        }
+                    }
+                };
+                listener(next);
+            }
+        })
+    }
+
+    /// Subscribe with the listener running on the store's worker threads
+    ///
+    /// Like `sub_with_value`, but the listener is not called by the writing
+    /// thread: each change's value is queued for this subscription and a
+    /// small pool of worker threads, shared by all offloaded subscriptions
+    /// of the store, delivers it. `set` returns without waiting for the
+    /// listener, so listeners may block (I/O, slow rendering).
+    ///
+    /// Values reach the listener in the order the changes happened, one at
+    /// a time: a subscription's queue is drained by one worker at a time.
+    /// Values still queued when unsubscribing are discarded. A panicking
+    /// listener loses that one value and keeps receiving later ones.
+    ///
+    /// **FP Pattern**: Observer pattern with an asynchronous mailbox (actor)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let unsub = store.sub_offloaded(document.as_atom(), move |doc| {
+    ///     save_to_disk(&doc); // blocking I/O off the writer's thread
+    /// });
+    /// ```
+    pub fn sub_offloaded<T, F>(&self, atom: &impl AsAtom<T>, listener: F) -> Unsubscribe
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let atom = atom.as_atom();
+        let jobs = self
+            .offload_pool
+            .get_or_init(|| OffloadPool::new(OFFLOAD_THREADS))
+            .jobs
+            .clone();
+        let queue = Arc::new(Mutex::new(OffloadQueue::new()));
+        let listener = Arc::new(listener);
+        let store = self.handle();
+        let target = atom.clone();
+
+        let mailbox = queue.clone();
+        let unsubscribe = self.sub(atom, move || {
+            let Ok(value) = store.get(&target) else {
+                return;
+            };
+            {
+                let mut queue = mailbox.lock();
+                queue.pending.push_back(value);
+                if queue.draining {
+                    // A worker is already draining this subscription
+                    return;
+                }
+                queue.draining = true;
+            }
+            let (queue, listener) = (mailbox.clone(), listener.clone());
+            let _ = jobs.send(Box::new(move || OffloadQueue::drain(&queue, &*listener)));
+        });
+
+        Box::new(move || {
+            {
+                let mut queue = queue.lock();
+                queue.active = false;
+                queue.pending.clear();
+            }
+            unsubscribe();
+        })
+    }
+
+    /// Ensure an atom has state initialized
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (ensureAtomState function)
+    ///
+    /// Creates an empty `AtomState<T>` if the atom has none yet (or if the
+    /// stored state has a different type) and returns the shared state.
+    ///
+    /// TODO: Call unstable_onInit if present
+    pub(crate) fn ensure_atom_state<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom_id: AtomId,
+    ) -> SharedAtomState {
+        let state_arc = self
+            .atom_states
+            .entry(atom_id)
+            .or_insert_with(|| Arc::new(RwLock::new(Box::new(AtomState::<T>::new()))))
+            .clone();
+
+        {
+            let mut lock = state_arc.write();
+            if lock.downcast_ref::<AtomState<T>>().is_none() {
+                // The ID now belongs to an atom of another type. Keep the
+                // epoch moving so dependents never mistake the new state for
+                // the one they recorded.
+                let mut state = AtomState::<T>::new();
+                state.epoch = lock.epoch().wrapping_add(1);
+                *lock = Box::new(state);
+            }
+        }
+
+        state_arc
+    }
+
+    /// Look up an atom's shared state without creating it
+    pub(crate) fn state_of(&self, atom_id: AtomId) -> Option<SharedAtomState> {
+        self.atom_states
+            .get(&atom_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Current epoch of an atom, if it has state in this store
+    pub(crate) fn epoch_of(&self, atom_id: AtomId) -> Option<EpochNumber> {
+        self.state_of(atom_id).map(|state| state.read().epoch())
+    }
+
+    /// Read atom state, computing if necessary
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (readAtomState function)
+    ///
+    /// This is the core function that:
+    /// - Checks cache validity (a cached value is reused unless the atom
+    ///   has been invalidated by a dependency change)
+    /// - Calls read function if needed, with a `DependencyTracker` as getter
+    /// - Commits the result and the discovered dependencies
+    ///
+    /// Concurrent reads of the same stale atom are serialized on its
+    /// `computing` gate: the first thread computes, the others wait and pick
+    /// up the committed value.
+    ///
+    /// An atom that (transitively) reads itself while computing gets
+    /// `AtomError::CircularDependency`, with the chain from the atom back to
+    /// itself. Every atom on the cycle caches that error until one of its
+    /// dependencies changes.
+    pub(crate) fn read_atom_state<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &Atom<T>,
+    ) -> Result<T> {
+        #[cfg(feature = "hot")]
+        if atom.hot {
+            return self.read_hot(atom);
+        }
+        let result = self.read_gated(atom);
+        if !atom.writable
+            && atom.cache_policy != CachePolicy::NoCache
+            && self.derived_cache_limit.load(Ordering::SeqCst) != usize::MAX
+            && !self.is_materialized(atom.id)
+        {
+            self.derived_lru.lock().touch(atom.id);
+            self.evict_derived();
+        }
+        result
+    }
+
+    /// Drop the least recently read derived values beyond the cache limit
+    ///
+    /// Mounted atoms and atoms being computed are skipped (they stay in the
+    /// index), so the limit may be exceeded while they hold their values.
+    /// Materialized atoms are never counted.
+    fn evict_derived(&self) {
+        let limit = self.derived_cache_limit.load(Ordering::SeqCst);
+        let mut lru = self.derived_lru.lock();
+        let candidates: Vec<AtomId> = lru.order.values().copied().collect();
+        for atom_id in candidates {
+            if lru.last_read.len() <= limit {
+                break;
+            }
+            if self.is_materialized(atom_id) {
+                lru.forget(atom_id);
+                continue;
+            }
+            if self.mounted.contains_key(&atom_id) || self.computing.contains_key(&atom_id) {
+                continue;
+            }
+            lru.forget(atom_id);
+            if let Some(state) = self.state_of(atom_id) {
+                state.write().clear_value();
+            }
+        }
+    }
+
+    /// Bound how many read-only derived values the store keeps cached
+    ///
+    /// When more than `limit` read-only derived atoms (those made with
+    /// `atom_derived` and friends) hold cached values, the values of the
+    /// least recently read ones are dropped; they recompute on their next
+    /// read. Mounted atoms are never evicted, so subscribers keep fresh
+    /// values. Primitive and other writable atoms are not counted: their
+    /// state may be the only copy of a written value.
+    ///
+    /// `usize::MAX` (the default) removes the limit. Lowering the limit
+    /// takes effect on the next derived read.
+    ///
+    /// **FP Pattern**: Memoization with bounded cache size (LRU eviction)
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.set_derived_cache_limit(1_000);
+    /// ```
+    pub fn set_derived_cache_limit(&self, limit: usize) {
+        self.derived_cache_limit.store(limit, Ordering::SeqCst);
+        if limit == usize::MAX {
+            *self.derived_lru.lock() = DerivedLru::default();
+        }
+    }
+
+    /// Serve a fresh cached value, or compute under the atom's gate
+    fn read_gated<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T> {
+        // Checked before the cache: mid-cycle, the atom still holds its
+        // previous value
+        let _frame = self.enter_read(atom.id)?;
+        if let Some(cached) = self.cached_result(atom) {
+            return cached;
+        }
+
+        let gate = self
+            .computing
+            .entry(atom.id)
+            .or_insert_with(|| Arc::new(ReentrantMutex::new(())))
+            .clone();
+        let result = {
+            let _computing = gate.lock();
+            self.compute_atom_state(atom)
+        };
+        // Only the map and this handle left: nobody else is waiting
+        self.computing
+            .remove_if(&atom.id, |_, entry| Arc::strong_count(entry) == 2);
+
+        result
+    }
+
+    /// Push `atom_id` on this thread's read stack, failing if it is already on it
+    fn enter_read(&self, atom_id: AtomId) -> Result<ReadFrame<'_>> {
+        let thread = std::thread::current().id();
+        let mut stacks = self.read_stacks.lock();
+        let stack = stacks.entry(thread).or_default();
+        if let Some(start) = stack.iter().position(|&id| id == atom_id) {
+            let mut dependency_chain = stack[start..].to_vec();
+            dependency_chain.push(atom_id);
+            return Err(AtomError::CircularDependency {
+                atom_id,
+                dependency_chain,
+            });
+        }
+        stack.push(atom_id);
+        Ok(ReadFrame {
+            stacks: &self.read_stacks,
+            thread,
+        })
+    }
+
+    /// Whether `atom_id` is being computed further up this thread's read
+    fn is_reading(&self, atom_id: AtomId) -> bool {
+        self.read_stacks
+            .lock()
+            .get(&std::thread::current().id())
+            .is_some_and(|stack| stack.contains(&atom_id))
+    }
+
+    /// Read a hot atom from its lock-free cell, creating the cell if needed
+    ///
+    /// The first read computes the atom as usual, then publishes the value.
+    /// Publishing once more after the cell exists catches a `set` that ran
+    /// in between and found no cell to update.
+    #[cfg(feature = "hot")]
+    fn read_hot<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T> {
+        let cell = self
+            .hot_cells
+            .get(&atom.id)
+            .map(|entry| entry.value().clone());
+        if let Some(cell) = cell
+            .as_ref()
+            .and_then(|cell| cell.downcast_ref::<HotCell<T>>())
+        {
+            return Ok(T::clone(&cell.load()));
+        }
+
+        let value = self.read_gated(atom)?;
+        self.hot_cells
+            .entry(atom.id)
+            .or_insert_with(|| Arc::new(HotCell::<T>::from_pointee(value.clone())));
+        self.publish_hot::<T>(atom.id);
+        Ok(value)
+    }
+
+    /// Copy an atom's stored value into its hot cell, if it has one
+    ///
+    /// The copy happens under the state's read lock, so a write cannot land
+    /// in between and racing publishers store values in write order.
+    #[cfg(feature = "hot")]
+    fn publish_hot<T: Clone + Send + Sync + 'static>(&self, atom_id: AtomId) {
+        let Some(cell) = self
+            .hot_cells
+            .get(&atom_id)
+            .map(|entry| entry.value().clone())
+        else {
+            return;
+        };
+        let Some(cell) = cell.downcast_ref::<HotCell<T>>() else {
+            return;
+        };
+        let Some(state_arc) = self.state_of(atom_id) else {
+            return;
+        };
+        let lock = state_arc.read();
+        if let Some(Some(Ok(value))) = lock
+            .downcast_ref::<AtomState<T>>()
+            .map(|state| &state.value)
+        {
+            cell.store(Arc::new(value.clone()));
+        }
+    }
+
+    /// Body of `read_atom_state`, run while holding the atom's gate
+    fn compute_atom_state<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T> {
+        // Another thread may have finished computing while we waited
+        if let Some(cached) = self.cached_result(atom) {
+            return cached;
+        }
+        if self.dependencies_unchanged(atom.id) {
+            self.invalidated.write().remove(&atom.id);
+            if let Some(cached) = self.cached_result(atom) {
+                return cached;
+            }
+        }
+
+        // Atoms made with `atom_retrying` get several attempts per
+        // computation; each attempt tracks its own dependencies
+        let mut attempt = 1;
+        let (result, dependencies) = loop {
+            let tracker = DependencyTracker::new(self, atom.id);
+            #[cfg(feature = "profiling")]
+            let started = Instant::now();
+            let result = self
+                .catch_panic(atom.id, PanicSite::Read, || atom.read(&tracker))
+                .unwrap_or_else(|info| Err(AtomError::read_error(info.atom_id, info.message)));
+            #[cfg(feature = "profiling")]
+            self.record_timing(atom.id, started.elapsed());
+            if result.is_ok() || attempt >= atom.max_attempts {
+                break (result, tracker.take_dependencies());
+            }
+            attempt += 1;
+        };
+        let dependencies = match &atom.declared_dependencies {
+            Some(declared) => declared
+                .iter()
+                .map(|&dependency| (dependency, self.epoch_of(dependency).unwrap_or(0)))
+                .collect(),
+            None => dependencies,
+        };
+        self.commit_computed(atom, result.clone(), dependencies);
+        self.register_config(atom);
+        if atom.cache_policy == CachePolicy::NoCache && self.in_flush_scope() {
+            self.flush_memo.lock().insert(atom.id);
+        }
+
+        result
+    }
+
+    /// Whether a read of `atom` must recompute regardless of its cache
+    fn skips_cache<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> bool {
+        atom.cache_policy == CachePolicy::NoCache
+            && !self.flush_memo.lock().contains(&atom.id)
+            && !self.is_materialized(atom.id)
+    }
+
+    /// Whether `materialize` froze this atom's value in this store
+    fn is_materialized(&self, atom_id: AtomId) -> bool {
+        self.materialized.read().contains(&atom_id)
+    }
+
+    /// Whether the current thread is inside a batch
+    fn in_batch(&self) -> bool {
+        self.batch_depth
+            .lock()
+            .contains_key(&std::thread::current().id())
+    }
+
+    /// Whether the current thread is inside a batch or a flush
+    fn in_flush_scope(&self) -> bool {
+        self.in_batch() || self.flushing.lock().contains(&std::thread::current().id())
+    }
+
+    /// Forget flush-scoped memos once no batch is running on any thread
+    fn end_flush_scope(&self) {
+        if self.batch_depth.lock().is_empty() {
+            self.flush_memo.lock().clear();
+        }
+    }
+
+    /// Remember the atom's config so it can be recomputed by ID
+    fn register_config<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) {
+        let registered = self
+            .atom_configs
+            .get(&atom.id)
+            .is_some_and(|config| config.value_type_id() == TypeId::of::<T>());
+        if !registered {
+            self.atom_configs.insert(atom.id, Arc::new(atom.clone()));
+        }
+    }
+
+    /// Resolve an atom, awaiting its future if it is async
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (readAtomState with promise values)
+    ///
+    /// For atoms made with `atom_async`, a fresh resolved value is returned
+    /// from the cache; otherwise the read function runs and its future is
+    /// awaited. Dependencies are the atoms read before the future was
+    /// returned, recorded at their epochs once it resolves. The resolved
+    /// value (or error) is cached, dependents are invalidated and listeners
+    /// of a mounted atom are notified.
+    ///
+    /// Concurrent calls share one evaluation: while it is in flight and the
+    /// atoms it read before its first await keep their epochs, later calls
+    /// await the same future instead of running the read function again.
+    ///
+    /// Starting a new evaluation (because a dependency changed) aborts the
+    /// `AbortSignal` of the previous one, whose result is then discarded
+    /// with `AtomError::Cancelled`. Dropping every future awaiting an
+    /// evaluation aborts it too.
+    ///
+    /// Other atoms resolve as with `get`.
+    #[cfg(feature = "async")]
+    pub async fn get_async<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+    ) -> Result<T> {
+        let atom = atom.as_atom();
+        let Some(read) = atom.async_read.clone() else {
+            return self.get(atom);
+        };
+        if let Some(Ok(value)) = self.cached_result(atom) {
+            return Ok(value);
+        }
+        if let Some(evaluation) = self.join_in_flight::<T>(atom.id) {
+            return evaluation.await;
+        }
+
+        let signal = AbortSignal::new();
+        if let Some(superseded) = self.aborts.insert(atom.id, signal.clone()) {
+            superseded.abort();
+        }
+        let abort_on_drop = AbortOnDrop(Some(signal.clone()));
+
+        let (future, dependencies) = {
+            let tracker = DependencyTracker::new(self, atom.id);
+            let future = read(&tracker, signal.clone());
+            (future, tracker.take_dependencies())
+        };
+        let started = dependencies
+            .keys()
+            .map(|&dependency| (dependency, self.epoch_of(dependency)))
+            .collect();
+
+        let store = self.handle();
+        let target = atom.clone();
+        let evaluation_signal = signal.clone();
+        let evaluation: SharedEvaluation<T> = async move {
+            let mut abort_on_drop = abort_on_drop;
+            let signal = evaluation_signal;
+            let result = future.await;
+
+            abort_on_drop.0 = None;
+            store
+                .aborts
+                .remove_if(&target.id, |_, current| current.same_as(&signal));
+            store
+                .in_flight
+                .remove_if(&target.id, |_, current| current.signal.same_as(&signal));
+            if signal.is_aborted() {
+                return Err(AtomError::Cancelled { atom_id: target.id });
+            }
+
+            let dependencies = dependencies
+                .into_keys()
+                .map(|dependency| (dependency, store.epoch_of(dependency).unwrap_or(0)))
+                .collect();
+            let epoch_before = store.epoch_of(target.id);
+            store.commit_computed(&target, result.clone(), dependencies);
+            store.register_config(&target);
+
+            if store.epoch_of(target.id) != epoch_before {
+                if store.mounted.contains_key(&target.id) {
+                    store.changed.write().insert(target.id);
+                }
+                store.invalidate_dependents(target.id);
+                store.flush_if_sync();
+            }
+
+            result
+        }
+        .boxed()
+        .shared();
+
+        // Only a weak handle is kept, so dropping every waiter still aborts
+        if let Some(weak) = evaluation.downgrade() {
+            self.in_flight.insert(
+                atom.id,
+                InFlight {
+                    signal,
+                    started,
+                    evaluation: Box::new(weak),
+                },
+            );
+        }
+        evaluation.await
+    }
+
+    /// The in-flight evaluation of an async atom, if it can be joined
+    ///
+    /// An evaluation is joinable while it is alive, not aborted, and every
+    /// atom it read before its first await still has the epoch it had then.
+    #[cfg(feature = "async")]
+    fn join_in_flight<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom_id: AtomId,
+    ) -> Option<SharedEvaluation<T>> {
+        let entry = self.in_flight.get(&atom_id)?;
+        if entry.signal.is_aborted()
+            || entry
+                .started
+                .iter()
+                .any(|&(dependency, epoch)| self.epoch_of(dependency) != epoch)
+        {
+            return None;
+        }
+        entry
+            .evaluation
+            .downcast_ref::<WeakShared<BoxFuture<'static, Result<T>>>>()?
+            .upgrade()
+    }
+
+    /// Whether an atom has a cached value that is not invalidated
+    fn has_fresh_value(&self, atom_id: AtomId) -> bool {
+        !self.invalidated.read().contains(&atom_id)
+            && self
+                .state_of(atom_id)
+                .is_some_and(|state| state.read().has_value())
+    }
+
+    /// Cached result for an atom, if present and not invalidated
+    ///
+    /// Always `None` for atoms with `CachePolicy::NoCache`, except within
+    /// the batch or flush that computed them.
+    fn cached_result<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Option<Result<T>> {
+        if self.skips_cache(atom) || self.invalidated.read().contains(&atom.id) {
+            return None;
+        }
+        let state_arc = self.state_of(atom.id)?;
+        let lock = state_arc.read();
+        lock.downcast_ref::<AtomState<T>>()?.value.clone()
+    }
+
+    /// Whether an invalidated atom can keep its cached value
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (readAtomState dependency check)
+    ///
+    /// Brings each recorded dependency up to date, then asks
+    /// `AtomState::is_fresh` whether every epoch still equals the one
+    /// recorded at the last computation. If none moved (e.g. a dependency
+    /// recomputed to an equal value), recomputing is unnecessary.
+    ///
+    /// A dependency whose stored state no longer matches its registered
+    /// atom's type (its ID was reused by an atom of another type) counts as
+    /// gone: the stale edge is dropped and the atom recomputes.
+    fn dependencies_unchanged(&self, atom_id: AtomId) -> bool {
+        let Some(state_arc) = self.state_of(atom_id) else {
+            return false;
+        };
+        let dependencies = {
+            let lock = state_arc.read();
+            if !lock.has_value() || lock.dependencies().is_empty() {
+                return false;
+            }
+            lock.dependencies().clone()
+        };
+
+        // Bring each dependency up to date, stopping at the first one that
+        // moved; `is_fresh` then compares every recorded epoch
+        let refreshed = dependencies.into_iter().all(|(dependency, epoch)| {
+            let config = self
+                .atom_configs
+                .get(&dependency)
+                .map(|entry| entry.value().clone());
+            let state_type = self
+                .state_of(dependency)
+                .map(|state| state.read().value_type_id());
+            match config {
+                Some(config) if state_type != Some(config.value_type_id()) => {
+                    if let Some(mut dependents) = self.dependents.get_mut(&dependency) {
+                        dependents.remove(&atom_id);
+                    }
+                    false
+                }
+                // Mid-computation further up this read: its epoch says
+                // nothing yet, and reading it closes a cycle
+                Some(_) if self.is_reading(dependency) => false,
+                Some(config) => {
+                    // Read errors are cached and compared by epoch like values
+                    let _ = config.read_in(self);
+                    self.epoch_of(dependency) == Some(epoch)
+                }
+                None => false,
+            }
+        });
+        refreshed
+            && state_arc
+                .read()
+                .is_fresh(&|dependency| self.epoch_of(dependency))
+    }
+
+    /// Store a freshly computed result along with its dependencies
+    ///
+    /// Bumps the epoch (unless the atom's `equals`, or its `value_hash`
+    /// against the stored hash, reports the new value equal to the previous
+    /// one), swaps in the new dependency map, updates the reverse index and
+    /// clears the atom's invalidated flag.
+    fn commit_computed<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &Atom<T>,
+        result: Result<T>,
+        dependencies: HashMap<AtomId, EpochNumber>,
+    ) {
+        let atom_id = atom.id;
+        let equals = atom.equals.as_ref();
+        let registered = equals
+            .is_none()
+            .then(|| self.registered_eq::<T>())
+            .flatten();
+        let equals = equals.or(registered.as_ref());
+        let next_hash = match (atom.value_hash, &result) {
+            (Some(hash), Ok(next)) => Some(hash(next)),
+            _ => None,
+        };
+        let state_arc = self.ensure_atom_state::<T>(atom_id);
+        let (previous, changed) = {
+            let mut lock = state_arc.write();
+            let state = lock
+                .downcast_mut::<AtomState<T>>()
+                .expect("ensure_atom_state guarantees the state type");
+            let same_hash = next_hash.is_some()
+                && matches!(state.value, Some(Ok(_)))
+                && state.value_hash == next_hash;
+            let unchanged = same_hash
+                || match (equals, &state.value, &result) {
+                    (Some(equals), Some(Ok(previous)), Ok(next)) => equals(previous, next),
+                    _ => false,
+                };
+            if !unchanged {
+                state.value = Some(result.map(|value| self.intern(value)));
+                state.value_hash = next_hash;
+                state.epoch = state.epoch.wrapping_add(1);
+            }
+            let previous = std::mem::replace(&mut state.dependencies, dependencies.clone());
+            (previous, !unchanged)
+        };
+        if changed {
+            self.record_change(atom_id);
+        }
+
+        self.update_dependents(atom_id, &previous, &dependencies);
+        self.invalidated.write().remove(&atom_id);
+
+        if self.mounted.contains_key(&atom_id) {
+            self.remount_dependencies(atom_id, &dependencies);
+        }
+    }
+
+    /// Keep the reverse dependency index in sync with a new dependency map
+    pub(crate) fn update_dependents(
+        &self,
+        atom_id: AtomId,
+        previous: &HashMap<AtomId, EpochNumber>,
+        current: &HashMap<AtomId, EpochNumber>,
+    ) {
+        for dependency in previous.keys().filter(|dep| !current.contains_key(dep)) {
+            if let Some(mut dependents) = self.dependents.get_mut(dependency) {
+                dependents.remove(&atom_id);
+            }
+        }
+        for dependency in current.keys() {
+            self.dependents
+                .entry(*dependency)
+                .or_default()
+                .insert(atom_id);
+        }
+    }
+
+    /// Compute a derived atom now and freeze the result in this store
+    ///
+    /// The atom is evaluated (or its fresh cached value reused), then its
+    /// dependency edges are dropped. With no dependencies left, later changes
+    /// to the atoms it used to read never invalidate it: the atom behaves like
+    /// a static snapshot in this store. Other stores are unaffected.
+    ///
+    /// The frozen value is pinned: derived-cache eviction (see
+    /// `set_derived_cache_limit`) and the atom's cache policy never drop or
+    /// recompute it. `retain` dropping the atom releases the pin.
+    ///
+    /// If the read fails, the error is returned and the atom is not frozen.
+    ///
+    /// **FP Pattern**: Eager evaluation on demand, then immutability
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = atom_derived(move |get| expensive_setup(get));
+    /// let snapshot = store.materialize(&config)?;
+    /// ```
+    pub fn materialize<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+    ) -> Result<T> {
+        let atom = atom.as_atom();
+        let value = self.read_atom_state(atom)?;
+
+        if let Some(state_arc) = self.state_of(atom.id) {
+            let previous = {
+                let mut lock = state_arc.write();
+                let previous = lock.dependencies().clone();
+                lock.clear_dependencies();
+                previous
+            };
+            self.update_dependents(atom.id, &previous, &HashMap::new());
+        }
+        self.materialized.write().insert(atom.id);
+        self.derived_lru.lock().forget(atom.id);
+
+        Ok(value)
+    }
+
+    /// Run `f` with a dependency-tracking getter and report what it read
+    ///
+    /// Returns the closure's result together with the IDs of every atom it
+    /// read (sorted, without duplicates). The reads go through the store as
+    /// usual, but no dependency edges are recorded: the closure is not an
+    /// atom, so nothing is invalidated when those atoms change later.
+    /// `get.previous` always returns `None` inside `f`.
+    ///
+    /// Useful for building combinators that need to know their inputs.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (total, read) = store.track(|get| {
+    ///     Ok::<_, AtomError>(get.get(a.as_atom())? + get.get(b.as_atom())?)
+    /// });
+    /// ```
+    pub fn track<R, F>(&self, f: F) -> (R, Vec<AtomId>)
+    where
+        F: FnOnce(&dyn Getter) -> R,
+    {
+        let tracker = DependencyTracker::detached(self);
+        let result = f(&tracker);
+        let mut read: Vec<AtomId> = tracker.take_dependencies().into_keys().collect();
+        read.sort_unstable();
+        (result, read)
+    }
+
+    /// Create an independent store starting from a copy of this one's state
+    ///
+    /// Every atom value (and cached error) is deep-copied along with the
+    /// dependency bookkeeping, so derived atoms stay cached in the fork.
+    /// Store settings carry over too: middleware, flush mode, registered
+    /// equalities and snapshot types, interning and the change history.
+    /// Subscriptions do not: the fork starts with nothing mounted and no
+    /// pending notifications.
+    ///
+    /// Afterwards the two stores are unrelated; writes to one are never seen
+    /// by the other. Writes racing with `fork` on other threads may or may
+    /// not be included in the copy.
+    ///
+    /// **FP Pattern**: Persistent snapshot, branching state
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let what_if = store.fork();
+    /// what_if.set(&budget, 0)?;
+    /// preview(what_if.get(&forecast)?);
+    /// ```
+    pub fn fork(&self) -> Store {
+        let fork = Store::new();
+
+        for entry in self.atom_states.iter() {
+            let state = entry.value().read().clone_boxed();
+            fork.atom_states
+                .insert(*entry.key(), Arc::new(RwLock::new(state)));
+        }
+        for entry in self.atom_configs.iter() {
+            fork.atom_configs
+                .insert(*entry.key(), entry.value().clone());
+        }
+        for entry in self.dependents.iter() {
+            fork.dependents.insert(*entry.key(), entry.value().clone());
+        }
+        *fork.invalidated.write() = self.invalidated.read().clone();
+
+        *fork.middleware.write() = self.middleware.read().clone();
+        *fork.panic_handler.write() = self.panic_handler.read().clone();
+        fork.derived_cache_limit.store(
+            self.derived_cache_limit.load(Ordering::SeqCst),
+            Ordering::SeqCst,
+        );
+        *fork.flush_mode.write() = self.flush_mode();
+        *fork.equalities.write() = self.equalities.read().clone();
+        *fork.snapshot_codecs.write() = self.snapshot_codecs.read().clone();
+        *fork.interner.write() = self.interner.read().clone();
+        *fork.change_log.lock() = self.change_log.lock().clone();
+        *fork.materialized.write() = self.materialized.read().clone();
+
+        fork
+    }
+
+    /// Drop the state of every atom for which `keep` returns false
+    ///
+    /// `keep` receives each atom's ID and debug label (`None` if unlabelled
+    /// or if the store has only seen its ID). Dropped atoms are unmounted
+    /// even if they have listeners: their listeners are discarded and their
+    /// cleanups run, and their dependencies are released. Mounted atoms that
+    /// depended on a dropped atom are recomputed, which brings it back from
+    /// its read function; unmounted dependents do the same on their next read.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Between screens: forget everything that belonged to the old page
+    /// store.retain(|_, label| !label.is_some_and(|label| label.starts_with("page/")));
+    /// ```
+    pub fn retain<F>(&self, keep: F)
+    where
+        F: Fn(AtomId, Option<&str>) -> bool,
+    {
+        let ids: Vec<AtomId> = self.atom_states.iter().map(|entry| *entry.key()).collect();
+        let dropped: Vec<AtomId> = ids
+            .into_iter()
+            .filter(|atom_id| {
+                let config = self.atom_configs.get(atom_id);
+                !keep(
+                    *atom_id,
+                    config.as_ref().and_then(|config| config.debug_label()),
+                )
+            })
+            .collect();
+
+        for &atom_id in &dropped {
+            self.force_unmount(atom_id);
+            self.invalidate_dependents(atom_id);
+
+            if let Some((_, state)) = self.atom_states.remove(&atom_id) {
+                let dependencies = state.read().dependencies().clone();
+                self.update_dependents(atom_id, &dependencies, &HashMap::new());
+            }
+            self.atom_configs.remove(&atom_id);
+            #[cfg(feature = "hot")]
+            self.hot_cells.remove(&atom_id);
+            self.invalidated.write().remove(&atom_id);
+            self.changed.write().remove(&atom_id);
+            self.materialized.write().remove(&atom_id);
+        }
+
+        self.flush_if_sync();
+    }
+
+    /// Recompute timings per atom, slowest (by total time) first
+    ///
+    /// Each entry is `(atom_id, count, total, max)`: how many times the
+    /// atom's read function ran in this store, the total time spent in it,
+    /// and the longest single run. Time spent reading dependencies inside the
+    /// read function is included.
+    ///
+    /// Only available with the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    pub fn timing_report(&self) -> Vec<(AtomId, usize, Duration, Duration)> {
+        let mut report: Vec<_> = self
+            .timings
+            .lock()
+            .iter()
+            .map(|(atom_id, timing)| (*atom_id, timing.count, timing.total, timing.max))
+            .collect();
+        report.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        report
+    }
+
+    /// How many times an atom's read function has run in this store
+    ///
+    /// Counts actual runs only: reads served from the cache, or recomputes
+    /// skipped because no dependency changed, leave it unchanged. Each
+    /// attempt of an `atom_retrying` atom counts. Atoms never computed
+    /// report 0.
+    ///
+    /// Only available with the `profiling` feature, so other builds pay
+    /// nothing for the bookkeeping.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let before = store.recompute_count(total.id());
+    /// store.set(&unrelated, 1)?;
+    /// store.get(&total)?;
+    /// assert_eq!(store.recompute_count(total.id()), before);
+    /// ```
+    #[cfg(feature = "profiling")]
+    pub fn recompute_count(&self, atom_id: AtomId) -> u64 {
+        self.timings
+            .lock()
+            .get(&atom_id)
+            .map_or(0, |timing| timing.count as u64)
+    }
+
+    /// Add one read-function run to an atom's timing stats
+    #[cfg(feature = "profiling")]
+    fn record_timing(&self, atom_id: AtomId, elapsed: Duration) {
+        let mut timings = self.timings.lock();
+        let timing = timings.entry(atom_id).or_default();
+        timing.count += 1;
+        timing.total += elapsed;
+        timing.max = timing.max.max(elapsed);
+    }
+
+    /// Length of the longest dependency chain below an atom
+    ///
+    /// Counts edges from `atom_id` down to a leaf (an atom with no recorded
+    /// dependencies) along `AtomState` dependencies: a primitive atom has
+    /// depth 0, an atom derived from it depth 1, and so on. Atoms never
+    /// computed in this store count as leaves. Reports the graph as of the
+    /// last computations; it does not recompute anything.
+    ///
+    /// Useful for spotting overly deep derivation chains.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// assert!(store.max_dependency_depth(report.id()) <= 8, "derivation too deep");
+    /// ```
+    pub fn max_dependency_depth(&self, atom_id: AtomId) -> usize {
+        fn depth(
+            store: &Store,
+            atom_id: AtomId,
+            memo: &mut HashMap<AtomId, usize>,
+            visiting: &mut HashSet<AtomId>,
+        ) -> usize {
+            if let Some(&known) = memo.get(&atom_id) {
+                return known;
+            }
+            // A cycle (only possible mid-error) ends the path
+            if !visiting.insert(atom_id) {
+                return 0;
+            }
+            let dependencies: Vec<AtomId> = store
+                .state_of(atom_id)
+                .map(|state| state.read().dependencies().keys().copied().collect())
+                .unwrap_or_default();
+            let result = dependencies
+                .into_iter()
+                .map(|dependency| depth(store, dependency, memo, visiting) + 1)
+                .max()
+                .unwrap_or(0);
+            visiting.remove(&atom_id);
+            memo.insert(atom_id, result);
+            result
+        }
+
+        depth(self, atom_id, &mut HashMap::new(), &mut HashSet::new())
+    }
+
+    /// Each atom's dependencies, as recorded by its last computation
+    ///
+    /// Keys are every atom with state in this store; each list holds the
+    /// IDs the atom read, sorted. Primitive atoms map to an empty list.
+    /// Atoms that were never read are absent, and dependencies reflect the
+    /// last computation, so a stale derived atom may list edges it would
+    /// not read now.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.get(&sum)?;
+    /// assert_eq!(store.dependency_graph()[&sum.id()], vec![a.id(), b.id()]);
+    /// ```
+    pub fn dependency_graph(&self) -> HashMap<AtomId, Vec<AtomId>> {
+        self.atom_states
+            .iter()
+            .map(|entry| {
+                let mut dependencies: Vec<AtomId> = entry
+                    .value()
+                    .read()
+                    .dependencies()
+                    .keys()
+                    .copied()
+                    .collect();
+                dependencies.sort_unstable();
+                (*entry.key(), dependencies)
+            })
+            .collect()
+    }
+
+    /// Render `dependency_graph` as Graphviz DOT
+    ///
+    /// Every atom with state in this store becomes a node labeled like
+    /// `Atom`'s `Display` (`atom{id}` or `atom{id}:{label}`), and every
+    /// recorded dependency becomes an edge from the dependency to the atom
+    /// that read it. Output is sorted by atom ID so it is stable.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// std::fs::write("atoms.dot", store.to_dot())?;
+    /// // dot -Tsvg atoms.dot > atoms.svg
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut graph: Vec<(AtomId, Vec<AtomId>)> = self.dependency_graph().into_iter().collect();
+        graph.sort_unstable();
+
+        let mut dot = String::from("digraph atoms {\n");
+        for (atom_id, _) in &graph {
+            let label = match self
+                .atom_configs
+                .get(atom_id)
+                .and_then(|config| config.debug_label().map(str::to_string))
+            {
+                Some(label) => format!("atom{}:{}", atom_id, label),
+                None => format!("atom{}", atom_id),
+            };
+            dot.push_str(&format!(
+                "    atom{} [label=\"{}\"];\n",
+                atom_id,
+                label.replace('\\', "\\\\").replace('"', "\\\"")
+            ));
+        }
+        for (atom_id, dependencies) in &graph {
+            for dependency in dependencies {
+                dot.push_str(&format!("    atom{} -> atom{};\n", dependency, atom_id));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Subscription topology: every mounted atom with its edges, by ID
+    ///
+    /// A structured counterpart to `Mounted`'s `Debug` output, for spotting
+    /// subscription leaks (atoms that stay mounted after their subscribers
+    /// are gone). Each entry is read under its own lock, so the result is
+    /// only consistent while no other thread subscribes or unsubscribes.
+    pub fn mounted_topology(&self) -> Vec<MountedInfo> {
+        let mut topology: Vec<MountedInfo> = self
+            .mounted
+            .iter()
+            .map(|entry| {
+                let mounted = entry.value().read();
+                let mut dependencies: Vec<AtomId> = mounted.dependencies.iter().copied().collect();
+                let mut dependents: Vec<AtomId> = mounted.dependents.iter().copied().collect();
+                dependencies.sort_unstable();
+                dependents.sort_unstable();
+                MountedInfo {
+                    atom_id: *entry.key(),
+                    listener_count: mounted.listeners.len(),
+                    dependencies,
+                    dependents,
+                    has_cleanup: mounted.cleanup.is_some(),
+                }
+            })
+            .collect();
+        topology.sort_unstable_by_key(|info| info.atom_id);
+        topology
+    }
+
+    /// Number of listeners subscribed to an atom (0 if it is not mounted)
+    ///
+    /// Counts direct subscriptions only: an atom mounted because a mounted
+    /// dependent reads it has no listeners of its own.
+    pub fn listener_count(&self, atom_id: AtomId) -> usize {
+        self.mounted_entry(atom_id)
+            .map_or(0, |mounted| mounted.read().listeners.len())
+    }
+
+    /// Drop every listener subscribed to an atom
+    ///
+    /// The atom is then unmounted as if each `Unsubscribe` had been called:
+    /// unless a mounted dependent still reads it, its cleanup runs and its
+    /// dependencies are released. Calling an `Unsubscribe` for one of the
+    /// dropped listeners afterwards does nothing.
+    pub fn unsubscribe_all(&self, atom_id: AtomId) {
+        if let Some(mounted) = self.mounted_entry(atom_id) {
+            mounted.write().listeners.clear();
+        }
+        self.unmount_if_unused(atom_id);
+        self.flush_if_sync();
+    }
+
+    /// Write atom state
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (writeAtomState function)
+    ///
+    /// Runs the atom's write function with a `StoreWriter`, or stores the
+    /// value directly for primitive atoms. Does not flush: writes made by a
+    /// write function are notified together once the outermost set ends.
+    pub(crate) fn write_atom_state<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &WritableAtom<T>,
+        value: T,
+    ) -> Result<()> {
+        self.write_atom_state_with(atom, value, &ValueSetter::new(self))
+    }
+
+    /// `write_atom_state`, recording every changed atom in `setter`
+    ///
+    /// Nested writes of other writable atoms share `setter`, so it collects
+    /// the full fan-out of the write.
+    pub(crate) fn write_atom_state_with<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &WritableAtom<T>,
+        value: T,
+        setter: &ValueSetter<'_>,
+    ) -> Result<()> {
+        self.catch_panic(atom.id(), PanicSite::Write, || {
+            atom.write(&StoreWriter::with_setter(setter.clone(), atom.id()), value)
+        })
+        .unwrap_or_else(|info| Err(AtomError::write_error(info.atom_id, info.message)))
+    }
+
+    /// Invalidate all atoms that depend on the given atom
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (invalidateDependents function)
+    ///
+    /// Uses breadth-first search over the reverse dependency index to mark all
+    /// transitive dependents as invalidated. Values are not recomputed here;
+    /// each invalidated atom recomputes on its next read.
+    ///
+    /// Atoms excluded by a running `set_except` are neither marked nor
+    /// traversed.
+    pub(crate) fn invalidate_dependents(&self, atom_id: AtomId) {
+        let exclusions = self.invalidation_exclusions.read().clone();
+        let mut invalidated = self.invalidated.write();
+        let mut queue = VecDeque::from([atom_id]);
+
+        while let Some(current) = queue.pop_front() {
+            let dependents = match self.dependents.get(&current) {
+                Some(dependents) => dependents.clone(),
+                None => continue,
+            };
+            for dependent in dependents {
+                if !exclusions.contains(&dependent) && invalidated.insert(dependent) {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    /// Recompute all invalidated atoms in topological order
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (recomputeInvalidatedAtoms function)
+    ///
+    /// Only mounted atoms are recomputed eagerly; unmounted ones stay
+    /// invalidated until their next read. The mounted ones are ordered with a
+    /// `TopologicalSorter` over their recorded dependencies, so each atom is
+    /// recomputed after the invalidated atoms it reads. Every recomputed atom
+    /// whose epoch moved is marked as changed and returned, in that order.
+    pub(crate) fn recompute_invalidated(&self) -> Result<Vec<AtomId>> {
+        let mut pending: Vec<AtomId> = self
+            .invalidated
+            .read()
+            .iter()
+            .filter(|atom_id| self.mounted.contains_key(atom_id))
+            .copied()
+            .collect();
+        pending.sort_unstable();
+
+        let dependencies = pending
+            .iter()
+            .map(|&atom_id| {
+                let dependencies = self
+                    .state_of(atom_id)
+                    .map(|state| state.read().dependencies().keys().copied().collect())
+                    .unwrap_or_default();
+                (atom_id, dependencies)
+            })
+            .collect();
+        let sorter = TopologicalSorter {
+            atoms: pending,
+            dependencies,
+        };
+        // A cycle surfaces as a read error; reads stay dependency-first anyway
+        let order = sorter.sort().unwrap_or(sorter.atoms);
+
+        let mut recomputed = Vec::new();
+        for atom_id in order {
+            let config = self
+                .atom_configs
+                .get(&atom_id)
+                .map(|entry| entry.value().clone());
+            if let Some(config) = config {
+                let epoch = self.epoch_of(atom_id);
+                // Read errors are cached in the atom state
+                let _ = config.read_in(self);
+                if self.epoch_of(atom_id) != epoch {
+                    recomputed.push(atom_id);
+                }
+            }
+        }
+
+        self.changed.write().extend(recomputed.iter().copied());
+        Ok(recomputed)
+    }
+
+    /// Recompute invalidated mounted atoms now, without notifying anyone
+    ///
+    /// For callers running their own scheduler (typically with
+    /// `FlushMode::Manual`): writes accumulate invalidations, and this
+    /// recomputes every affected mounted atom once, in dependency order.
+    /// Returns the atoms whose value changed. Listeners and mount callbacks
+    /// are left for the next `flush`, which reports these atoms as changed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.set_flush_mode(FlushMode::Manual);
+    /// store.set(&x, 1)?;
+    /// store.set(&y, 2)?;
+    /// let changed = store.flush_recompute()?;
+    /// scheduler.schedule_render(changed);
+    /// ```
+    pub fn flush_recompute(&self) -> Result<Vec<AtomId>> {
+        self.recompute_invalidated()
+    }
+
+    /// Flush pending callbacks (mount, unmount, listeners)
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (flushCallbacks function)
+    ///
+    /// Loops until no more changes occur: recomputes mounted invalidated
+    /// atoms, calls the listeners of changed atoms, then runs queued unmount
+    /// and mount callbacks. Listeners are called without any store lock held,
+    /// so they may read or write the store.
+    pub(crate) fn flush_callbacks(&self) {
+        let thread = std::thread::current().id();
+        if !self.flushing.lock().insert(thread) {
+            return;
+        }
+        let _guard = FlushGuard {
+            flushing: &self.flushing,
+            thread,
+        };
+        #[cfg(debug_assertions)]
+        let mut notified = Vec::new();
+
+        loop {
+            let _ = self.recompute_invalidated();
+
+            let changed = std::mem::take(&mut *self.changed.write());
+            let unmount_callbacks = std::mem::take(&mut *self.unmount_callbacks.lock());
+            let mount_callbacks = std::mem::take(&mut *self.mount_callbacks.lock());
+            if changed.is_empty() && unmount_callbacks.is_empty() && mount_callbacks.is_empty() {
+                break;
+            }
+
+            for atom_id in self.notification_order(changed) {
+                let Some(mounted) = self.mounted_entry(atom_id) else {
+                    continue;
+                };
+                #[cfg(debug_assertions)]
+                notified.push(atom_id);
+                Mounted::notify_listeners(&mounted, |listener| {
+                    // A caught panic has been reported; keep notifying
+                    let _ = self.catch_panic(atom_id, PanicSite::Listener, || listener());
+                });
+            }
+            for callback in unmount_callbacks {
+                callback();
+            }
+            for callback in mount_callbacks {
+                callback();
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        if !notified.is_empty() {
+            *self.last_flush_order.lock() = notified;
+        }
+        self.end_flush_scope();
+    }
+
+    /// Order changed atoms so mounted dependencies come before their dependents
+    ///
+    /// Depth-first over the mounted dependency edges between changed atoms,
+    /// starting from the lowest ID, so the order is deterministic.
+    fn notification_order(&self, changed: HashSet<AtomId>) -> Vec<AtomId> {
+        fn visit(
+            store: &Store,
+            atom_id: AtomId,
+            changed: &HashSet<AtomId>,
+            visited: &mut HashSet<AtomId>,
+            order: &mut Vec<AtomId>,
+        ) {
+            if !visited.insert(atom_id) {
+                return;
+            }
+            if let Some(mounted) = store.mounted_entry(atom_id) {
+                let mut dependencies: Vec<AtomId> = mounted
+                    .read()
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| changed.contains(dependency))
+                    .copied()
+                    .collect();
+                dependencies.sort_unstable();
+                for dependency in dependencies {
+                    visit(store, dependency, changed, visited, order);
+                }
+            }
+            order.push(atom_id);
+        }
+
+        let mut roots: Vec<AtomId> = changed.iter().copied().collect();
+        roots.sort_unstable();
+        let mut visited = HashSet::new();
+        let mut order = Vec::with_capacity(roots.len());
+        for atom_id in roots {
+            visit(self, atom_id, &changed, &mut visited, &mut order);
+        }
+        order
+    }
+
+    /// Mounted atoms notified by the most recent flush, in notification order
+    ///
+    /// A debugging and testing hook for checking that dependencies are
+    /// notified before their dependents. Flushes that notify nothing leave
+    /// the previous order in place. Only recorded in debug builds.
+    #[cfg(debug_assertions)]
+    pub fn last_flush_order(&self) -> Vec<AtomId> {
+        self.last_flush_order.lock().clone()
+    }
+
+    /// Choose when `set`, `sub` and unsubscribe flush
+    ///
+    /// In `FlushMode::Manual`, changes still take effect immediately (reads
+    /// see new values), but mounted atoms are not recomputed, listeners are
+    /// not called and mount/unmount callbacks do not run until `flush()`.
+    /// This lets a host framework batch notifications on its own schedule.
+    pub fn set_flush_mode(&self, mode: FlushMode) {
+        *self.flush_mode.write() = mode;
+    }
+
+    /// The current flush mode
+    pub fn flush_mode(&self) -> FlushMode {
+        *self.flush_mode.read()
+    }
+
+    /// Run all pending recomputations, listeners and lifecycle callbacks
+    ///
+    /// Needed in `FlushMode::Manual`; harmless (a no-op when nothing is
+    /// pending) in `FlushMode::Sync`.
+    pub fn flush(&self) {
+        self.flush_callbacks();
+    }
+
+    /// Flush now unless the store is in manual flush mode or this thread is
+    /// inside a batch
+    fn flush_if_sync(&self) {
+        if self.flush_mode() == FlushMode::Sync && !self.in_batch() {
+            self.flush_callbacks();
+        }
+    }
+
+    /// Run `f` with flushing deferred, then report what changed
+    ///
+    /// Writes inside `f` take effect immediately, but mounted dependents are
+    /// not recomputed and listeners are not called until `f` returns, so
+    /// each listener runs at most once for the whole batch. Returns the IDs
+    /// (sorted) of every atom that changed: the atoms written plus the
+    /// mounted derived atoms whose values changed as a result. Unmounted
+    /// derived atoms are only invalidated and recompute on their next read,
+    /// so they are not reported.
+    ///
+    /// Batches nest; only the outermost one flushes. Flushing is deferred
+    /// only on the thread running the batch: writes from other threads
+    /// flush as usual, and such a flush also delivers the batch's writes
+    /// made so far. In `FlushMode::Manual` the batch does not flush at all, and the
+    /// result also includes changes still pending from before the batch.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let changed = store.batch(|| {
+    ///     store.set(&first_name, "Ada".into()).unwrap();
+    ///     store.set(&last_name, "Lovelace".into()).unwrap();
+    /// });
+    /// log::debug!("batch changed {:?}", changed);
+    /// ```
+    pub fn batch<F>(&self, f: F) -> Vec<AtomId>
+    where
+        F: FnOnce(),
+    {
+        {
+            let thread = std::thread::current().id();
+            *self.batch_depth.lock().entry(thread).or_insert(0) += 1;
+            let _guard = BatchGuard {
+                depths: &self.batch_depth,
+                thread,
+            };
+            f();
+        }
+
+        // Cascade into mounted dependents so they show up in `changed`
+        let _ = self.recompute_invalidated();
+        let mut changed: Vec<AtomId> = self.changed.read().iter().copied().collect();
+        changed.sort_unstable();
+
+        self.flush_if_sync();
+        self.end_flush_scope();
+        changed
+    }
+
+    /// Run `f` as a transaction: if it fails, undo every write it made
+    ///
+    /// `f` runs inside a `batch`, so listeners are notified once at the end.
+    /// If it returns `Err`, every atom written during the transaction gets
+    /// back the value (or error, or absence of a value) it had before its
+    /// first write, and the error is returned. Restoring is itself a write:
+    /// epochs move forward, so dependents recompute from the restored
+    /// values.
+    ///
+    /// Only written atoms are snapshotted, when first written. Transactions
+    /// nest: an inner one that fails undoes only its own writes, one that
+    /// succeeds hands them to the outer one. Only writes made on the
+    /// transaction's own thread are recorded; a failing transaction leaves
+    /// other threads' writes alone.
+    ///
+    /// **FP Pattern**: All-or-nothing state transition
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.transaction(|store| {
+    ///     store.set(&from, balance_from - amount)?;
+    ///     store.set(&to, balance_to + amount)?;
+    ///     check_limits(store)
+    /// })?;
+    /// ```
+    pub fn transaction<R, F>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&Store) -> Result<R>,
+    {
+        let thread = std::thread::current().id();
+        let mut result = None;
+        self.batch(|| {
+            self.transactions
+                .lock()
+                .entry(thread)
+                .or_default()
+                .push(HashMap::new());
+            let outcome = f(self);
+            let frame = {
+                let mut transactions = self.transactions.lock();
+                let frames = transactions.entry(thread).or_default();
+                let frame = frames.pop().unwrap_or_default();
+                if frames.is_empty() {
+                    transactions.remove(&thread);
+                }
+                frame
+            };
+
+            if outcome.is_ok() {
+                let mut transactions = self.transactions.lock();
+                if let Some(outer) = transactions
+                    .get_mut(&thread)
+                    .and_then(|frames| frames.last_mut())
+                {
+                    for (atom_id, rollback) in frame {
+                        outer.entry(atom_id).or_insert(rollback);
+                    }
+                }
+            } else {
+                for rollback in frame.into_values() {
+                    rollback(self);
+                }
+            }
+            result = Some(outcome);
+        });
+        result.expect("batch runs its closure")
+    }
+
+    /// Remember an atom's current value for this thread's innermost transaction
+    ///
+    /// Only the first write in a transaction is recorded.
+    fn record_rollback<T: Clone + Send + Sync + 'static>(&self, atom_id: AtomId) {
+        let mut transactions = self.transactions.lock();
+        let Some(frame) = transactions
+            .get_mut(&std::thread::current().id())
+            .and_then(|frames| frames.last_mut())
+        else {
+            return;
+        };
+        if frame.contains_key(&atom_id) {
+            return;
+        }
+        let previous = self.state_of(atom_id).and_then(|state| {
+            state
+                .read()
+                .downcast_ref::<AtomState<T>>()
+                .and_then(|state| state.value.clone())
+        });
+        frame.insert(
+            atom_id,
+            Box::new(move |store: &Store| store.restore_value(atom_id, previous)),
+        );
+    }
+
+    /// Put back a value recorded by `record_rollback`, as a new change
+    fn restore_value<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom_id: AtomId,
+        previous: Option<Result<T>>,
+    ) {
+        let state_arc = self.ensure_atom_state::<T>(atom_id);
+        if let Some(state) = state_arc.write().downcast_mut::<AtomState<T>>() {
+            match previous {
+                Some(Ok(value)) => state.set_value(value),
+                Some(Err(error)) => state.set_error(error),
+                None => {
+                    state.value = None;
+                    state.value_hash = None;
+                    state.epoch = state.epoch.wrapping_add(1);
+                }
+            }
+        }
+        self.record_change(atom_id);
+        #[cfg(feature = "hot")]
+        self.publish_hot::<T>(atom_id);
+
+        self.changed.write().insert(atom_id);
+        self.invalidate_dependents(atom_id);
+    }
+
+    /// Register `T` as a value type that `snapshot` captures
+    ///
+    /// Values are encoded with `ToString` and decoded with `FromStr`.
+    /// Registering again for the same type replaces the previous codec.
+    ///
+    /// ```rust,ignore
+    /// store.register_snapshot_type::<i32>();
+    /// store.register_snapshot_type::<String>();
+    /// ```
+    pub fn register_snapshot_type<T>(&self)
+    where
+        T: ToString + FromStr + Clone + Send + Sync + 'static,
+        T::Err: std::fmt::Display,
+    {
+        let type_name = std::any::type_name::<T>();
+        let codec = SnapshotCodec {
+            type_name,
+            encode: Box::new(|value| value.downcast_ref::<T>().map(T::to_string)),
+            decode: Box::new(move |atom_id, raw| {
+                let value = raw.parse::<T>().map_err(|error| {
+                    AtomError::read_error(atom_id, format!("{}: {}", type_name, error))
+                })?;
+                Ok(Box::new(move |store: &Store| {
+                    store.restore_value(atom_id, Some(Ok(value)))
+                }))
+            }),
+        };
+        self.snapshot_codecs
+            .write()
+            .insert(TypeId::of::<T>(), Arc::new(codec));
+    }
+
+    /// Capture the values of this store's primitive atoms
+    ///
+    /// Reference: `useAtomsSnapshot` in `jotai-devtools`
+    ///
+    /// An atom is included when it holds a value (not an error), is
+    /// writable, has no recorded dependencies (primitive atoms, and writable
+    /// atoms that read nothing) and its value type was registered with
+    /// `register_snapshot_type`. Everything else is skipped: derived atoms
+    /// are recomputed from the restored values anyway.
+    ///
+    /// Entries are keyed by atom ID. IDs are assigned in creation order, so
+    /// a snapshot only maps back onto the right atoms in a program that
+    /// creates its atoms in the same order.
+    ///
+    /// **FP Pattern**: State as serializable data
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// store.register_snapshot_type::<i32>();
+    /// let saved = store.snapshot();
+    /// // ...
+    /// store.restore(&saved)?;
+    /// ```
+    pub fn snapshot(&self) -> StoreSnapshot {
+        let codecs = self.snapshot_codecs.read();
+        let mut snapshot = StoreSnapshot::default();
+        for entry in self.atom_states.iter() {
+            let state = entry.value().read();
+            if !state.dependencies().is_empty() || self.is_read_only(*entry.key()) {
+                continue;
+            }
+            let Some(codec) = codecs.get(&state.value_type_id()) else {
+                continue;
+            };
+            let Some(value) = state
+                .value_erased()
+                .and_then(|value| (codec.encode)(&*value))
+            else {
+                continue;
+            };
+            snapshot.entries.insert(
+                *entry.key(),
+                SnapshotEntry {
+                    type_name: codec.type_name.to_string(),
+                    value,
+                },
+            );
+        }
+        snapshot
+    }
+
+    /// Reinstall the values captured by `snapshot`
+    ///
+    /// Every entry is decoded before anything is written, so a snapshot
+    /// that fails to decode leaves the store untouched: an unregistered
+    /// type name is reported as `AtomError::StoreError`, an unparsable
+    /// value as `AtomError::ReadError` and an entry for a known read-only
+    /// derived atom as `AtomError::NotWritable`. The values are then written in one
+    /// `batch`, each as a new epoch, so dependents recompute and listeners
+    /// are notified once. Atoms not in the snapshot keep their values.
+    pub fn restore(&self, snapshot: &StoreSnapshot) -> Result<()> {
+        let installs = {
+            let codecs = self.snapshot_codecs.read();
+            snapshot
+                .entries
+                .iter()
+                .map(|(&atom_id, entry)| {
+                    if self.is_read_only(atom_id) {
+                        return Err(AtomError::NotWritable { atom_id });
+                    }
+                    let codec = codecs
+                        .values()
+                        .find(|codec| codec.type_name == entry.type_name)
+                        .ok_or_else(|| AtomError::StoreError {
+                            message: format!("snapshot type {} is not registered", entry.type_name),
+                        })?;
+                    (codec.decode)(atom_id, &entry.value)
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        self.batch(|| {
+            for install in installs {
+                install(self);
+            }
+        });
+        Ok(())
+    }
+
+    /// Whether the store knows the atom as a read-only derived atom
+    fn is_read_only(&self, atom_id: AtomId) -> bool {
+        self.atom_configs
+            .get(&atom_id)
+            .is_some_and(|config| !config.is_writable())
+    }
+
+    /// Start a list of writes to atoms of any type, applied together
+    ///
+    /// Queue writes with `WriteList::set`, then `commit` applies them in
+    /// order inside one `batch`, so listeners are notified once.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut tx = store.writes();
+    /// tx.set(&count, 1);
+    /// tx.set(&name, "x".to_string());
+    /// tx.commit()?;
+    /// ```
+    pub fn writes(&self) -> WriteList<'_> {
+        WriteList {
+            store: self,
+            writes: Vec::new(),
+        }
+    }
+
+    /// Look up an atom's Mounted entry
+    fn mounted_entry(&self, atom_id: AtomId) -> Option<Arc<RwLock<Mounted>>> {
+        self.mounted
+            .get(&atom_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Mount an atom (add to mounted map)
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (mountAtom function)
+    ///
+    /// Computes the atom so its dependencies are known, mounts it along with
+    /// its dependencies, and registers the listener. Returns the listener ID
+    /// to pass to `unmount_atom`.
+    ///
+    /// Newly mounted atoms with an onMount callback have it queued for the
+    /// next flush.
+    pub(crate) fn mount_atom<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &Atom<T>,
+        listener: Listener,
+    ) -> ListenerId {
+        // A read error is cached; the subscription still works
+        let _ = self.read_atom_state(atom);
+        self.register_config(atom);
+        self.mount_by_id(atom.id);
+
+        self.mounted_entry(atom.id)
+            .expect("mount_by_id creates the entry")
+            .write()
+            .add_listener(listener)
+    }
+
+    /// Mount an atom and, recursively, the atoms it depends on
+    fn mount_by_id(&self, atom_id: AtomId) {
+        let dependencies: Vec<AtomId> = self
+            .state_of(atom_id)
+            .map(|state| state.read().dependencies().keys().copied().collect())
+            .unwrap_or_default();
+
+        match self.mounted.entry(atom_id) {
+            dashmap::mapref::entry::Entry::Occupied(_) => return,
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                let mut mounted = Mounted::new();
+                for dependency in &dependencies {
+                    mounted.add_dependency(*dependency);
+                }
+                entry.insert(Arc::new(RwLock::new(mounted)));
+            }
+        }
+        self.queue_on_mount(atom_id);
+
+        for dependency in dependencies {
+            self.mount_by_id(dependency);
+            if let Some(mounted) = self.mounted_entry(dependency) {
+                mounted.write().add_dependent(atom_id);
+            }
+        }
+    }
+
+    /// Queue a freshly mounted atom's onMount callback for the next flush
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (mountAtom queuing onMount)
+    ///
+    /// The returned cleanup is kept on the `Mounted` entry. If the atom was
+    /// unmounted again before the flush, the cleanup runs right away.
+    fn queue_on_mount(&self, atom_id: AtomId) {
+        let Some(on_mount) = self
+            .atom_configs
+            .get(&atom_id)
+            .and_then(|config| config.on_mount())
+        else {
+            return;
+        };
+
+        let store = self.handle();
+        self.mount_callbacks.lock().push(Box::new(move || {
+            let Some(cleanup) = on_mount(&StoreWriter::detached(&store)) else {
+                return;
+            };
+            match store.mounted_entry(atom_id) {
+                Some(mounted) => mounted.write().cleanup = Some(cleanup),
+                None => cleanup(),
+            }
+        }));
+    }
+
+    /// Sync a mounted atom's dependencies after it was recomputed
+    ///
+    /// Newly read atoms get mounted; atoms no longer read are released and
+    /// unmounted if nothing else needs them.
+    fn remount_dependencies(&self, atom_id: AtomId, current: &HashMap<AtomId, EpochNumber>) {
+        let Some(mounted) = self.mounted_entry(atom_id) else {
+            return;
+        };
+        let previous = std::mem::replace(
+            &mut mounted.write().dependencies,
+            current.keys().copied().collect(),
+        );
+
+        for dependency in current.keys().filter(|dep| !previous.contains(dep)) {
+            self.mount_by_id(*dependency);
+            if let Some(dependency) = self.mounted_entry(*dependency) {
+                dependency.write().add_dependent(atom_id);
+            }
+        }
+        for dependency in previous.iter().filter(|dep| !current.contains_key(dep)) {
+            self.release_dependency(*dependency, atom_id);
+        }
+    }
+
+    /// Drop a dependent edge from a mounted dependency, unmounting it if unused
+    fn release_dependency(&self, dependency: AtomId, dependent: AtomId) {
+        if let Some(mounted) = self.mounted_entry(dependency) {
+            mounted.write().remove_dependent(&dependent);
+        }
+        self.unmount_if_unused(dependency);
+    }
+
+    /// Unmount an atom (remove from mounted map)
+    ///
+    /// Reference: `jotai/src/vanilla/internals.ts` (unmountAtom function)
+    ///
+    /// Removes the listener; once the atom has neither listeners nor mounted
+    /// dependents it is unmounted, its cleanup is queued, and its own
+    /// dependencies are released in turn.
+    pub(crate) fn unmount_atom(&self, atom_id: AtomId, listener_id: ListenerId) {
+        if let Some(mounted) = self.mounted_entry(atom_id) {
+            mounted.write().remove_listener(listener_id);
+        }
+        self.unmount_if_unused(atom_id);
+    }
+
+    /// Unmount an atom regardless of its listeners and mounted dependents
+    ///
+    /// Mounted dependents forget the edge (they are expected to be
+    /// invalidated by the caller); the atom's own dependencies are released.
+    fn force_unmount(&self, atom_id: AtomId) {
+        let Some((_, mounted)) = self.mounted.remove(&atom_id) else {
+            return;
+        };
+
+        let (dependencies, dependents, cleanup) = {
+            let mut mounted = mounted.write();
+            (
+                std::mem::take(&mut mounted.dependencies),
+                std::mem::take(&mut mounted.dependents),
+                mounted.take_cleanup(),
+            )
+        };
+        if let Some(cleanup) = cleanup {
+            self.unmount_callbacks.lock().push(Box::new(cleanup));
+        }
+
+        for dependent in dependents {
+            if let Some(dependent) = self.mounted_entry(dependent) {
+                dependent.write().dependencies.remove(&atom_id);
+            }
+        }
+        for dependency in dependencies {
+            self.release_dependency(dependency, atom_id);
+        }
+    }
+
+    /// Unmount an atom if no listener or mounted dependent needs it
+    fn unmount_if_unused(&self, atom_id: AtomId) {
+        let Some((_, mounted)) = self
+            .mounted
+            .remove_if(&atom_id, |_, mounted| !mounted.read().is_in_use())
+        else {
+            return;
+        };
+
+        let (dependencies, cleanup) = {
+            let mut mounted = mounted.write();
+            (
+                std::mem::take(&mut mounted.dependencies),
+                mounted.take_cleanup(),
+            )
+        };
+        if let Some(cleanup) = cleanup {
+            self.unmount_callbacks.lock().push(Box::new(cleanup));
+        }
+
+        let drop_value = self
+            .atom_configs
+            .get(&atom_id)
+            .is_some_and(|config| config.cache_policy() == CachePolicy::CacheWhileMounted)
+            && !self.is_materialized(atom_id);
+        if drop_value {
+            if let Some(state) = self.state_of(atom_id) {
+                state.write().clear_value();
+            }
+        }
+
+        for dependency in dependencies {
+            self.release_dependency(dependency, atom_id);
+        }
+    }
+}
+
+/// Typed write access handed to atom write functions
+///
+/// Reference: `jotai/src/vanilla/internals.ts` (the `get`/`set` passed to `writeAtomState`)
+///
+/// Unlike `&dyn Setter`, this is a concrete type, so its methods can be
+/// generic over the atom's value type. Writes go through the same path as
+/// `Store::set` (middleware, epochs, invalidation) but listeners are only
+/// notified after the outermost write function returns.
+///
+/// **FP Pattern**: Capability object scoped to one write
+pub struct StoreWriter<'a> {
+    store: &'a Store,
+
+    /// Atom whose write function is running; setting it stores the value
+    writing: Option<AtomId>,
+
+    /// Stores values and records which atoms changed
+    setter: ValueSetter<'a>,
+}
+
+impl<'a> StoreWriter<'a> {
+    pub(crate) fn new(store: &'a Store, writing: AtomId) -> Self {
+        Self::with_setter(ValueSetter::new(store), writing)
+    }
+
+    /// A writer recording its changes into an existing `ValueSetter`
+    pub(crate) fn with_setter(setter: ValueSetter<'a>, writing: AtomId) -> Self {
+        StoreWriter {
+            store: setter.store,
+            writing: Some(writing),
+            setter,
+        }
+    }
+
+    /// A writer outside any write function, e.g. for onMount callbacks
+    ///
+    /// Every `set` runs the target's write function.
+    pub(crate) fn detached(store: &'a Store) -> Self {
+        StoreWriter {
+            store,
+            writing: None,
+            setter: ValueSetter::new(store),
+        }
+    }
+
+    /// Read an atom's current value
+    pub fn get<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>) -> Result<T> {
+        self.store.get(atom)
+    }
+
+    /// Write an atom
+    ///
+    /// Runs the target's own write function, except for the atom being
+    /// written, whose value is stored directly (like Jotai's `set(self, ...)`).
+    pub fn set<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &WritableAtom<T>,
+        value: T,
+    ) -> Result<()> {
+        if Some(atom.id()) == self.writing {
+            self.setter.set(atom.as_atom(), value)
+        } else {
+            self.store.write_atom_state_with(atom, value, &self.setter)
+        }
+    }
+
+    /// Current epoch of an atom, if it has state
+    pub(crate) fn epoch_of(&self, atom_id: AtomId) -> Option<EpochNumber> {
+        self.store.epoch_of(atom_id)
+    }
+
+    /// Write an atom with a function of its current value
+    pub fn update<T, F>(&self, atom: &WritableAtom<T>, f: F) -> Result<()>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(T) -> T,
+    {
+        let current = self.get(atom.as_atom())?;
+        self.set(atom, f(current))
+    }
+
+    /// Write the value the atom's read function produces
+    ///
+    /// For a primitive atom, that is its initial value.
+    pub fn reset<T: Clone + Send + Sync + 'static>(&self, atom: &WritableAtom<T>) -> Result<()> {
+        let initial = atom.as_atom().read(self.store)?;
+        self.set(atom, initial)
+    }
+}
+
+impl std::fmt::Debug for StoreWriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreWriter")
+            .field("writing", &self.writing)
+            .finish()
+    }
+}
+
+/// A queued write, with its atom's value type erased
+type QueuedWrite = Box<dyn FnOnce(&Store) -> Result<()> + Send>;
+
+/// Writes to atoms of different types, committed with a single flush
+///
+/// Created by `Store::writes`. Nothing is written until `commit`; dropping
+/// the list discards the queued writes.
+///
+/// **Rust Pattern**: Type erasure via boxed closures (write thunks)
+pub struct WriteList<'a> {
+    store: &'a Store,
+    writes: Vec<QueuedWrite>,
+}
+
+impl WriteList<'_> {
+    /// Queue a write of `value` to `atom`
+    pub fn set<T: Clone + Send + Sync + 'static>(
+        &mut self,
+        atom: &WritableAtom<T>,
+        value: T,
+    ) -> &mut Self {
+        let atom = atom.clone();
+        self.writes
+            .push(Box::new(move |store: &Store| store.set(&atom, value)));
+        self
+    }
+
+    /// Number of queued writes
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Whether no writes are queued
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Apply the queued writes in order, then flush once
+    ///
+    /// Stops at the first failing write and returns its error. Writes
+    /// applied before it are kept (there is no rollback), and listeners are
+    /// still notified of them.
+    pub fn commit(self) -> Result<()> {
+        let store = self.store;
+        let mut result = Ok(());
+        store.batch(|| {
+            result = self.writes.into_iter().try_for_each(|write| write(store));
+        });
+        result
+    }
+}
+
+impl std::fmt::Debug for WriteList<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteList")
+            .field("queued", &self.writes.len())
+            .finish()
+    }
+}
+
+/// Call the first middleware with a continuation running the rest
+fn run_middleware(
+    chain: &[Middleware],
+    ctx: SetContext,
+    write: &dyn Fn(SetContext) -> Result<()>,
+) -> Result<()> {
+    match chain.split_first() {
+        Some((first, rest)) => first(ctx, &|ctx| run_middleware(rest, ctx, write)),
+        None => write(ctx),
+    }
+}
+
+/// Undo entry of a transaction, restoring one atom's previous value
+type Rollback = Box<dyn FnOnce(&Store) + Send>;
+
+/// Undo entries of one transaction, keyed by the atom they restore
+type UndoFrame = HashMap<AtomId, Rollback>;
+
+/// Encoder and decoder of one value type registered for snapshots
+pub(crate) struct SnapshotCodec {
+    /// `std::any::type_name` of the value type
+    type_name: &'static str,
+
+    /// Encode a value, given as `&T` behind `dyn Any`
+    encode: SnapshotEncode,
+
+    /// Decode a value for an atom into a write installing it
+    decode: SnapshotDecode,
+}
+
+/// Ring buffer of recent atom changes (see `Store::enable_history`)
+#[derive(Clone)]
+pub(crate) struct ChangeLog {
+    /// Maximum number of events kept
+    capacity: usize,
+
+    /// Recorded events, oldest first
+    events: VecDeque<ChangeEvent>,
+}
+
+impl ChangeLog {
+    /// Append an event, dropping the oldest one when full
+    fn push(&mut self, event: ChangeEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Encoder of a snapshot value type, `None` if the value is not a `T`
+type SnapshotEncode = Box<dyn Fn(&dyn Any) -> Option<String> + Send + Sync>;
+
+/// Decoder of a snapshot value type, for a given atom
+type SnapshotDecode = Box<dyn Fn(AtomId, &str) -> Result<SnapshotInstall> + Send + Sync>;
+
+/// Write of one decoded snapshot value, run by `Store::restore`
+type SnapshotInstall = Box<dyn FnOnce(&Store) + Send>;
+
+/// Aborts a signal when dropped, unless disarmed by clearing it
+#[cfg(feature = "async")]
+struct AbortOnDrop(Option<AbortSignal>);
+
+#[cfg(feature = "async")]
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(signal) = self.0.take() {
+            signal.abort();
+        }
+    }
+}
+
+/// An async atom evaluation that several `get_async` calls can await
+#[cfg(feature = "async")]
+type SharedEvaluation<T> = Shared<BoxFuture<'static, Result<T>>>;
+
+/// Bookkeeping for an in-flight async evaluation, with its value type erased
+#[cfg(feature = "async")]
+pub(crate) struct InFlight {
+    /// Abort signal of the evaluation
+    signal: AbortSignal,
+
+    /// Epochs of the atoms read before the first await, when it started
+    started: Vec<(AtomId, Option<EpochNumber>)>,
+
+    /// `WeakShared<BoxFuture<'static, Result<T>>>` for the atom's `T`
+    evaluation: Box<dyn Any + Send + Sync>,
+}
+
+/// Removes the current thread from `Store::flushing` when a flush ends,
+/// even if a listener panics
+struct FlushGuard<'a> {
+    flushing: &'a Mutex<HashSet<ThreadId>>,
+    thread: ThreadId,
+}
+
+impl Drop for FlushGuard<'_> {
+    fn drop(&mut self) {
+        self.flushing.lock().remove(&self.thread);
+    }
+}
+
+/// Leaves a `Store::batch` when its closure returns, even if it panics
+struct BatchGuard<'a> {
+    depths: &'a Mutex<HashMap<ThreadId, usize>>,
+    thread: ThreadId,
+}
+
+impl Drop for BatchGuard<'_> {
+    fn drop(&mut self) {
+        let mut depths = self.depths.lock();
+        if let Some(depth) = depths.get_mut(&self.thread) {
+            *depth -= 1;
+            if *depth == 0 {
+                depths.remove(&self.thread);
+            }
+        }
+    }
+}
+
+/// Pops an atom off its thread's read stack when its computation ends
+struct ReadFrame<'a> {
+    stacks: &'a Mutex<HashMap<ThreadId, Vec<AtomId>>>,
+    thread: ThreadId,
+}
+
+impl Drop for ReadFrame<'_> {
+    fn drop(&mut self) {
+        let mut stacks = self.stacks.lock();
+        if let Some(stack) = stacks.get_mut(&self.thread) {
+            stack.pop();
+            if stack.is_empty() {
+                stacks.remove(&self.thread);
+            }
+        }
+    }
+}
+
+/// Per-subscription queue used by `Store::sub_bounded`
+struct BoundedQueue {
+    /// Epochs of queued notifications, oldest first
+    pending: VecDeque<EpochNumber>,
+    capacity: usize,
+    /// Notifications dropped since the last delivery
+    dropped: usize,
+    /// Whether some thread is currently calling the listener
+    delivering: bool,
+}
+
+impl BoundedQueue {
+    fn new(capacity: usize) -> Self {
+        BoundedQueue {
+            pending: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+            delivering: false,
+        }
+    }
+
+    fn push(&mut self, epoch: EpochNumber) {
+        if self.pending.len() == self.capacity {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(epoch);
+    }
+
+    fn pop(&mut self) -> Option<Notification> {
+        let epoch = self.pending.pop_front()?;
+        Some(Notification {
+            epoch,
+            dropped: std::mem::take(&mut self.dropped),
+        })
+    }
+}
+
+/// Read-order index of cached derived atoms (see `Store::set_derived_cache_limit`)
+///
+/// Each read stamps the atom with an increasing tick; `order` maps ticks
+/// back to atoms, so the least recently read atom is the first key.
+#[derive(Default)]
+pub(crate) struct DerivedLru {
+    /// Next read tick to hand out
+    tick: u64,
+
+    /// Last read tick of each cached atom
+    last_read: HashMap<AtomId, u64>,
+
+    /// Atoms ordered by last read (oldest first)
+    order: BTreeMap<u64, AtomId>,
+}
+
+impl DerivedLru {
+    /// Mark an atom as most recently read
+    fn touch(&mut self, atom_id: AtomId) {
+        if let Some(previous) = self.last_read.insert(atom_id, self.tick) {
+            self.order.remove(&previous);
+        }
+        self.order.insert(self.tick, atom_id);
+        self.tick += 1;
+    }
+
+    /// Drop an atom from the index
+    fn forget(&mut self, atom_id: AtomId) {
+        if let Some(previous) = self.last_read.remove(&atom_id) {
+            self.order.remove(&previous);
+        }
+    }
+}
+
+/// Number of worker threads behind `Store::sub_offloaded`
+const OFFLOAD_THREADS: usize = 2;
+
+/// Work item run by an offload worker
+type OffloadJob = Box<dyn FnOnce() + Send>;
+
+/// Worker threads shared by a store's offloaded subscriptions
+///
+/// Workers take jobs from one channel and exit once every sender (the
+/// store's and its subscriptions') is gone.
+pub(crate) struct OffloadPool {
+    jobs: mpsc::Sender<OffloadJob>,
+}
+
+impl OffloadPool {
+    fn new(threads: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<OffloadJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..threads {
+            let receiver = receiver.clone();
+            std::thread::Builder::new()
+                .name(format!("jotai-offload-{}", index))
+                .spawn(move || loop {
+                    // The lock is released before the job runs
+                    let job = receiver.lock().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                })
+                .expect("failed to spawn offload worker");
+        }
+        OffloadPool { jobs }
+    }
+}
+
+/// Per-subscription mailbox used by `Store::sub_offloaded`
+struct OffloadQueue<T> {
+    /// Values not yet delivered, oldest first
+    pending: VecDeque<T>,
+    /// Whether a drain job is queued or running
+    draining: bool,
+    /// Cleared on unsubscribe
+    active: bool,
+}
+
+impl<T> OffloadQueue<T> {
+    fn new() -> Self {
+        OffloadQueue {
+            pending: VecDeque::new(),
+            draining: false,
+            active: true,
+        }
+    }
+
+    /// Deliver queued values in order until the queue is empty
+    fn drain(queue: &Mutex<OffloadQueue<T>>, listener: &dyn Fn(T)) {
+        loop {
+            let next = {
+                let mut queue = queue.lock();
+                match queue.pending.pop_front().filter(|_| queue.active) {
+                    Some(value) => value,
+                    None => {
+                        queue.draining = false;
+                        return;
+                    }
+                }
+            };
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| listener(next)));
+        }
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Implement Getter trait for Store (reads without dependency tracking)
+impl Getter for Store {
+    fn get_erased(&self, atom: &dyn AnyAtom) -> Result<Box<dyn Any + Send + Sync>> {
+        atom.read_in(self)
+    }
+}
+
+// Implement Setter trait for Store
+impl Setter for Store {
+    /// Write an atom, rejecting read-only derived atoms
+    ///
+    /// A `WritableAtom` goes through `Store::set`, so a writable derived
+    /// atom runs its write function. A bare `Atom` has no write function to
+    /// run, and its value is stored directly.
+    fn set<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>, value: T) -> Result<()> {
+        if let Some(writable) = atom.as_writable() {
+            return Store::set(self, writable, value);
+        }
+        let atom = atom.as_atom();
+        if !atom.is_writable() {
+            return Err(AtomError::NotWritable { atom_id: atom.id() });
+        }
+
+        let result = self.apply_set(atom, value);
+        self.flush_if_sync();
+
+        result
+    }
+}
+
+impl std::fmt::Debug for Store {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Store")
+            .field("atom_states_count", &self.atom_states.len())
+            .field("mounted_count", &self.mounted.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_creation() {
+        // Test that Store::new initializes all maps correctly
+        let store = Store::new();
+        assert_eq!(store.atom_states.len(), 0);
+        assert_eq!(store.mounted.len(), 0);
+    }
+
+    // ============================================================================
+    // PHASE 1.3: Store::get() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_get_primitive_atom() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(42);
+
+        // First read should compute and cache the value
+        let value = store.get(&count.as_atom()).expect("Should read atom");
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn test_get_caches_value() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(100);
+
+        // First read
+        let v1 = store.get(&count.as_atom()).unwrap();
+
+        // Second read should return cached value
+        let v2 = store.get(&count.as_atom()).unwrap();
+
+        assert_eq!(v1, v2);
+        assert_eq!(v1, 100);
+
+        // Verify the atom is now in atom_states
+        assert_eq!(store.atom_states.len(), 1);
+    }
+
+    #[test]
+    fn test_get_multiple_atoms() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let c = atom(3);
+
+        assert_eq!(store.get(&a.as_atom()).unwrap(), 1);
+        assert_eq!(store.get(&b.as_atom()).unwrap(), 2);
+        assert_eq!(store.get(&c.as_atom()).unwrap(), 3);
+
+        // All three atoms should be cached
+        assert_eq!(store.atom_states.len(), 3);
+    }
+
+    #[test]
+    fn test_get_different_types() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let num = atom(42);
+        let text = atom("hello".to_string());
+        let flag = atom(true);
+
+        assert_eq!(store.get(&num.as_atom()).unwrap(), 42);
+        assert_eq!(store.get(&text.as_atom()).unwrap(), "hello");
+        assert!(store.get(&flag.as_atom()).unwrap());
+    }
+
+    #[test]
+    fn test_get_with_label() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(5).with_label("counter");
+
+        let value = store.get(&count.as_atom()).unwrap();
+        assert_eq!(value, 5);
+        assert_eq!(count.as_atom().debug_label(), Some("counter"));
+    }
+
+    #[test]
+    fn test_get_and_sub_without_as_atom() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let count = atom(2);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(&source)? * 2));
+
+        // A WritableAtom and a plain Atom, both passed directly
+        assert_eq!(store.get(&count).unwrap(), 2);
+        assert_eq!(store.get(&doubled).unwrap(), 4);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let _unsub = store.sub(&count, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        store.set(&count, 5).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_read_and_sub_variants_without_as_atom() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(2);
+
+        assert_eq!(store.get_or(&count, 0), 2);
+        assert_eq!(store.get_stale_ok(&count).unwrap(), 2);
+        assert_eq!(store.get_result(&count).unwrap().unwrap(), 2);
+        assert_eq!(
+            store
+                .get_with_overrides(&count, &[ErasedOverride::new(&count, 9)])
+                .unwrap(),
+            9
+        );
+
+        let diffs = Arc::new(Mutex::new(Vec::new()));
+        let seen = diffs.clone();
+        let _unsub = store.sub_diff(&count, move |old, new| seen.lock().push((old, new)));
+        store.set(&count, 5).unwrap();
+        assert_eq!(*diffs.lock(), vec![(Some(2), 5)]);
+    }
+
+    // ============================================================================
+    // Store::get_result() Tests
+    // ============================================================================
+
+    fn insert_state<T: Clone + Send + Sync + 'static>(
+        store: &Store,
+        atom_id: AtomId,
+        value: Option<Result<T>>,
+    ) {
+        store.atom_states.insert(
+            atom_id,
+            Arc::new(RwLock::new(Box::new(AtomState {
+                epoch: 1,
+                value,
+                dependencies: HashMap::new(),
+                value_hash: None,
+            }))),
+        );
+    }
+
+    #[test]
+    fn test_get_result_value() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(7);
+
+        let result = store.get_result(count.as_atom()).unwrap();
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    #[test]
+    fn test_get_result_atom_error_is_inner() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        insert_state::<i32>(
+            &store,
+            count.id(),
+            Some(Err(AtomError::read_error(count.id(), "bad input"))),
+        );
+
+        let result = store
+            .get_result(count.as_atom())
+            .expect("store should find the atom");
+        assert!(matches!(result, Err(AtomError::ReadError { .. })));
+    }
+
+    #[test]
+    fn test_get_result_missing_value_is_outer() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        insert_state::<i32>(&store, count.id(), None);
+
+        let result = store.get_result(count.as_atom());
+        assert!(matches!(result, Err(AtomError::Uninitialized { .. })));
+    }
+
+    #[test]
+    fn test_get_result_type_mismatch_is_outer() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        insert_state(&store, count.id(), Some(Ok("not a number".to_string())));
+
+        let result = store.get_result(count.as_atom());
+        assert!(matches!(result, Err(AtomError::TypeMismatch { .. })));
+    }
+
+    // ============================================================================
+    // Store::materialize() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_materialize_freezes_derived_value() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(2);
+
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let source = base.clone();
+        let tripled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 3));
+
+        assert_eq!(store.materialize(&doubled).unwrap(), 4);
+        assert_eq!(store.get(&tripled).unwrap(), 6);
+
+        store.set(&base, 10).unwrap();
+
+        // The materialized atom keeps its snapshot, the other one recomputes
+        assert_eq!(store.get(&doubled).unwrap(), 4);
+        assert_eq!(store.get(&tripled).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_materialize_is_per_store() {
+        use crate::atom::{atom, atom_derived};
+
+        let frozen_store = Store::new();
+        let live_store = Store::new();
+        let base = atom(1);
+
+        let source = base.clone();
+        let plus_one = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+
+        frozen_store.materialize(&plus_one).unwrap();
+        live_store.get(&plus_one).unwrap();
+
+        frozen_store.set(&base, 5).unwrap();
+        live_store.set(&base, 5).unwrap();
+
+        assert_eq!(frozen_store.get(&plus_one).unwrap(), 2);
+        assert_eq!(live_store.get(&plus_one).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_materialize_survives_derived_cache_eviction() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::AtomicUsize;
+
+        let store = Store::new();
+        store.set_derived_cache_limit(1);
+        let base = atom(1);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (source, counter) = (base.clone(), runs.clone());
+        let frozen = atom_derived(move |get| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            get.get(source.as_atom())
+        });
+        let source = base.clone();
+        let other = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = base.clone();
+        let another = atom_derived(move |get| Ok(get.get(source.as_atom())? + 2));
+
+        assert_eq!(store.materialize(&frozen).unwrap(), 1);
+        // Two more derived values: past the limit of one
+        store.get(&other).unwrap();
+        store.get(&another).unwrap();
+        store.set(&base, 5).unwrap();
+
+        assert_eq!(store.get(&frozen).unwrap(), 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    // ============================================================================
+    // Store::track() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_track_reports_atoms_read() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let source = b.clone();
+        let b_doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        let (sum, read) = store.track(|get| {
+            // Reading an atom twice reports it once
+            get.get(a.as_atom()).unwrap()
+                + get.get(b.as_atom()).unwrap()
+                + get.get(a.as_atom()).unwrap()
+        });
+        assert_eq!(sum, 4);
+        assert_eq!(read, vec![a.id(), b.id()]);
+
+        // Only direct reads are reported, not the dependencies of derived atoms
+        let (doubled, read) = store.track(|get| get.get(&b_doubled).unwrap());
+        assert_eq!(doubled, 4);
+        assert_eq!(read, vec![b_doubled.id()]);
+
+        // No atom owns the tracked closure, so it has no previous value
+        let (previous, _) = store.track(|get| get.previous::<i32>());
+        assert_eq!(previous, None);
+    }
+
+    // ============================================================================
+    // Store::set_with() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_set_with_applies_updaters_in_sequence() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+
+        store.set_with(&count, |prev| prev + 1).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 1);
+        let epoch = store.epoch_of(count.id()).unwrap();
+
+        // Each updater is one write: one epoch bump apiece
+        store.set_with(&count, |prev| prev + 1).unwrap();
+        store.set_with(&count, |prev| prev * 10).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 20);
+        assert_eq!(store.epoch_of(count.id()), Some(epoch + 2));
+    }
+
+    #[test]
+    fn test_set_with_propagates_uninitialized() {
+        use crate::atom::atom_writable;
+
+        let store = Store::new();
+        let missing = atom_writable(
+            |_get| -> Result<i32> { Err(AtomError::Uninitialized { atom_id: 0 }) },
+            |_set, _value| Ok(()),
+        );
+
+        let result = store.set_with(&missing, |prev| prev + 1);
+        assert!(matches!(result, Err(AtomError::Uninitialized { .. })));
+    }
+
+    #[test]
+    fn test_set_if_changed_skips_equal_values() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let name = atom("ada".to_string());
+        let source = name.clone();
+        let computations = Arc::new(AtomicUsize::new(0));
+        let counter = computations.clone();
+        let upper = atom_derived(move |get| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(get.get(source.as_atom())?.to_uppercase())
+        });
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let seen = notifications.clone();
+        let _unsub = store.sub(&upper, move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+        let epoch = store.epoch_of(name.id()).unwrap();
+        let computed = computations.load(Ordering::SeqCst);
+
+        // A redundant set changes nothing
+        assert!(!store.set_if_changed(&name, "ada".to_string()).unwrap());
+        assert_eq!(store.epoch_of(name.id()), Some(epoch));
+        assert_eq!(computations.load(Ordering::SeqCst), computed);
+        assert_eq!(notifications.load(Ordering::SeqCst), 0);
+
+        // A real change is a regular set
+        assert!(store.set_if_changed(&name, "grace".to_string()).unwrap());
+        assert_eq!(store.epoch_of(name.id()), Some(epoch + 1));
+        assert_eq!(store.get(&upper).unwrap(), "GRACE");
+        assert_eq!(notifications.load(Ordering::SeqCst), 1);
+    }
+
+    // ============================================================================
+    // Store::update() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_update_mutates_in_place_with_one_epoch_bump() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug)]
+        struct Counter(u64);
+        impl Clone for Counter {
+            fn clone(&self) -> Self {
+                CLONES.fetch_add(1, Ordering::SeqCst);
+                Counter(self.0)
+            }
+        }
+
+        let store = Store::new();
+        let counter = atom(Counter(0));
+        store.get(counter.as_atom()).unwrap();
+
+        let epoch = store.epoch_of(counter.id()).unwrap();
+        let clones = CLONES.load(Ordering::SeqCst);
+        for _ in 0..3 {
+            store.update(&counter, |counter| counter.0 += 1).unwrap();
+        }
+
+        // No clone-read-clone-write round trips, one epoch bump per call
+        assert_eq!(CLONES.load(Ordering::SeqCst), clones);
+        assert_eq!(store.epoch_of(counter.id()), Some(epoch + 3));
+
+        // Dependents are invalidated and listeners notified as after `set`
+        let source = counter.clone();
+        let label = atom_derived(move |get| Ok(format!("{}", get.get(source.as_atom())?.0)));
+        let notified = Arc::new(AtomicUsize::new(0));
+        let sink = notified.clone();
+        let _unsub = store.sub(&label, move || {
+            sink.fetch_add(1, Ordering::SeqCst);
+        });
+        store.update(&counter, |counter| counter.0 += 1).unwrap();
+        assert_eq!(store.get(&label).unwrap(), "4");
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+        // An atom without a value yet goes through read-then-set
+        let fresh = atom(1);
+        store.update(&fresh, |value| *value += 1).unwrap();
+        assert_eq!(store.get(fresh.as_atom()).unwrap(), 2);
+
+        // A cached error also goes through the read, which reports it
+        let failed = atom(1);
+        insert_state::<i32>(
+            &store,
+            failed.id(),
+            Some(Err(AtomError::read_error(failed.id(), "restored"))),
+        );
+        assert!(matches!(
+            store.update(&failed, |value| *value += 1),
+            Err(AtomError::ReadError { .. })
+        ));
+    }
+
+    // ============================================================================
+    // Store::sub() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_sub_fires_once_per_set_and_unmounts() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let count = atom(0);
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let first = calls.clone();
+        let unsub_first = store.sub(count.as_atom(), move || {
+            first.fetch_add(1, Ordering::SeqCst);
+        });
+        let second = calls.clone();
+        let unsub_second = store.sub(count.as_atom(), move || {
+            second.fetch_add(10, Ordering::SeqCst);
+        });
+
+        store.set(&count, 1).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 11);
+
+        // Removing one listener leaves the other registered and the atom mounted
+        unsub_first();
+        store.set(&count, 2).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 21);
+        assert!(store.mounted.contains_key(&count.id()));
+
+        unsub_second();
+        assert!(!store.mounted.contains_key(&count.id()));
+        store.set(&count, 3).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 21);
+    }
+
+    // ============================================================================
+    // Store::sub_immediate() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_sub_immediate_delivers_initial_value() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let count = atom(3);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        let unsub = store.sub_immediate(&doubled, move |value| sink.lock().push(value));
+        assert_eq!(*received.lock(), vec![6]);
+
+        // The derived atom was mounted before the immediate read
+        assert!(store.mounted.contains_key(&count.id()));
+
+        store.set(&count, 5).unwrap();
+        assert_eq!(*received.lock(), vec![6, 10]);
+
+        unsub();
+        store.set(&count, 7).unwrap();
+        assert_eq!(*received.lock(), vec![6, 10]);
+    }
+
+    // ============================================================================
+    // Store::retain() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_retain_keeps_only_matching_labels() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let kept = atom(1).with_label("keep/count");
+        let dropped = atom(10).with_label("page/draft");
+        let unlabelled = atom(100);
+        let source = dropped.clone();
+        let page_view =
+            atom_derived(move |get| Ok(get.get(source.as_atom())? + 1)).with_label("page/view");
+
+        store.set(&kept, 2).unwrap();
+        store.set(&dropped, 20).unwrap();
+        store.get(unlabelled.as_atom()).unwrap();
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = notified.clone();
+        let _unsub = store.sub(&page_view, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store.retain(|_, label| label.is_some_and(|label| label.starts_with("keep")));
+
+        assert!(store.atom_states.contains_key(&kept.id()));
+        for atom_id in [dropped.id(), unlabelled.id(), page_view.id()] {
+            assert!(!store.atom_states.contains_key(&atom_id));
+            assert!(!store.mounted.contains_key(&atom_id));
+        }
+
+        // Dropped atoms start over; the subscription is gone
+        assert_eq!(store.get(kept.as_atom()).unwrap(), 2);
+        assert_eq!(store.get(dropped.as_atom()).unwrap(), 10);
+        store.set(&dropped, 30).unwrap();
+        assert_eq!(store.get(&page_view).unwrap(), 31);
+        assert_eq!(notified.load(Ordering::SeqCst), 0);
+    }
+
+    // ============================================================================
+    // StoreWriter Tests
+    // ============================================================================
+
+    #[test]
+    fn test_writable_atom_writes_several_atoms() {
+        use crate::atom::{atom, atom_writable};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let first = atom("John".to_string());
+        let last = atom("Doe".to_string());
+        let full_name = {
+            let (first_read, last_read) = (first.clone(), last.clone());
+            let (first, last) = (first.clone(), last.clone());
+            atom_writable(
+                move |get| {
+                    Ok(format!(
+                        "{} {}",
+                        get.get(first_read.as_atom())?,
+                        get.get(last_read.as_atom())?
+                    ))
+                },
+                move |writer, value: String| {
+                    let (given, family) = value
+                        .split_once(' ')
+                        .ok_or_else(|| AtomError::write_error(0, "expected two names"))?;
+                    writer.set(&first, given.to_string())?;
+                    writer.set(&last, family.to_string())
+                },
+            )
+        };
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = notified.clone();
+        let _unsub = store.sub(full_name.as_atom(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store.set(&full_name, "Jane Roe".to_string()).unwrap();
+        assert_eq!(store.get(first.as_atom()).unwrap(), "Jane");
+        assert_eq!(store.get(last.as_atom()).unwrap(), "Roe");
+        assert_eq!(store.get(full_name.as_atom()).unwrap(), "Jane Roe");
+        // Both writes are flushed together
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+        assert!(store.set(&full_name, "Cher".to_string()).is_err());
+        assert_eq!(store.get(full_name.as_atom()).unwrap(), "Jane Roe");
+    }
+
+    #[test]
+    fn test_store_writer_update_and_reset() {
+        use crate::atom::{atom, atom_write_only};
+
+        let store = Store::new();
+        let total = atom(10);
+        let target = total.clone();
+        let add = atom_write_only(0, move |writer, amount: i32| {
+            if amount == 0 {
+                writer.reset(&target)
+            } else {
+                writer.update(&target, |total| total + amount)
+            }
+        });
+
+        store.set(&add, 5).unwrap();
+        store.set(&add, 2).unwrap();
+        assert_eq!(store.get(total.as_atom()).unwrap(), 17);
+
+        store.set(&add, 0).unwrap();
+        assert_eq!(store.get(total.as_atom()).unwrap(), 10);
+        // The write-only atom's own value is untouched
+        assert_eq!(store.get(add.as_atom()).unwrap(), 0);
+    }
+
+    // ============================================================================
+    // Store::sub_diff() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_sub_diff_delivers_previous_and_new() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let count = atom(1);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        let _unsub = store.sub_diff(&doubled, move |old, new| sink.lock().push((old, new)));
+        assert!(received.lock().is_empty());
+
+        store.set(&count, 2).unwrap();
+        store.set(&count, 5).unwrap();
+        assert_eq!(*received.lock(), vec![(Some(2), 4), (Some(4), 10)]);
+    }
+
+    #[test]
+    fn test_sub_diff_immediate_starts_without_previous() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let name = atom("a".to_string());
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        let unsub = store.sub_diff_immediate(name.as_atom(), move |old, new| {
+            sink.lock().push((old, new));
+        });
+        store.set(&name, "b".to_string()).unwrap();
+        unsub();
+        store.set(&name, "c".to_string()).unwrap();
+
+        assert_eq!(
+            *received.lock(),
+            vec![
+                (None, "a".to_string()),
+                (Some("a".to_string()), "b".to_string()),
+            ]
+        );
+    }
+
+    // ============================================================================
+    // Store::sub_bounded() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_sub_bounded_delivers_every_change() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let sink = received.clone();
+        let unsub = store.sub_bounded(count.as_atom(), 4, move |notification| {
+            sink.lock().push(notification);
+        });
+        store.set(&count, 1).unwrap();
+        store.set(&count, 2).unwrap();
+        unsub();
+        store.set(&count, 3).unwrap();
+
+        let received = received.lock();
+        assert_eq!(received.len(), 2);
+        assert!(received
+            .iter()
+            .all(|notification| notification.dropped == 0));
+    }
+
+    #[test]
+    fn test_sub_bounded_drops_oldest_on_overflow() {
+        use crate::atom::atom;
+        use std::sync::mpsc;
+
+        let store = Arc::new(Store::new());
+        let count = atom(0);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let entered_tx = Mutex::new(entered_tx);
+        let release_rx = Mutex::new(release_rx);
+
+        let sink = received.clone();
+        let _unsub = store.sub_bounded(count.as_atom(), 2, move |notification| {
+            let first = {
+                let mut received = sink.lock();
+                received.push(notification);
+                received.len() == 1
+            };
+            if first {
+                // Block the first delivery so later changes pile up
+                entered_tx.lock().send(()).unwrap();
+                release_rx.lock().recv().unwrap();
+            }
+        });
+
+        let writer = {
+            let store = store.clone();
+            let count = count.clone();
+            std::thread::spawn(move || store.set(&count, 1).unwrap())
+        };
+        entered_rx.recv().unwrap();
+
+        for value in 2..=6 {
+            store.set(&count, value).unwrap();
+        }
+        release_tx.send(()).unwrap();
+        writer.join().unwrap();
+
+        let received = received.lock();
+        let dropped: Vec<usize> = received.iter().map(|n| n.dropped).collect();
+        assert_eq!(dropped, vec![0, 3, 0]);
+        assert_eq!(
+            received.last().unwrap().epoch,
+            store.epoch_of(count.id()).unwrap()
+        );
+    }
+
+    // ============================================================================
+    // Read-time Cycle Detection Tests
+    // ============================================================================
+
+    #[test]
+    fn test_mutual_dependency_reports_cycle() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let toggle = atom(false);
+        let other: Arc<OnceLock<Atom<i32>>> = Arc::new(OnceLock::new());
+        let (source, late) = (toggle.clone(), other.clone());
+        let a = atom_derived(move |get| {
+            // The cycle only closes while the toggle is on
+            if !get.get(source.as_atom())? {
+                return Ok(0);
+            }
+            Ok(get.get(late.get().expect("wired below"))? + 1)
+        });
+        let source = a.clone();
+        let b = atom_derived(move |get| Ok(get.get(&source)? + 1));
+        let _ = other.set(b.clone());
+
+        assert_eq!(store.get(&b).unwrap(), 1);
+
+        store.set(&toggle, true).unwrap();
+        match store.get(&a) {
+            Err(AtomError::CircularDependency {
+                atom_id,
+                dependency_chain,
+            }) => {
+                assert_eq!(atom_id, a.id());
+                assert_eq!(dependency_chain, vec![a.id(), b.id(), a.id()]);
+            }
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+        assert!(matches!(
+            store.get(&b),
+            Err(AtomError::CircularDependency { .. })
+        ));
+        assert!(store.read_stacks.lock().is_empty());
+
+        // Breaking the cycle recovers both atoms
+        store.set(&toggle, false).unwrap();
+        assert_eq!(store.get(&b).unwrap(), 1);
+    }
+
+    // ============================================================================
+    // Setter writability Tests
+    // ============================================================================
+
+    #[test]
+    fn test_restore_rejects_read_only_derived_atom() {
+        use crate::atom::atom_derived;
+
+        let store = Store::new();
+        store.register_snapshot_type::<i32>();
+        let constant = atom_derived(|_get| Ok(2));
+        assert_eq!(store.get(&constant).unwrap(), 2);
+
+        // No dependencies, but not writable: left out of snapshots
+        assert!(store.snapshot().entries.is_empty());
+
+        let mut forged = StoreSnapshot::default();
+        forged.entries.insert(
+            constant.id(),
+            SnapshotEntry {
+                type_name: std::any::type_name::<i32>().to_string(),
+                value: "10".to_string(),
+            },
+        );
+        assert!(matches!(
+            store.restore(&forged),
+            Err(AtomError::NotWritable { atom_id }) if atom_id == constant.id()
+        ));
+        assert_eq!(store.get(&constant).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_setter_rejects_read_only_derived_atom() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        let result = Setter::set(&store, &doubled, 10);
+        assert!(
+            matches!(result, Err(AtomError::NotWritable { atom_id }) if atom_id == doubled.id())
+        );
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+
+        // Primitive atoms stay writable through the same path
+        Setter::set(&store, base.as_atom(), 5).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_setter_runs_write_fn_of_writable_derived_atom() {
+        use crate::atom::{atom, atom_writable};
+
+        let store = Store::new();
+        let celsius = atom(0);
+        let source = celsius.clone();
+        let target = celsius.clone();
+        let fahrenheit = atom_writable(
+            move |get| Ok(get.get(&source)? * 9 / 5 + 32),
+            move |set, value: i32| set.set(&target, (value - 32) * 5 / 9),
+        );
+
+        Setter::set(&store, &fahrenheit, 212).unwrap();
+        assert_eq!(store.get(&celsius).unwrap(), 100);
+        assert_eq!(store.get(&fahrenheit).unwrap(), 212);
+    }
+
+    // ============================================================================
+    // Store::use_middleware() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_middleware_runs_in_order_and_can_reject() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0).with_label("count");
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let trace = log.clone();
+        store.use_middleware(move |ctx, next| {
+            trace.lock().push(format!("validate {:?}", ctx.label));
+            if ctx.value::<i32>().is_some_and(|value| *value < 0) {
+                return Err(AtomError::write_error(ctx.atom_id, "negative value"));
+            }
+            next(ctx)
+        });
+        let trace = log.clone();
+        store.use_middleware(move |ctx, next| {
+            trace.lock().push(format!("log {:?}", ctx.value::<i32>()));
+            next(ctx)
+        });
+
+        store.set(&count, 5).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 5);
+        assert_eq!(*log.lock(), vec!["validate Some(\"count\")", "log Some(5)"]);
+
+        log.lock().clear();
+        let result = store.set(&count, -1);
+        assert!(matches!(result, Err(AtomError::WriteError { .. })));
+        assert_eq!(store.get(count.as_atom()).unwrap(), 5);
+        assert_eq!(*log.lock(), vec!["validate Some(\"count\")"]);
+    }
+
+    #[test]
+    fn test_middleware_can_modify_value() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        store.use_middleware(|mut ctx, next| {
+            if let Some(value) = ctx.value_mut::<i32>() {
+                *value = (*value).min(100);
+            }
+            next(ctx)
+        });
+
+        store.set(&count, 250).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 100);
+    }
+
+    // ============================================================================
+    // Store::for_each() Tests
+    // ============================================================================
+
+    static ITEM_CLONES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    /// Item type that counts how often it is cloned
+    struct Counted(u64);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            ITEM_CLONES.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Counted(self.0)
+        }
+    }
+
+    #[test]
+    fn test_for_each_iterates_without_cloning() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
+        let store = Store::new();
+        let items = atom((1..=10_000).map(Counted).collect::<Vec<_>>());
+        store.get(items.as_atom()).unwrap();
+
+        let clones_before = ITEM_CLONES.load(Ordering::SeqCst);
+        let mut sum = 0;
+        store
+            .for_each(items.as_atom(), |item| sum += item.0)
+            .unwrap();
+
+        assert_eq!(sum, 10_000 * 10_001 / 2);
+        assert_eq!(ITEM_CLONES.load(Ordering::SeqCst), clones_before);
+    }
+
+    #[test]
+    fn test_borrow_array_reads_cached_array_in_place() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let samples = atom(std::array::from_fn::<i32, 256, _>(|i| i as i32));
+
+        let (sum, first_addr) = store
+            .borrow_array(samples.as_atom(), |values| {
+                (values.iter().sum::<i32>(), values.as_ptr() as usize)
+            })
+            .unwrap();
+        assert_eq!(sum, 255 * 256 / 2);
+
+        // Both reads see the same allocation: the store's cached array
+        let second_addr = store
+            .borrow_array(samples.as_atom(), |values| values.as_ptr() as usize)
+            .unwrap();
+        assert_eq!(first_addr, second_addr);
+
+        let mut updated = [0; 256];
+        updated[255] = 7;
+        store.set(&samples, updated).unwrap();
+        let peak = store
+            .borrow_array(samples.as_atom(), |values| values.iter().copied().max())
+            .unwrap();
+        assert_eq!(peak, Some(7));
+    }
+
+    // ============================================================================
+    // Dependency ID reuse Tests
+    // ============================================================================
+
+    #[test]
+    fn test_dependency_id_reused_by_other_type_recomputes() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        store.set(&base, 5).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+
+        // Another atom of a different type takes over the same ID
+        let imposter = atom("reused".to_string());
+        let imposter = crate::atom::WritableAtom {
+            atom: imposter.as_atom().clone().with_id(base.id()),
+            ..imposter
+        };
+        store.set(&imposter, "changed".to_string()).unwrap();
+
+        // The old i32 state is gone, so the derived atom recomputes from the
+        // base atom's initial value instead of erroring or reusing 10
+        assert_eq!(store.get_result(&doubled).unwrap().unwrap(), 2);
+        let epoch = store.epoch_of(base.id()).unwrap();
+        let state = store.state_of(doubled.id()).unwrap();
+        assert_eq!(state.read().dependencies().get(&base.id()), Some(&epoch));
+    }
+
+    // ============================================================================
+    // Store::dependency_graph() / to_dot() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_to_dot_diamond() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let top = atom(1).with_label("top");
+        let source = top.clone();
+        let left = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = top.clone();
+        let right = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let bottom = {
+            let left = left.clone();
+            let right = right.clone();
+            atom_derived(move |get| Ok(get.get(&left)? + get.get(&right)?))
+        };
+        store.get(&bottom).unwrap();
+
+        let dot = store.to_dot();
+        assert!(dot.starts_with("digraph atoms {"));
+        assert!(dot.contains(&format!(
+            "atom{} [label=\"atom{}:top\"];",
+            top.id(),
+            top.id()
+        )));
+        for node in [left.id(), right.id(), bottom.id()] {
+            assert!(dot.contains(&format!("atom{} [label=\"atom{}\"];", node, node)));
+        }
+        for (from, to) in [
+            (top.id(), left.id()),
+            (top.id(), right.id()),
+            (left.id(), bottom.id()),
+            (right.id(), bottom.id()),
+        ] {
+            assert!(dot.contains(&format!("atom{} -> atom{};", from, to)));
+        }
+        assert_eq!(dot.matches(" -> ").count(), 4);
+        assert_eq!(dot.matches("[label=").count(), 4);
+    }
+
+    #[test]
+    fn test_dependency_graph_of_diamond() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let source = atom(1);
+        let (s1, s2) = (source.clone(), source.clone());
+        let left = atom_derived(move |get| Ok(get.get(s1.as_atom())? + 1));
+        let right = atom_derived(move |get| Ok(get.get(s2.as_atom())? * 2));
+        let (l, r) = (left.clone(), right.clone());
+        let sink = atom_derived(move |get| Ok(get.get(&l)? + get.get(&r)?));
+        let unread = atom(0);
+
+        assert_eq!(store.get(&sink).unwrap(), 4);
+
+        let graph = store.dependency_graph();
+        assert_eq!(graph.len(), 4);
+        assert!(!graph.contains_key(&unread.id()));
+        assert_eq!(graph[&source.id()], Vec::<AtomId>::new());
+        assert_eq!(graph[&left.id()], vec![source.id()]);
+        assert_eq!(graph[&right.id()], vec![source.id()]);
+        assert_eq!(graph[&sink.id()], vec![left.id(), right.id()]);
+    }
+
+    // ============================================================================
+    // Store::history() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_history_records_changes_in_order() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        store.register_snapshot_type::<i32>();
+        let count = atom(0);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let _unsub = store.sub(&doubled, || {});
+
+        // Disabled by default
+        store.set(&count, 1).unwrap();
+        assert!(store.history().is_empty());
+
+        store.enable_history(10);
+        store.set(&count, 2).unwrap();
+        store.set(&count, 3).unwrap();
+        let history = store.history();
+        let changes: Vec<(AtomId, Option<&str>)> = history
+            .iter()
+            .map(|event| (event.atom_id, event.value.as_deref()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (count.id(), Some("2")),
+                (doubled.id(), Some("4")),
+                (count.id(), Some("3")),
+                (doubled.id(), Some("6")),
+            ]
+        );
+        assert_eq!(history[2].epoch, history[0].epoch + 1);
+        assert!(history.windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+        // Bounded: only the most recent events are kept
+        store.enable_history(3);
+        for value in 4..=6 {
+            store.set(&count, value).unwrap();
+        }
+        let history = store.history();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].atom_id, doubled.id());
+        assert_eq!(history[2].value.as_deref(), Some("12"));
+
+        store.disable_history();
+        store.set(&count, 7).unwrap();
+        assert!(store.history().is_empty());
+    }
+
+    // ============================================================================
+    // Retrying Atom Tests
+    // ============================================================================
+
+    #[test]
+    fn test_retrying_atom_caches_first_success() {
+        use crate::atom::{atom, atom_retrying};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let base = atom(1);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let flaky = {
+            let base = base.clone();
+            let calls = calls.clone();
+            atom_retrying(
+                move |get| {
+                    let value = get.get(base.as_atom())?;
+                    // Fails on the first two calls, then always succeeds
+                    if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                        return Err(AtomError::read_error(0, "temporarily unavailable"));
+                    }
+                    Ok(value * 10)
+                },
+                3,
+            )
+        };
+
+        assert_eq!(store.get(&flaky).unwrap(), 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(store.get(&flaky).unwrap(), 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        store.set(&base, 2).unwrap();
+        assert_eq!(store.get(&flaky).unwrap(), 20);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_retrying_atom_caches_error_after_last_attempt() {
+        use crate::atom::{atom, atom_retrying};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let base = atom(0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let failing = {
+            let base = base.clone();
+            let calls = calls.clone();
+            atom_retrying(
+                move |get| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    match get.get(base.as_atom())? {
+                        0 => Err(AtomError::read_error(0, "not ready")),
+                        value => Ok(value),
+                    }
+                },
+                2,
+            )
+        };
+
+        assert!(store.get(&failing).is_err());
+        assert!(store.get(&failing).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // A dependency change starts a fresh round of attempts
+        store.set(&base, 5).unwrap();
+        assert_eq!(store.get(&failing).unwrap(), 5);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    // ============================================================================
+    // WritableAtom::with_on_mount() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_on_mount_runs_on_first_subscribe() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let mounts = Arc::new(AtomicUsize::new(0));
+        let unmounts = Arc::new(AtomicUsize::new(0));
+        let polled = {
+            let mounts = mounts.clone();
+            let unmounts = unmounts.clone();
+            atom(0).with_on_mount(move |_set| {
+                mounts.fetch_add(1, Ordering::SeqCst);
+                let unmounts = unmounts.clone();
+                Some(Box::new(move || {
+                    unmounts.fetch_add(1, Ordering::SeqCst);
+                }))
+            })
+        };
+
+        // Reading and writing do not mount
+        store.set(&polled, 1).unwrap();
+        assert_eq!(store.get(polled.as_atom()).unwrap(), 1);
+        assert_eq!(mounts.load(Ordering::SeqCst), 0);
+
+        let first = store.sub(polled.as_atom(), || {});
+        assert_eq!(mounts.load(Ordering::SeqCst), 1);
+        let second = store.sub(polled.as_atom(), || {});
+        assert_eq!(mounts.load(Ordering::SeqCst), 1);
+
+        first();
+        assert_eq!(unmounts.load(Ordering::SeqCst), 0);
+        second();
+        assert_eq!(unmounts.load(Ordering::SeqCst), 1);
+        assert!(store.mounted_topology().is_empty());
+    }
+
+    #[test]
+    fn test_on_mount_seeds_state_and_cleans_up() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicI32, Ordering};
+
+        let store = Store::new();
+        let active = Arc::new(AtomicI32::new(0));
+        let status = atom("idle".to_string());
+        let connection = {
+            let active = active.clone();
+            let status = status.clone();
+            atom(0).with_on_mount(move |set| {
+                active.fetch_add(1, Ordering::SeqCst);
+                set.set(&status, "connected".to_string()).unwrap();
+                let active = active.clone();
+                Some(Box::new(move || {
+                    active.fetch_sub(1, Ordering::SeqCst);
+                }))
+            })
+        };
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let (reader, sink, watched) = (store.handle(), statuses.clone(), status.clone());
+        let _unsub_status = store.sub(status.as_atom(), move || {
+            sink.lock().push(reader.get(watched.as_atom()).unwrap());
+        });
+
+        let unsub = store.sub(connection.as_atom(), || {});
+        assert_eq!(active.load(Ordering::SeqCst), 1);
+        // The seeded value is notified within the same flush
+        assert_eq!(*statuses.lock(), vec!["connected"]);
+
+        unsub();
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_on_mount_runs_when_mounted_as_dependency() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let mounts = Arc::new(AtomicUsize::new(0));
+        let base = {
+            let mounts = mounts.clone();
+            atom(2).with_on_mount(move |_set| {
+                mounts.fetch_add(1, Ordering::SeqCst);
+                None
+            })
+        };
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        let unsub = store.sub(&doubled, || {});
+        assert_eq!(mounts.load(Ordering::SeqCst), 1);
+        unsub();
+    }
+
+    // ============================================================================
+    // Store::batch() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_batch_returns_changed_atoms() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let unrelated = atom(0);
+        let (left, right) = (a.clone(), b.clone());
+        let sum = atom_derived(move |get| Ok(get.get(left.as_atom())? + get.get(right.as_atom())?));
+
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        let seen = notifications.clone();
+        let _unsub = store.sub_with_value(&sum, move |value| seen.lock().push(value));
+
+        let changed = store.batch(|| {
+            store.set(&a, 10).unwrap();
+            store.set(&b, 20).unwrap();
+            // Listeners wait for the end of the batch
+            assert!(notifications.lock().is_empty());
+        });
+
+        let mut expected = vec![a.id(), b.id(), sum.id()];
+        expected.sort_unstable();
+        assert_eq!(changed, expected);
+        assert!(!changed.contains(&unrelated.id()));
+        assert_eq!(*notifications.lock(), vec![30]);
+    }
+
+    #[test]
+    fn test_batch_of_three_sets_notifies_sum_once() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let parts = [atom(1), atom(2), atom(3)];
+        let computations = Arc::new(AtomicUsize::new(0));
+        let sum = {
+            let (parts, computations) = (parts.clone(), computations.clone());
+            atom_derived(move |get| {
+                computations.fetch_add(1, Ordering::SeqCst);
+                parts
+                    .iter()
+                    .map(|part| get.get(part.as_atom()))
+                    .sum::<Result<i32>>()
+            })
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = calls.clone();
+        let _unsub = store.sub(&sum, move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+        let computed_before = computations.load(Ordering::SeqCst);
+
+        store.batch(|| {
+            for (part, value) in parts.iter().zip([10, 20, 30]) {
+                store.set(part, value).unwrap();
+            }
+        });
+
+        assert_eq!(store.get(&sum).unwrap(), 60);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(computations.load(Ordering::SeqCst), computed_before + 1);
+    }
+
+    #[test]
+    fn test_batch_does_not_defer_other_threads() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let mine = atom(0);
+        let theirs = atom(0);
+        let (mine_calls, their_calls) =
+            (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let (seen_mine, seen_theirs) = (mine_calls.clone(), their_calls.clone());
+        let _unsub_mine = store.sub(&mine, move || {
+            seen_mine.fetch_add(1, Ordering::SeqCst);
+        });
+        let _unsub_theirs = store.sub(&theirs, move || {
+            seen_theirs.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store.batch(|| {
+            // Another thread's write flushes while this batch is open
+            std::thread::scope(|scope| {
+                scope.spawn(|| store.set(&theirs, 1).unwrap());
+            });
+            assert_eq!(their_calls.load(Ordering::SeqCst), 1);
+
+            store.set(&mine, 1).unwrap();
+            assert_eq!(mine_calls.load(Ordering::SeqCst), 0);
+        });
+
+        assert_eq!(mine_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(their_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_nested_batch_flushes_once() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let count = atom(0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = calls.clone();
+        let _unsub = store.sub(count.as_atom(), move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let changed = store.batch(|| {
+            let inner = store.batch(|| store.set(&count, 1).unwrap());
+            assert_eq!(inner, vec![count.id()]);
+            assert_eq!(calls.load(Ordering::SeqCst), 0);
+            store.set(&count, 2).unwrap();
+        });
+
+        assert_eq!(changed, vec![count.id()]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(store.batch(|| {}), Vec::<AtomId>::new());
+    }
+
+    // ============================================================================
+    // Store::transaction() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_transaction_rolls_back_on_error() {
+        use crate::atom::{atom, atom_derived, atom_writable};
+
+        let store = Store::new();
+        let from = atom(100);
+        let to = atom(0);
+        let (left, right) = (from.clone(), to.clone());
+        let total =
+            atom_derived(move |get| Ok(get.get(left.as_atom())? + get.get(right.as_atom())?));
+        let limited_to = {
+            let (read, write) = (to.clone(), to.clone());
+            atom_writable(
+                move |get| get.get(read.as_atom()),
+                move |writer, value: i32| {
+                    writer.set(&write, value)?;
+                    if value > 50 {
+                        return Err(AtomError::write_error(0, "over limit"));
+                    }
+                    Ok(())
+                },
+            )
+        };
+        let totals = Arc::new(Mutex::new(Vec::new()));
+        let seen = totals.clone();
+        let _unsub = store.sub_with_value(&total, move |value| seen.lock().push(value));
+
+        // The second write fails after storing its value: both are undone
+        let result = store.transaction(|store| {
+            store.set(&from, 30)?;
+            store.set(&limited_to, 70)
+        });
+        assert!(matches!(result, Err(AtomError::WriteError { .. })));
+        assert_eq!(store.get(from.as_atom()).unwrap(), 100);
+        assert_eq!(store.get(to.as_atom()).unwrap(), 0);
+        assert_eq!(store.get(&total).unwrap(), 100);
+
+        // A successful transaction keeps its writes
+        store
+            .transaction(|store| {
+                store.set(&from, 60)?;
+                store.set(&limited_to, 40)
+            })
+            .unwrap();
+        assert_eq!(store.get(from.as_atom()).unwrap(), 60);
+        assert_eq!(store.get(to.as_atom()).unwrap(), 40);
+        assert_eq!(totals.lock().last(), Some(&100));
+    }
+
+    #[test]
+    fn test_transactions_on_two_threads_keep_their_own_undo_frames() {
+        use crate::atom::atom;
+        use std::sync::Barrier;
+
+        let store = Store::new();
+        let x = atom(0);
+        let y = atom(0);
+        let (started, written, failed) = (Barrier::new(2), Barrier::new(2), Barrier::new(2));
+
+        std::thread::scope(|scope| {
+            // Fails after the other thread's transaction has started and written
+            scope.spawn(|| {
+                let result: Result<()> = store.transaction(|store| {
+                    store.set(&x, 1)?;
+                    started.wait();
+                    written.wait();
+                    Err(AtomError::write_error(x.id(), "abort"))
+                });
+                assert!(result.is_err());
+                failed.wait();
+            });
+            // Commits only after the first transaction has rolled back
+            scope.spawn(|| {
+                started.wait();
+                store
+                    .transaction(|store| {
+                        store.set(&y, 1)?;
+                        written.wait();
+                        failed.wait();
+                        Ok(())
+                    })
+                    .unwrap();
+            });
+        });
+
+        assert_eq!(store.get(&x).unwrap(), 0);
+        assert_eq!(store.get(&y).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_int_and_string_atoms() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        store.register_snapshot_type::<i32>();
+        store.register_snapshot_type::<String>();
+        let count = atom(1);
+        let name = atom("ada".to_string());
+        let (source_count, source_name) = (count.clone(), name.clone());
+        let label = atom_derived(move |get| {
+            Ok(format!(
+                "{}:{}",
+                get.get(source_name.as_atom())?,
+                get.get(source_count.as_atom())?
+            ))
+        });
+
+        store.set(&count, 7).unwrap();
+        store.set(&name, "grace".to_string()).unwrap();
+        assert_eq!(store.get(&label).unwrap(), "grace:7");
+
+        // Derived atoms are left out
+        let saved = store.snapshot();
+        assert_eq!(saved.entries.len(), 2);
+        assert_eq!(saved.entries[&count.id()].value, "7");
+        assert_eq!(saved.entries[&name.id()].value, "grace");
+        assert!(!saved.entries.contains_key(&label.id()));
+
+        // Restoring over later changes bumps epochs and recomputes dependents
+        store.set(&count, 0).unwrap();
+        let epoch = store.epoch_of(count.id()).unwrap();
+        store.restore(&saved).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 7);
+        assert_eq!(store.epoch_of(count.id()), Some(epoch + 1));
+        assert_eq!(store.get(&label).unwrap(), "grace:7");
+
+        // A fresh store with the same types registered takes it as well
+        let other = Store::new();
+        other.register_snapshot_type::<i32>();
+        other.register_snapshot_type::<String>();
+        other.restore(&saved).unwrap();
+        assert_eq!(other.get(&label).unwrap(), "grace:7");
+        assert_eq!(other.snapshot(), saved);
+
+        // Unregistered types are rejected before anything is written
+        assert!(matches!(
+            Store::new().restore(&saved),
+            Err(AtomError::StoreError { .. })
+        ));
+    }
+
+    // ============================================================================
+    // Store::writes() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_write_list_commits_mixed_types_with_one_notification() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let count = atom(0);
+        let name = atom(String::new());
+        let (c, n) = (count.clone(), name.clone());
+        let label = atom_derived(move |get| {
+            Ok(format!(
+                "{}:{}",
+                get.get(n.as_atom())?,
+                get.get(c.as_atom())?
+            ))
+        });
+
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let seen = notifications.clone();
+        let _unsub = store.sub(&label, move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let mut tx = store.writes();
+        tx.set(&count, 1);
+        tx.set(&name, "x".to_string());
+        assert_eq!(tx.len(), 2);
+        // Nothing is applied before commit
+        assert_eq!(store.get(&label).unwrap(), ":0");
+
+        tx.commit().unwrap();
+        assert_eq!(store.get(&label).unwrap(), "x:1");
+        assert_eq!(notifications.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_write_list_stops_at_first_error() {
+        use crate::atom::{atom, atom_writable};
+
+        let store = Store::new();
+        let first = atom(0);
+        let last = atom(0);
+        let rejecting = atom_writable(
+            |_get| Ok(0),
+            |_writer, _value: i32| Err(AtomError::Generic("rejected".to_string())),
+        );
+
+        let mut tx = store.writes();
+        tx.set(&first, 1).set(&rejecting, 2).set(&last, 3);
+        assert!(tx.commit().is_err());
+
+        assert_eq!(store.get(first.as_atom()).unwrap(), 1);
+        assert_eq!(store.get(last.as_atom()).unwrap(), 0);
+    }
+
+    // ============================================================================
+    // Store::fork() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_fork_branches_state() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let count = atom(1);
+        let todos = atom(vec!["write".to_string()]);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        store.set(&count, 5).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+
+        let fork = store.fork();
+        assert_eq!(fork.get(count.as_atom()).unwrap(), 5);
+        assert_eq!(fork.get(&doubled).unwrap(), 10);
+
+        fork.set(&count, 7).unwrap();
+        fork.set(&todos, vec!["review".to_string()]).unwrap();
+        assert_eq!(fork.get(&doubled).unwrap(), 14);
+
+        // The original is untouched, and vice versa
+        assert_eq!(store.get(count.as_atom()).unwrap(), 5);
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert_eq!(
+            store.get(todos.as_atom()).unwrap(),
+            vec!["write".to_string()]
+        );
+
+        store.set(&count, 0).unwrap();
+        assert_eq!(fork.get(&doubled).unwrap(), 14);
+    }
+
+    #[test]
+    fn test_fork_does_not_copy_subscriptions() {
+        use crate::atom::atom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let count = atom(0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = calls.clone();
+        let _unsub = store.sub(count.as_atom(), move || {
+            seen.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let fork = store.fork();
+        assert!(fork.mounted_topology().is_empty());
+        fork.set(&count, 1).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    // ============================================================================
+    // Store::dispatch() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_dispatch_command_actions() {
+        use crate::atom::{atom_command, atom_derived};
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Todo {
+            title: String,
+        }
+
+        enum TodoAction {
+            Add(String),
+            Remove(usize),
+        }
+
+        let store = Store::new();
+        let todos = atom_command(Vec::<Todo>::new(), |writer, todos, action| match action {
+            TodoAction::Add(title) => writer.update(todos, |mut list| {
+                list.push(Todo { title });
+                list
+            }),
+            TodoAction::Remove(index) => {
+                let mut list = writer.get(todos.as_atom())?;
+                if index >= list.len() {
+                    return Err(AtomError::Generic(format!("no todo at {}", index)));
+                }
+                list.remove(index);
+                writer.set(todos, list)
+            }
+        });
+        let source = todos.as_atom().clone();
+        let count = atom_derived(move |get| Ok(get.get(&source)?.len()));
+
+        let counts = Arc::new(Mutex::new(Vec::new()));
+        let seen = counts.clone();
+        let _unsub = store.sub_with_value(&count, move |count| seen.lock().push(count));
+
+        store
+            .dispatch(&todos, TodoAction::Add("write".to_string()))
+            .unwrap();
+        store
+            .dispatch(&todos, TodoAction::Add("review".to_string()))
+            .unwrap();
+        store.dispatch(&todos, TodoAction::Remove(0)).unwrap();
+
+        assert_eq!(
+            store.get(todos.as_atom()).unwrap(),
+            vec![Todo {
+                title: "review".to_string()
+            }]
+        );
+        assert_eq!(*counts.lock(), vec![1, 2, 1]);
+        assert!(store.dispatch(&todos, TodoAction::Remove(5)).is_err());
+    }
+
+    // ============================================================================
+    // Store::max_dependency_depth() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_max_dependency_depth_of_chain() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let mut chain = vec![base.as_atom().clone()];
+        for _ in 0..4 {
+            let previous = chain.last().unwrap().clone();
+            chain.push(atom_derived(move |get| Ok(get.get(&previous)? + 1)));
+        }
+        let top = chain.last().unwrap();
+
+        assert_eq!(store.get(top).unwrap(), 5);
+        assert_eq!(store.max_dependency_depth(top.id()), 4);
+        assert_eq!(store.max_dependency_depth(chain[2].id()), 2);
+        assert_eq!(store.max_dependency_depth(base.id()), 0);
+    }
+
+    #[test]
+    fn test_max_dependency_depth_of_diamond() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let left = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = left.clone();
+        let left_deeper = atom_derived(move |get| Ok(get.get(&source)? * 2));
+        let source = base.clone();
+        let right = atom_derived(move |get| Ok(get.get(source.as_atom())? * 3));
+        let (l, r) = (left_deeper.clone(), right.clone());
+        let bottom = atom_derived(move |get| Ok(get.get(&l)? + get.get(&r)?));
+
+        store.get(&bottom).unwrap();
+        // The longer of the two paths counts: bottom -> left_deeper -> left -> base
+        assert_eq!(store.max_dependency_depth(bottom.id()), 3);
+        assert_eq!(store.max_dependency_depth(right.id()), 1);
+    }
+
+    // ============================================================================
+    // Store::watch_channel() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_watch_channel_delivers_changes_until_receiver_dropped() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+
+        let (initial, changes) = store.watch_channel(count.as_atom()).unwrap();
+        assert_eq!(initial, 0);
+
+        store.set(&count, 1).unwrap();
+        store.set(&count, 2).unwrap();
+        assert_eq!(changes.try_recv().unwrap(), 1);
+        assert_eq!(changes.try_recv().unwrap(), 2);
+        assert!(changes.try_recv().is_err());
+        assert_eq!(store.mounted_topology().len(), 1);
+
+        // The next change finds the receiver gone and unsubscribes
+        drop(changes);
+        store.set(&count, 3).unwrap();
+        assert!(store.mounted_topology().is_empty());
+        assert_eq!(store.get(count.as_atom()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_watch_channel_across_threads() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        let (_, changes) = store.watch_channel(count.as_atom()).unwrap();
+
+        let consumer = std::thread::spawn(move || changes.iter().take(2).collect::<Vec<_>>());
+        store.set(&count, 10).unwrap();
+        store.set(&count, 20).unwrap();
+        assert_eq!(consumer.join().unwrap(), vec![10, 20]);
+    }
+
+    // ============================================================================
+    // Epoch Wraparound Tests
+    // ============================================================================
+
+    #[test]
+    fn test_wrapped_epoch_still_invalidates() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        // Push the base atom to the last epoch before wrapping
+        store.get(base.as_atom()).unwrap();
+        store
+            .state_of(base.id())
+            .unwrap()
+            .write()
+            .downcast_mut::<AtomState<i32>>()
+            .unwrap()
+            .epoch = EpochNumber::MAX;
+
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+
+        store.set(&base, 5).unwrap();
+        assert_eq!(store.epoch_of(base.id()), Some(0));
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_cached_state_is_fresh_against_store_epochs() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let is_fresh = || {
+            store
+                .state_of(doubled.id())
+                .unwrap()
+                .read()
+                .is_fresh(&|id| store.epoch_of(id))
+        };
+
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert!(is_fresh());
+
+        store.set(&base, 5).unwrap();
+        assert!(!is_fresh());
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert!(is_fresh());
+    }
+
+    // ============================================================================
+    // Store::flush_recompute() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_flush_recompute_runs_once_after_many_invalidations() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (left, right, counter) = (a.clone(), b.clone(), runs.clone());
+        let sum = atom_derived(move |get| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(get.get(left.as_atom())? + get.get(right.as_atom())?)
+        });
+        let source = sum.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(&source)? * 2));
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = notified.clone();
+        let _unsub = store.sub(&doubled, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        store.set_flush_mode(FlushMode::Manual);
+        store.set(&a, 10).unwrap();
+        store.set(&b, 20).unwrap();
+        store.set(&a, 30).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        let changed = store.flush_recompute().unwrap();
+        assert_eq!(changed, vec![sum.id(), doubled.id()]);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(notified.load(Ordering::SeqCst), 0);
+
+        // Nothing left to recompute; the flush only notifies
+        assert!(store.flush_recompute().unwrap().is_empty());
+        store.flush();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+        assert_eq!(store.get(&doubled).unwrap(), 100);
+    }
+
+    // ============================================================================
+    // Store::set_returning() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_set_returning_yields_generated_id() {
+        use crate::atom::{atom, atom_writable_returning};
+
+        let store = Store::new();
+        let records = atom(Vec::<(u32, String)>::new());
+        let next_id = atom(1u32);
+        let (list, ids) = (records.clone(), next_id.clone());
+        let create_record = atom_writable_returning(
+            // Reads as the name of the latest record
+            move |get| {
+                let records = get.get(list.as_atom())?;
+                Ok(records
+                    .last()
+                    .map(|(_, name)| name.clone())
+                    .unwrap_or_default())
+            },
+            move |writer, name: String| {
+                let id = writer.get(ids.as_atom())?;
+                writer.set(&ids, id + 1)?;
+                writer.update(&records, |mut records| {
+                    records.push((id, name));
+                    records
+                })?;
+                Ok(id)
+            },
+        );
+
+        assert_eq!(
+            store
+                .set_returning(&create_record, "first".to_string())
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .set_returning(&create_record, "second".to_string())
+                .unwrap(),
+            2
+        );
+        assert_eq!(store.get(create_record.as_atom()).unwrap(), "second");
+    }
+
+    // ============================================================================
+    // Store::call() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_call_invokes_current_function() {
+        use crate::atom::{atom_derived, atom_fn};
+
+        let store = Store::new();
+        let transform = atom_fn(Arc::new(|x: i32| x * 2));
+        let source = transform.clone();
+        let applied = atom_derived(move |get| Ok(get.get(source.as_atom())?(10)));
+
+        assert_eq!(store.call(transform.as_atom(), 21).unwrap(), 42);
+        assert_eq!(store.get(&applied).unwrap(), 20);
+
+        store.set(&transform, Arc::new(|x: i32| x + 1)).unwrap();
+        assert_eq!(store.call(transform.as_atom(), 21).unwrap(), 22);
+        assert_eq!(store.get(&applied).unwrap(), 11);
+    }
+
+    // ============================================================================
+    // Store::set_except() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_set_except_skips_excluded_dependent() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let base = atom(1);
+
+        let doubled_runs = Arc::new(AtomicUsize::new(0));
+        let (source, runs) = (base.clone(), doubled_runs.clone());
+        let doubled = atom_derived(move |get| {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Ok(get.get(source.as_atom())? * 2)
+        });
+        let echo_runs = Arc::new(AtomicUsize::new(0));
+        let (source, runs) = (base.clone(), echo_runs.clone());
+        let echo = atom_derived(move |get| {
+            runs.fetch_add(1, Ordering::SeqCst);
+            get.get(source.as_atom())
+        });
+        // Reachable only through the excluded atom
+        let echo_source = echo.clone();
+        let echo_label = atom_derived(move |get| Ok(format!("echo {}", get.get(&echo_source)?)));
+
+        let _doubled_sub = store.sub(&doubled, || {});
+        let _echo_sub = store.sub(&echo_label, || {});
+        assert_eq!(doubled_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(echo_runs.load(Ordering::SeqCst), 1);
+
+        store.set_except(&base, 5, &[echo.id()]).unwrap();
+
+        assert_eq!(doubled_runs.load(Ordering::SeqCst), 2);
+        assert_eq!(echo_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert_eq!(store.get(&echo).unwrap(), 1);
+        assert_eq!(store.get(&echo_label).unwrap(), "echo 1");
+
+        // The exclusion ends with the call
+        store.set(&base, 6).unwrap();
+        assert_eq!(echo_runs.load(Ordering::SeqCst), 2);
+        assert_eq!(store.get(&echo_label).unwrap(), "echo 6");
+    }
+
+    // ============================================================================
+    // Store::sub_offloaded() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_sub_offloaded_does_not_block_writer() {
+        use crate::atom::atom;
+        use std::time::Duration;
+
+        let store = Store::new();
+        let count = atom(0);
+        let (gate, gate_rx) = mpsc::channel::<()>();
+        let gate_rx = Mutex::new(gate_rx);
+        let (seen, seen_rx) = mpsc::channel();
+
+        let _unsub = store.sub_offloaded(count.as_atom(), move |value: i32| {
+            // Blocks until the test opens the gate, once per value
+            gate_rx.lock().recv().unwrap();
+            seen.send(value).unwrap();
+        });
+
+        // The listener is stuck on the gate, yet every set returns
+        for value in 1..=3 {
+            store.set(&count, value).unwrap();
+        }
+        assert!(seen_rx.try_recv().is_err());
+
+        let mut delivered = Vec::new();
+        for _ in 1..=3 {
+            gate.send(()).unwrap();
+            delivered.push(seen_rx.recv_timeout(Duration::from_secs(5)).unwrap());
+        }
+        assert_eq!(delivered, vec![1, 2, 3]);
+    }
+
+    // ============================================================================
+    // Store::set_panic_handler() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_panic_handler_contains_listener_panic() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let caught = Arc::new(Mutex::new(Vec::new()));
+        let log = caught.clone();
+        store.set_panic_handler(move |info| log.lock().push(info));
+
+        let count = atom(0);
+        let other = atom(0);
+        let _panicking = store.sub(count.as_atom(), || panic!("listener exploded"));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let _healthy = store.sub(count.as_atom(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store.set(&count, 1).unwrap();
+        {
+            let caught = caught.lock();
+            assert_eq!(caught.len(), 1);
+            assert_eq!(caught[0].atom_id, count.id());
+            assert_eq!(caught[0].site, PanicSite::Listener);
+            assert_eq!(caught[0].message, "listener exploded");
+        }
+        // The other listener still ran, and the store keeps working
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        store.set(&other, 5).unwrap();
+        assert_eq!(store.get(other.as_atom()).unwrap(), 5);
+
+        // A panicking read function caches a read error
+        let broken = atom_derived(|_get| -> Result<i32> { panic!("read exploded") });
+        assert!(matches!(
+            store.get(&broken),
+            Err(AtomError::ReadError { ref message, .. }) if message == "read exploded"
+        ));
+        assert_eq!(caught.lock().last().unwrap().site, PanicSite::Read);
+    }
+
+    // ============================================================================
+    // Store::set_derived_cache_limit() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_derived_cache_limit_evicts_oldest_unmounted() {
+        use crate::atom::{atom, atom_derived, PrimitiveAtom};
+        use std::sync::atomic::AtomicUsize;
+
+        fn counted(base: &PrimitiveAtom<i32>, runs: &Arc<AtomicUsize>) -> Atom<i32> {
+            let (base, runs) = (base.clone(), runs.clone());
+            atom_derived(move |get| {
+                runs.fetch_add(1, Ordering::SeqCst);
+                get.get(base.as_atom())
+            })
+        }
+
+        let store = Store::new();
+        store.set_derived_cache_limit(2);
+        let base = atom(1);
+        let (mounted_runs, a_runs, b_runs) = (
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+        );
+        let mounted = counted(&base, &mounted_runs);
+        let a = counted(&base, &a_runs);
+        let b = counted(&base, &b_runs);
+
+        let _unsub = store.sub(&mounted, || {});
+        store.get(&mounted).unwrap();
+        store.get(&a).unwrap();
+        // Three cached derived values: the oldest unmounted one (a) goes
+        store.get(&b).unwrap();
+
+        store.get(&b).unwrap();
+        assert_eq!(b_runs.load(Ordering::SeqCst), 1);
+        store.get(&mounted).unwrap();
+        assert_eq!(mounted_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(store.get(&a).unwrap(), 1);
+        assert_eq!(a_runs.load(Ordering::SeqCst), 2);
+
+        // Reading a again evicted b, the least recently read
+        store.get(&b).unwrap();
+        assert_eq!(b_runs.load(Ordering::SeqCst), 2);
+    }
+
+    // ============================================================================
+    // Store::get_with_overrides() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_get_with_overrides_leaves_store_untouched() {
+        use crate::atom::{atom, atom_derived};
+        use crate::types::ErasedOverride;
+
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let label = atom("sum".to_string());
+        let (left, right) = (a.clone(), b.clone());
+        let sum = atom_derived(move |get| Ok(get.get(left.as_atom())? + get.get(right.as_atom())?));
+        let (source, name) = (sum.clone(), label.clone());
+        let report = atom_derived(move |get| {
+            Ok(format!(
+                "{} = {}",
+                get.get(name.as_atom())?,
+                get.get(&source)?
+            ))
+        });
+
+        assert_eq!(store.get(&report).unwrap(), "sum = 3");
+
+        let overrides = [ErasedOverride::new(a.as_atom(), 10)];
+        assert_eq!(store.get_with_overrides(&sum, &overrides).unwrap(), 12);
+        assert_eq!(
+            store.get_with_overrides(&report, &overrides).unwrap(),
+            "sum = 12"
+        );
+
+        // Overrides of different types together
+        let overrides = [
+            ErasedOverride::new(b.as_atom(), 5),
+            ErasedOverride::new(label.as_atom(), "total".to_string()),
+        ];
+        assert_eq!(
+            store.get_with_overrides(&report, &overrides).unwrap(),
+            "total = 6"
+        );
+
+        // A derived atom never read for real gets no state
+        let (left, right) = (a.clone(), b.clone());
+        let product = atom_derived(move |get| Ok(get.get(&left)? * get.get(&right)?));
+        let overrides = [ErasedOverride::new(a.as_atom(), 3)];
+        assert_eq!(store.get_with_overrides(&product, &overrides).unwrap(), 6);
+        assert!(store.state_of(product.id()).is_none());
+        assert!(!store.atom_configs.contains_key(&product.id()));
+        assert!(!store
+            .dependents
+            .get(&b.id())
+            .is_some_and(|d| d.contains(&product.id())));
+
+        // Nothing was written or cached
+        assert_eq!(store.get(a.as_atom()).unwrap(), 1);
+        assert_eq!(store.get(&sum).unwrap(), 3);
+        assert_eq!(store.get(&report).unwrap(), "sum = 3");
+    }
+
+    #[test]
+    fn test_get_with_overrides_skips_unaffected_atoms() {
+        use crate::atom::{atom, atom_derived};
+        use crate::types::ErasedOverride;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let a = atom(1);
+        let unrelated = atom(100);
+        let computations = Arc::new(AtomicUsize::new(0));
+        let (source, counter) = (unrelated.clone(), computations.clone());
+        let expensive = atom_derived(move |get| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(get.get(source.as_atom())? * 2)
+        });
+        let (left, right) = (a.clone(), expensive.clone());
+        let total = atom_derived(move |get| Ok(get.get(left.as_atom())? + get.get(&right)?));
+
+        assert_eq!(store.get(&total).unwrap(), 201);
+        let overrides = [ErasedOverride::new(a.as_atom(), 0)];
+        assert_eq!(store.get_with_overrides(&total, &overrides).unwrap(), 200);
+        // The unaffected branch came from the cache
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+    }
+
+    // ============================================================================
+    // Store::mounted_topology() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_mounted_topology_of_subscribed_diamond() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let top = atom(1);
+        let source = top.clone();
+        let left = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = top.clone();
+        let right = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let bottom = {
+            let left = left.clone();
+            let right = right.clone();
+            atom_derived(move |get| Ok(get.get(&left)? + get.get(&right)?))
+        };
+
+        let unsub_bottom = store.sub(&bottom, || {});
+        let _unsub_left = store.sub(&left, || {});
+
+        let sorted = |mut ids: Vec<AtomId>| {
+            ids.sort_unstable();
+            ids
+        };
+        let info = |atom_id, listener_count, dependencies, dependents| MountedInfo {
+            atom_id,
+            listener_count,
+            dependencies: sorted(dependencies),
+            dependents: sorted(dependents),
+            has_cleanup: false,
+        };
+        assert_eq!(
+            store.mounted_topology(),
+            vec![
+                info(top.id(), 0, vec![], vec![left.id(), right.id()]),
+                info(left.id(), 1, vec![top.id()], vec![bottom.id()]),
+                info(right.id(), 0, vec![top.id()], vec![bottom.id()]),
+                info(bottom.id(), 1, vec![left.id(), right.id()], vec![]),
+            ]
+        );
+
+        // Dropping the bottom subscription leaves only the left branch
+        unsub_bottom();
+        assert_eq!(
+            store.mounted_topology(),
+            vec![
+                info(top.id(), 0, vec![], vec![left.id()]),
+                info(left.id(), 1, vec![top.id()], vec![]),
+            ]
+        );
+    }
+
+    // ============================================================================
+    // Store::listener_count() / unsubscribe_all() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_unsubscribe_all_unmounts_atom() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let cleanups = Arc::new(AtomicUsize::new(0));
+        let counter = cleanups.clone();
+        let count = atom(0).with_on_mount(move |_set| {
+            let counter = counter.clone();
+            Some(Box::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }))
+        });
+
+        let unsubs: Vec<Unsubscribe> = (0..3).map(|_| store.sub(count.as_atom(), || {})).collect();
+        assert_eq!(store.listener_count(count.id()), 3);
+
+        store.unsubscribe_all(count.id());
+        assert_eq!(store.listener_count(count.id()), 0);
+        assert!(!store.mounted.contains_key(&count.id()));
+        assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+
+        // The stale handles are harmless
+        for unsub in unsubs {
+            unsub();
+        }
+        assert_eq!(cleanups.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_subscription_unsubscribes_on_drop() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let count = atom(0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        {
+            let counter = calls.clone();
+            let _subscription = store.subscribe(count.as_atom(), move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            assert_eq!(store.listener_count(count.id()), 1);
+            store.set(&count, 1).unwrap();
+        }
+        assert_eq!(store.listener_count(count.id()), 0);
+        assert!(!store.mounted.contains_key(&count.id()));
 
-    /// Mount an atom (add to mounted map)
-    ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (mountAtom function)
-    ///
-    /// TODO: Phase 3.2 - Implement mounting
-    pub(crate) fn mount_atom<T: Clone + Send + Sync + 'static>(
-        &self,
-        atom: &Atom<T>,
-        listener: Listener,
-    ) -> Result<()> {
-        // TODO: Create Mounted entry if needed
-        // TODO: Add listener
-        // TODO: Mount dependencies recursively
-        // TODO: Call onMount callback
-        todo!("mount_atom - Phase 3.2")
+        store.set(&count, 2).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 
-    /// Unmount an atom (remove from mounted map)
-    ///
-    /// Reference: `jotai/src/vanilla/internals.ts` (unmountAtom function)
-    ///
-    /// TODO: Phase 3.2 - Implement unmounting
-    pub(crate) fn unmount_atom<T: Clone + Send + Sync + 'static>(
-        &self,
-        atom: &Atom<T>,
-        listener: &Listener,
-    ) -> Result<()> {
-        // TODO: Remove listener
-        // TODO: If no more listeners, remove Mounted entry
-        // TODO: Call cleanup callback
-        // TODO: Unmount dependencies if not used elsewhere
-        todo!("unmount_atom - Phase 3.2")
+    // ============================================================================
+    // Store::timing_report() / recompute_count() Tests
+    // ============================================================================
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_timing_report_counts_recomputes() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(0);
+        let source = base.clone();
+        let slow = atom_derived(move |get| {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            Ok(get.get(source.as_atom())? + 1)
+        });
+
+        for value in 1..=3 {
+            store.set(&base, value).unwrap();
+            store.get(&slow).unwrap();
+        }
+
+        let report = store.timing_report();
+        let (atom_id, count, total, max) = report[0];
+        assert_eq!(atom_id, slow.id());
+        assert_eq!(count, 3);
+        assert!(max >= std::time::Duration::from_millis(2));
+        assert!(total >= max && total >= std::time::Duration::from_millis(6));
     }
-}
 
-impl Default for Store {
-    fn default() -> Self {
-        Self::new()
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_recompute_count_skips_cache_hits() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let (a, b) = (atom(1), atom(2));
+        let source = a.clone();
+        let a_plus_one = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = a_plus_one.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(&source)? * 2));
+        assert_eq!(store.recompute_count(doubled.id()), 0);
+
+        store.get(&doubled).unwrap();
+        store.get(&doubled).unwrap();
+        assert_eq!(store.recompute_count(doubled.id()), 1);
+        assert_eq!(store.recompute_count(a_plus_one.id()), 1);
+
+        // An unrelated write recomputes nothing
+        store.set(&b, 5).unwrap();
+        store.get(&doubled).unwrap();
+        assert_eq!(store.recompute_count(doubled.id()), 1);
+
+        store.set(&a, 5).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 12);
+        assert_eq!(store.recompute_count(doubled.id()), 2);
+        assert_eq!(store.recompute_count(a_plus_one.id()), 2);
     }
-}
 
-// Implement Getter trait for Store
-impl Getter for Store {
-    fn get<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T> {
-        self.get(atom)
+    // ============================================================================
+    // Cache Policy Tests
+    // ============================================================================
+
+    /// Atom doubling `base` with the given policy, counting its computations
+    fn counted_with_policy(
+        base: &crate::atom::PrimitiveAtom<i32>,
+        policy: CachePolicy,
+    ) -> (Atom<i32>, Arc<std::sync::atomic::AtomicUsize>) {
+        use crate::atom::atom_with_policy;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let computations = Arc::new(AtomicUsize::new(0));
+        let counter = computations.clone();
+        let base = base.clone();
+        let atom = atom_with_policy(
+            move |get| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(get.get(base.as_atom())? * 2)
+            },
+            policy,
+        );
+        (atom, computations)
     }
-}
 
-// Implement Setter trait for Store
-impl Setter for Store {
-    fn set<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>, value: T) -> Result<()> {
-        // TODO: This needs to handle WritableAtom conversion
-        if let Some(state_arc) = self.atom_states.get(&atom.id()) {
-            let mut lock = state_arc.write();
-            if let Some(state) = lock.downcast_mut::<AtomState<T>>() {
-                state.value = Some(Ok(value));
-                state.epoch += 1;
-                self.changed.write().insert(atom.id());
-            }
-        }
-        Ok(())
+    #[test]
+    fn test_no_cache_recomputes_every_read() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
+        let store = Store::new();
+        let base = atom(1);
+        let (doubled, computations) = counted_with_policy(&base, CachePolicy::NoCache);
+
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(computations.load(Ordering::SeqCst), 3);
     }
-}
 
-impl std::fmt::Debug for Store {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Store")
-            .field("atom_states_count", &self.atom_states.len())
-            .field("mounted_count", &self.mounted.len())
-            .finish()
+    #[test]
+    fn test_no_cache_computes_once_per_batch() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
+        let store = Store::new();
+        let base = atom(1);
+        let (doubled, computations) = counted_with_policy(&base, CachePolicy::NoCache);
+
+        store.batch(|| {
+            assert_eq!(store.get(&doubled).unwrap(), 2);
+            assert_eq!(store.get(&doubled).unwrap(), 2);
+            assert_eq!(store.get(&doubled).unwrap(), 2);
+        });
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+
+        // A dependency change inside the batch still recomputes
+        store.batch(|| {
+            assert_eq!(store.get(&doubled).unwrap(), 2);
+            store.set(&base, 5).unwrap();
+            assert_eq!(store.get(&doubled).unwrap(), 10);
+            assert_eq!(store.get(&doubled).unwrap(), 10);
+        });
+        assert_eq!(computations.load(Ordering::SeqCst), 3);
+
+        // Outside a batch every read recomputes again
+        store.get(&doubled).unwrap();
+        assert_eq!(computations.load(Ordering::SeqCst), 4);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_no_cache_computes_once_per_flush() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
+        let store = Store::new();
+        let base = atom(1);
+        let (doubled, computations) = counted_with_policy(&base, CachePolicy::NoCache);
+
+        // Two listeners reading the atom during the same flush
+        let handle = store.handle();
+        let target = doubled.clone();
+        let _first = store.sub(base.as_atom(), move || {
+            handle.get(&target).unwrap();
+        });
+        let handle = store.handle();
+        let target = doubled.clone();
+        let _second = store.sub(base.as_atom(), move || {
+            handle.get(&target).unwrap();
+        });
+
+        store.set(&base, 2).unwrap();
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+    }
 
     #[test]
-    fn test_store_creation() {
-        // Test that Store::new initializes all maps correctly
+    fn test_cache_forever_survives_unmount() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
         let store = Store::new();
-        assert_eq!(store.atom_states.len(), 0);
-        assert_eq!(store.mounted.len(), 0);
+        let base = atom(1);
+        let (doubled, computations) = counted_with_policy(&base, CachePolicy::CacheForever);
+
+        let unsub = store.sub(&doubled, || {});
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        unsub();
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+
+        store.set(&base, 2).unwrap();
+        assert_eq!(store.get(&doubled).unwrap(), 4);
+        assert_eq!(computations.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_cache_while_mounted_drops_value_on_unmount() {
+        use crate::atom::atom;
+        use std::sync::atomic::Ordering;
+
+        let store = Store::new();
+        let base = atom(1);
+        let (doubled, computations) = counted_with_policy(&base, CachePolicy::CacheWhileMounted);
+
+        let unsub = store.sub(&doubled, || {});
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+        unsub();
+
+        // Unmounting dropped the value, so the next read recomputes
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(computations.load(Ordering::SeqCst), 2);
+
+        // While mounted again, reads are cached
+        let _unsub = store.sub(&doubled, || {});
+        assert_eq!(store.get(&doubled).unwrap(), 2);
+        assert_eq!(computations.load(Ordering::SeqCst), 2);
     }
 
     // ============================================================================
-    // PHASE 1.3: Store::get() Tests
+    // Store::register_eq() Tests
     // ============================================================================
 
     #[test]
-    fn test_get_primitive_atom() {
-        use crate::atom::atom;
+    fn test_registered_eq_suppresses_tiny_changes() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
         let store = Store::new();
-        let count = atom(42);
+        store.register_eq::<f64, _>(|a, b| (a - b).abs() < 1e-9);
 
-        // First read should compute and cache the value
-        let value = store.get(&count.as_atom()).expect("Should read atom");
-        assert_eq!(value, 42);
+        let price = atom(1.0_f64);
+        let source = price.clone();
+        let scaled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 1e-12));
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let counter = notified.clone();
+        let _unsub_price = store.sub(price.as_atom(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let counter = notified.clone();
+        let _unsub_scaled = store.sub(&scaled, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store.set(&price, 1.0 + 1e-12).unwrap();
+        assert_eq!(notified.load(Ordering::SeqCst), 0);
+        assert_eq!(store.get(price.as_atom()).unwrap(), 1.0);
+
+        // A real change notifies the atom; its derived value stays within
+        // tolerance, so the derived listener is still skipped
+        store.set(&price, 2.0).unwrap();
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+        assert_eq!(store.get(price.as_atom()).unwrap(), 2.0);
+
+        // Other types are unaffected
+        let count = atom(1);
+        store.set(&count, 1).unwrap();
+        assert_eq!(store.epoch_of(count.id()), Some(1));
+    }
+
+    // ============================================================================
+    // Concurrent Read Tests
+    // ============================================================================
+
+    #[test]
+    fn test_concurrent_first_reads_compute_once() {
+        use crate::atom::{atom, atom_derived};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Barrier;
+        use std::time::Duration;
+
+        let store = Store::new();
+        let base = atom(21);
+        let computations = Arc::new(AtomicUsize::new(0));
+        let expensive = {
+            let base = base.clone();
+            let computations = computations.clone();
+            atom_derived(move |get| {
+                computations.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(50));
+                Ok(get.get(base.as_atom())? * 2)
+            })
+        };
+
+        let barrier = Barrier::new(2);
+        let results: Vec<i32> = std::thread::scope(|scope| {
+            let readers: Vec<_> = (0..2)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        store.get(&expensive).unwrap()
+                    })
+                })
+                .collect();
+            readers
+                .into_iter()
+                .map(|reader| reader.join().unwrap())
+                .collect()
+        });
+
+        assert_eq!(results, vec![42, 42]);
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+        assert!(store.computing.is_empty());
     }
 
+    // ============================================================================
+    // flush_callbacks() Tests
+    // ============================================================================
+
     #[test]
-    fn test_get_caches_value() {
+    fn test_listener_set_is_handled_in_same_flush() {
         use crate::atom::atom;
 
         let store = Store::new();
-        let count = atom(100);
+        let source = atom(0);
+        let mirror = atom(0);
+        let mirror_calls = Arc::new(Mutex::new(Vec::new()));
 
-        // First read
-        let v1 = store.get(&count.as_atom()).unwrap();
+        let writer = store.handle();
+        let (from, to) = (source.clone(), mirror.clone());
+        let _unsub_source = store.sub(source.as_atom(), move || {
+            let value = writer.get(from.as_atom()).unwrap();
+            writer.set(&to, value * 10).unwrap();
+        });
+        let reader = store.handle();
+        let (watched, sink) = (mirror.clone(), mirror_calls.clone());
+        let _unsub_mirror = store.sub(mirror.as_atom(), move || {
+            sink.lock().push(reader.get(watched.as_atom()).unwrap());
+        });
 
-        // Second read should return cached value
-        let v2 = store.get(&count.as_atom()).unwrap();
+        // The nested set is queued and notified by the outer flush loop
+        store.set(&source, 2).unwrap();
+        assert_eq!(*mirror_calls.lock(), vec![20]);
 
-        assert_eq!(v1, v2);
-        assert_eq!(v1, 100);
+        store.set(&source, 3).unwrap();
+        assert_eq!(*mirror_calls.lock(), vec![20, 30]);
+    }
 
-        // Verify the atom is now in atom_states
-        assert_eq!(store.atom_states.len(), 1);
+    // ============================================================================
+    // Store::last_flush_order() Tests
+    // ============================================================================
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_flush_order_notifies_dependencies_first() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let middle = atom_derived(move |get| Ok(get.get(source.as_atom())? + 1));
+        let source = middle.clone();
+        let top = atom_derived(move |get| Ok(get.get(&source)? * 2));
+
+        let _unsub_top = store.sub(&top, || {});
+        let _unsub_base = store.sub(base.as_atom(), || {});
+        store.set(&base, 5).unwrap();
+
+        assert_eq!(
+            store.last_flush_order(),
+            vec![base.id(), middle.id(), top.id()]
+        );
     }
 
+    // ============================================================================
+    // Store::set_flush_mode() Tests
+    // ============================================================================
+
     #[test]
-    fn test_get_multiple_atoms() {
+    fn test_sync_flush_mode_notifies_inside_set() {
         use crate::atom::atom;
 
         let store = Store::new();
-        let a = atom(1);
-        let b = atom(2);
-        let c = atom(3);
+        let count = atom(0);
+        let calls = Arc::new(Mutex::new(0));
 
-        assert_eq!(store.get(&a.as_atom()).unwrap(), 1);
-        assert_eq!(store.get(&b.as_atom()).unwrap(), 2);
-        assert_eq!(store.get(&c.as_atom()).unwrap(), 3);
+        let counter = calls.clone();
+        let _unsub = store.sub(count.as_atom(), move || *counter.lock() += 1);
+        store.set(&count, 1).unwrap();
 
-        // All three atoms should be cached
-        assert_eq!(store.atom_states.len(), 3);
+        assert_eq!(store.flush_mode(), FlushMode::Sync);
+        assert_eq!(*calls.lock(), 1);
     }
 
     #[test]
-    fn test_get_different_types() {
+    fn test_manual_flush_mode_defers_until_flush() {
         use crate::atom::atom;
 
         let store = Store::new();
-        let num = atom(42);
-        let text = atom("hello".to_string());
-        let flag = atom(true);
+        store.set_flush_mode(FlushMode::Manual);
+        let count = atom(0);
+        let calls = Arc::new(Mutex::new(0));
 
-        assert_eq!(store.get(&num.as_atom()).unwrap(), 42);
-        assert_eq!(store.get(&text.as_atom()).unwrap(), "hello");
-        assert_eq!(store.get(&flag.as_atom()).unwrap(), true);
+        let counter = calls.clone();
+        let _unsub = store.sub(count.as_atom(), move || *counter.lock() += 1);
+        store.set(&count, 1).unwrap();
+        store.set(&count, 2).unwrap();
+
+        // The value is visible, the listener has not run yet
+        assert_eq!(store.get(count.as_atom()).unwrap(), 2);
+        assert_eq!(*calls.lock(), 0);
+
+        store.flush();
+        assert_eq!(*calls.lock(), 1);
+
+        store.flush();
+        assert_eq!(*calls.lock(), 1);
+    }
+
+    // ============================================================================
+    // Store::get_async() Tests
+    // ============================================================================
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_get_async_dedupes_concurrent_reads() {
+        use crate::atom::{atom, atom_async};
+        use std::sync::atomic::AtomicUsize;
+
+        let store = Store::new();
+        let user_id = atom(1);
+        let reads = Arc::new(AtomicUsize::new(0));
+        let user = {
+            let (user_id, reads) = (user_id.clone(), reads.clone());
+            atom_async(move |get, _signal| {
+                reads.fetch_add(1, Ordering::SeqCst);
+                let id = get.get(user_id.as_atom());
+                async move {
+                    tokio::task::yield_now().await;
+                    Ok(format!("user-{}", id?))
+                }
+            })
+        };
+
+        let (first, second) = tokio::join!(store.get_async(&user), store.get_async(&user));
+        assert_eq!(first.unwrap(), "user-1");
+        assert_eq!(second.unwrap(), "user-1");
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+        assert!(store.in_flight.is_empty());
+
+        // A changed dependency starts a fresh evaluation
+        store.set(&user_id, 2).unwrap();
+        assert_eq!(store.get_async(&user).await.unwrap(), "user-2");
+        assert_eq!(reads.load(Ordering::SeqCst), 2);
+    }
+
+    // ============================================================================
+    // Store::set_async() Tests
+    // ============================================================================
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_set_async_keeps_value_on_success() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let title = atom("draft".to_string()).with_async_write(|_| async {
+            tokio::task::yield_now().await;
+            Ok(())
+        });
+
+        store.set_async(&title, "saved".to_string()).await.unwrap();
+        assert_eq!(store.get(title.as_atom()).unwrap(), "saved");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_set_async_rolls_back_on_failure() {
+        use crate::atom::atom;
+
+        let store = Store::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let title = atom("draft".to_string());
+        let title_id = title.id();
+
+        // The write effect observes the optimistic value before failing
+        let observer = store.handle();
+        let atom_for_effect = title.as_atom().clone();
+        let sink = seen.clone();
+        let title = title.with_async_write(move |_| {
+            sink.lock().push(observer.get(&atom_for_effect).unwrap());
+            async move {
+                tokio::task::yield_now().await;
+                Err(AtomError::write_error(title_id, "server unavailable"))
+            }
+        });
+
+        let result = store.set_async(&title, "published".to_string()).await;
+        assert!(matches!(result, Err(AtomError::WriteError { .. })));
+        assert_eq!(*seen.lock(), vec!["published".to_string()]);
+        assert_eq!(store.get(title.as_atom()).unwrap(), "draft");
     }
 
+    // ============================================================================
+    // Store::get_stale_ok() Tests
+    // ============================================================================
+
     #[test]
-    fn test_get_with_label() {
+    fn test_get_stale_ok_skips_recomputation() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let base = atom(1);
+        let source = base.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        // Nothing cached yet: computes
+        assert_eq!(store.get_stale_ok(&doubled).unwrap(), 2);
+
+        store.set(&base, 5).unwrap();
+        assert_eq!(store.get_stale_ok(&doubled).unwrap(), 2);
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert_eq!(store.get_stale_ok(&doubled).unwrap(), 10);
+    }
+
+    // ============================================================================
+    // Store::get_or() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_get_or_returns_value_on_success() {
         use crate::atom::atom;
 
         let store = Store::new();
-        let count = atom(5).with_label("counter");
+        let count = atom(3);
+        assert_eq!(store.get_or(count.as_atom(), 0), 3);
 
-        let value = store.get(&count.as_atom()).unwrap();
-        assert_eq!(value, 5);
-        assert_eq!(count.as_atom().debug_label(), Some("counter"));
+        store.set(&count, 7).unwrap();
+        assert_eq!(store.get_or(count.as_atom(), 0), 7);
+    }
+
+    #[test]
+    fn test_get_or_falls_back_when_uninitialized() {
+        use crate::atom::atom_derived;
+
+        let store = Store::new();
+        let not_loaded: Atom<i32> =
+            atom_derived(|_get| Err(AtomError::Uninitialized { atom_id: 0 }));
+        assert_eq!(store.get_or(&not_loaded, -1), -1);
+    }
+
+    #[test]
+    fn test_get_or_falls_back_on_read_error() {
+        use crate::atom::{atom, atom_derived};
+
+        let store = Store::new();
+        let input = atom("12".to_string());
+        let source = input.clone();
+        let parsed = atom_derived(move |get| {
+            get.get(source.as_atom())?
+                .parse::<i32>()
+                .map_err(|e| AtomError::read_error(0, e.to_string()))
+        });
+
+        assert_eq!(store.get_or(&parsed, 0), 12);
+        store.set(&input, "twelve".to_string()).unwrap();
+        assert_eq!(store.get_or(&parsed, 0), 0);
+        assert!(store.get(&parsed).is_err());
+    }
+
+    // ============================================================================
+    // atom_hot() Tests
+    // ============================================================================
+
+    #[cfg(feature = "hot")]
+    #[test]
+    fn test_hot_atom_reads_and_writes() {
+        use crate::atom::{atom_derived, atom_hot};
+
+        let store = Store::new();
+        let other_store = Store::new();
+        let count = atom_hot(1);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let seen = notified.clone();
+        let _unsub = store.sub_with_value(&doubled, move |value| seen.lock().push(value));
+
+        assert_eq!(store.get(count.as_atom()).unwrap(), 1);
+        store.set(&count, 5).unwrap();
+        assert_eq!(store.get(count.as_atom()).unwrap(), 5);
+        assert_eq!(store.get(&doubled).unwrap(), 10);
+        assert_eq!(*notified.lock(), vec![10]);
+
+        // Each store keeps its own cell
+        assert_eq!(other_store.get(count.as_atom()).unwrap(), 1);
+    }
+
+    #[cfg(feature = "hot")]
+    #[test]
+    fn test_hot_atom_set_before_first_read() {
+        use crate::atom::atom_hot;
+
+        let store = Store::new();
+        let name = atom_hot("initial".to_string());
+
+        store.set(&name, "written".to_string()).unwrap();
+        assert_eq!(store.get(name.as_atom()).unwrap(), "written");
+        store.set(&name, "again".to_string()).unwrap();
+        assert_eq!(store.get(name.as_atom()).unwrap(), "again");
+
+        store.retain(|_, _| false);
+        assert_eq!(store.get(name.as_atom()).unwrap(), "initial");
+    }
+
+    // ============================================================================
+    // Store::enable_interning() Tests
+    // ============================================================================
+
+    #[test]
+    fn test_interning_shares_equal_strings() {
+        use crate::atom::atom_interned;
+
+        let store = Store::new();
+        store.enable_interning();
+        let statuses: Vec<_> = (0..50).map(|_| atom_interned("idle")).collect();
+
+        for status in &statuses {
+            store.set(status, Arc::from("active")).unwrap();
+        }
+
+        let first = store.get(statuses[0].as_atom()).unwrap();
+        for status in &statuses[1..] {
+            let value = store.get(status.as_atom()).unwrap();
+            assert_eq!(&*value, "active");
+            assert!(Arc::ptr_eq(&value, &first));
+        }
     }
 
-    // TODO: Phase 1.4 - Add tests for set operation
-    // TODO: Phase 3.2 - Add tests for subscribe operation
-    // TODO: Phase 2.3 - Add tests for invalidation
-    // TODO: Phase 4.2 - Add tests for recomputation
+    #[test]
+    fn test_interning_disabled_keeps_separate_allocations() {
+        use crate::atom::atom_interned;
+
+        let store = Store::new();
+        let a = atom_interned("idle");
+        let b = atom_interned("idle");
+        store.set(&a, Arc::from("active")).unwrap();
+        store.set(&b, Arc::from("active")).unwrap();
+
+        let a = store.get(a.as_atom()).unwrap();
+        let b = store.get(b.as_atom()).unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+    }
 }
@@ -9,9 +9,21 @@
 //! - First-class functions: Functions as types (Getter, Setter)
 //! - Type-level programming: Complex trait bounds for safety
 
+use std::any::Any;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use crate::atom::Atom;
-use crate::error::Result;
+use std::time::Instant;
+#[cfg(feature = "async")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+#[cfg(feature = "async")]
+use futures::future::BoxFuture;
+#[cfg(feature = "async")]
+use parking_lot::Mutex;
+use crate::atom::{AnyAtom, AsAtom, WritableAtom};
+use crate::error::{AtomError, Result};
+use crate::store::StoreWriter;
 
 /// Unique identifier for each atom
 ///
@@ -31,6 +43,10 @@ pub type AtomId = usize;
 /// Each time an atom's value changes, its epoch increments. Dependencies
 /// store the epoch number they were computed with, enabling smart cache
 /// invalidation.
+///
+/// Epochs wrap around on overflow. They are only ever compared for
+/// equality ("has this atom changed since?"), never ordered, so a wrapped
+/// epoch still counts as a change.
 pub type EpochNumber = u64;
 
 /// Getter trait for reading atom values
@@ -42,26 +58,105 @@ pub type EpochNumber = u64;
 /// The Getter is passed to atom read functions, allowing them to access
 /// other atom values and automatically register dependencies.
 ///
-/// TODO: Implement dependency tracking during get() calls
-/// TODO: Handle type erasure for heterogeneous atom types
-/// TODO: Add error handling for missing/uninitialized atoms
+/// The trait itself only exposes a type-erased read so that it stays
+/// object-safe: read functions receive a `&dyn Getter` and use the typed
+/// `get` method defined on `dyn Getter` below.
+///
+/// **Rust Pattern**: Object-safe core method plus typed wrapper on the trait object
 pub trait Getter: Send + Sync {
+    /// Read an atom's current value as a boxed `Any`
+    ///
+    /// Implementations:
+    /// 1. Look up the atom's current state in the store
+    /// 2. Evaluate the atom's read function if the cache is stale
+    /// 3. Register a dependency relationship (when tracking)
+    /// 4. Return the value boxed for type erasure
+    fn get_erased(&self, atom: &dyn AnyAtom) -> Result<Box<dyn Any + Send + Sync>>;
+
+    /// The last successfully computed value of the atom being read, if any
+    ///
+    /// Only getters bound to a computation (the store's dependency tracker)
+    /// know which atom is being read; other getters return `None`.
+    fn previous_erased(&self) -> Option<Box<dyn Any + Send + Sync>> {
+        None
+    }
+
+    /// Resolve an atom's value asynchronously, as a boxed `Any`
+    ///
+    /// Reference: `get(asyncAtom)` returning a promise in Jotai
+    ///
+    /// The returned future owns everything it needs, so an async read
+    /// function can collect it synchronously and await it later. Only the
+    /// store's dependency tracker can resolve async atoms; other getters
+    /// return a future that fails.
+    #[cfg(feature = "async")]
+    fn get_async_erased(
+        &self,
+        atom: &dyn AnyAtom,
+    ) -> BoxFuture<'static, Result<Box<dyn Any + Send + Sync>>> {
+        let error = AtomError::async_error(atom.id(), "getter cannot resolve async atoms");
+        Box::pin(futures::future::ready(Err(error)))
+    }
+}
+
+impl dyn Getter + '_ {
     /// Read the current value of an atom
     ///
-    /// This function:
-    /// 1. Looks up the atom's current state in the store
-    /// 2. If not computed, evaluates the atom's read function
-    /// 3. Registers a dependency relationship
-    /// 4. Returns the cached value
+    /// This is the typed entry point used inside read functions:
+    ///
+    /// ```rust,ignore
+    /// let doubled = atom_derived(move |get| Ok(get.get(count.as_atom())? * 2));
+    /// ```
     ///
     /// # Type Safety
     ///
     /// The `T: 'static` bound ensures we can use type erasure safely.
+    pub fn get<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>) -> Result<T> {
+        let atom = atom.as_atom();
+        self.get_erased(atom)?
+            .downcast::<T>()
+            .map(|value| *value)
+            .map_err(|_| AtomError::type_mismatch::<T>(atom.id(), "unknown"))
+    }
+
+    /// The value the atom being computed produced last time in this store
     ///
-    /// TODO: Add caching based on epoch numbers
-    /// TODO: Implement lazy evaluation
-    /// TODO: Track dependencies for invalidation
-    fn get<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>) -> Result<T>;
+    /// Returns `None` on the first computation, after a failed one, or
+    /// when `T` is not the atom's value type.
+    pub fn previous<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.previous_erased()?
+            .downcast::<T>()
+            .ok()
+            .map(|value| *value)
+    }
+
+    /// Future resolving an atom's value, awaiting it if the atom is async
+    ///
+    /// Call it synchronously inside an async read function (so the
+    /// dependency is recorded) and await the future afterwards:
+    ///
+    /// ```rust,ignore
+    /// let user = atom_async(move |get, _signal| {
+    ///     let id = get.get_async(&user_id);
+    ///     async move { fetch_user(id.await?).await }
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn get_async<T: Clone + Send + Sync + 'static>(
+        &self,
+        atom: &impl AsAtom<T>,
+    ) -> BoxFuture<'static, Result<T>> {
+        let atom = atom.as_atom();
+        let atom_id = atom.id();
+        let future = self.get_async_erased(atom);
+        Box::pin(async move {
+            future
+                .await?
+                .downcast::<T>()
+                .map(|value| *value)
+                .map_err(|_| AtomError::type_mismatch::<T>(atom_id, "unknown"))
+        })
+    }
 }
 
 /// Setter trait for writing atom values
@@ -72,10 +167,6 @@ pub trait Getter: Send + Sync {
 ///
 /// The Setter is passed to atom write functions, allowing them to update
 /// the values of atoms (including other atoms).
-///
-/// TODO: Implement invalidation of dependent atoms on set
-/// TODO: Increment epoch numbers when values change
-/// TODO: Collect changed atoms for notification
 pub trait Setter: Send + Sync {
     /// Update the value of an atom
     ///
@@ -87,8 +178,7 @@ pub trait Setter: Send + Sync {
     ///
     /// TODO: Support SetStateAction pattern (value or updater function)
     /// TODO: Handle async/promise values
-    /// TODO: Trigger cascading updates
-    fn set<T: Clone + Send + Sync + 'static>(&self, atom: &Atom<T>, value: T) -> Result<()>;
+    fn set<T: Clone + Send + Sync + 'static>(&self, atom: &impl AsAtom<T>, value: T) -> Result<()>;
 }
 
 // TODO: Add set_state_action method in future phase
@@ -127,14 +217,51 @@ where
 /// Read functions should be pure - given the same dependencies,
 /// they should always return the same result.
 ///
-/// Note: We can't use `&dyn Getter` because Getter has generic methods.
-/// Instead, we'll pass a concrete type that implements read operations.
-/// For now, we use a placeholder that will be resolved during implementation.
+/// The store calls the read function with a `&dyn Getter` that records
+/// every atom read as a dependency. Primitive atoms ignore the getter and
+/// return their initial value; the store keeps their current value cached.
 ///
-/// TODO: Phase 1.3 - Decide on final type (likely &Store or similar)
 /// TODO: Add AbortSignal support for async operations
 /// TODO: Add SetSelf parameter for writable atoms
-pub type ReadFn<T> = Arc<dyn Fn() -> Result<T> + Send + Sync>;
+pub type ReadFn<T> = Arc<dyn Fn(&dyn Getter) -> Result<T> + Send + Sync>;
+
+/// Equality check deciding whether a recomputed value counts as a change
+///
+/// Reference: `Object.is` comparison in `jotai/src/vanilla/internals.ts`
+///
+/// When an atom's new value equals its previous one, the store keeps the
+/// old epoch, so dependents see no change and are not recomputed.
+pub type EqualityFn<T> = Arc<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+/// Hash used for change detection instead of an equality check
+///
+/// The store keeps the hash of an atom's current value and treats a
+/// recomputed value with the same hash as unchanged (see
+/// `atom_derived_hashed`).
+pub type HashFn<T> = fn(&T) -> u64;
+
+/// How long the store keeps an atom's computed value
+///
+/// Reference: no direct Jotai equivalent (Jotai caches every atom while referenced)
+///
+/// Set per atom with `atom_with_policy`; other atoms use `CacheForever`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Recompute on every read, never reusing the cached value
+    ///
+    /// Within one `Store::batch` or flush the value is computed at most
+    /// once (unless a dependency changes), so side-effecting reads do not
+    /// repeat while listeners run.
+    NoCache,
+
+    /// Keep the value until a dependency changes (the default)
+    #[default]
+    CacheForever,
+
+    /// Like `CacheForever`, but drop the value when the atom unmounts, so
+    /// the first read after that recomputes it
+    CacheWhileMounted,
+}
 
 /// Type alias for write functions
 ///
@@ -142,17 +269,134 @@ pub type ReadFn<T> = Arc<dyn Fn() -> Result<T> + Send + Sync>;
 ///
 /// **FP Pattern**: Higher-order functions, state transformations
 ///
-/// Write functions receive both getter and setter capabilities, allowing them to:
+/// Write functions receive a `StoreWriter`, allowing them to:
 /// 1. Read current state
 /// 2. Update multiple atoms
 /// 3. Perform complex state transformations
 ///
-/// Note: We can't use `&dyn Getter/Setter` due to generic methods.
-/// The actual implementation will pass the Store reference.
+/// `StoreWriter` is a concrete type, so its typed `set`/`update`/`reset`
+/// methods need no object-safe `&dyn Setter` workaround.
+///
+/// `R` is what a write hands back to its caller: `()` for ordinary writable
+/// atoms, anything for atoms made with `atom_writable_returning`.
 ///
-/// TODO: Phase 1.4 - Finalize signature with proper getter/setter access
 /// TODO: Support generic Args tuple for different write signatures
-pub type WriteFn<T> = Arc<dyn Fn(T) -> Result<()> + Send + Sync>;
+pub type WriteFn<T, R = ()> = Arc<dyn Fn(&StoreWriter<'_>, T) -> Result<R> + Send + Sync>;
+
+/// A function stored as an atom's value (see `atom_fn`)
+///
+/// Cloning shares the function, so it satisfies the `Clone + Send + Sync`
+/// bounds on atom values.
+pub type FnValue<X, Y> = Arc<dyn Fn(X) -> Y + Send + Sync>;
+
+/// Write function of a command atom (see `atom_command`)
+///
+/// Reference: `jotai/src/vanilla/atom.ts:22-26` (`Write<Args, Result>` with
+/// `Args` unrelated to `Value`)
+///
+/// Receives the writer, the atom holding the command atom's value `V`, and
+/// the dispatched action `A`.
+pub type CommandFn<V, A> =
+    Arc<dyn Fn(&StoreWriter<'_>, &WritableAtom<V>, A) -> Result<()> + Send + Sync>;
+
+/// Asynchronous write function (see `WritableAtom::with_async_write`)
+///
+/// **FP Pattern**: Effect described as a value (a boxed future)
+///
+/// Receives the value being written and resolves once the side effect (e.g.
+/// persisting to a server) has finished or failed.
+#[cfg(feature = "async")]
+pub type AsyncWriteFn<T> =
+    Arc<dyn Fn(T) -> futures::future::BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Type alias for asynchronous read functions (see `atom_async`)
+///
+/// Reference: `jotai/src/vanilla/atom.ts:17-20` (`Read` returning a promise, with `{ signal }`)
+///
+/// Called synchronously with a tracking getter (dependencies are the atoms
+/// read before the future is returned) and an `AbortSignal` that fires when
+/// this evaluation is superseded or its result is no longer wanted.
+#[cfg(feature = "async")]
+pub type AsyncReadFn<T> =
+    Arc<dyn Fn(&dyn Getter, AbortSignal) -> BoxFuture<'static, Result<T>> + Send + Sync>;
+
+/// Cancellation token handed to async read functions
+///
+/// Reference: `AbortController` / `AbortSignal` passed to Jotai read functions
+///
+/// The store aborts the signal of an async evaluation when a newer one of
+/// the same atom starts, or when the future awaiting it is dropped (e.g. it
+/// lost a `race_atom`). Read functions can poll `is_aborted` or await
+/// `aborted()` to stop work early. Clones share the same state.
+#[cfg(feature = "async")]
+#[derive(Clone, Default)]
+pub struct AbortSignal {
+    inner: Arc<AbortState>,
+}
+
+#[cfg(feature = "async")]
+#[derive(Default)]
+struct AbortState {
+    aborted: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+#[cfg(feature = "async")]
+impl AbortSignal {
+    /// Create a signal that has not been aborted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abort the signal, waking every task awaiting `aborted()`
+    pub fn abort(&self) {
+        self.inner.aborted.store(true, Ordering::SeqCst);
+        for waker in self.inner.wakers.lock().drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Whether `abort` has been called
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.load(Ordering::SeqCst)
+    }
+
+    /// Future that resolves once the signal is aborted
+    pub fn aborted(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let signal = self.clone();
+        futures::future::poll_fn(move |cx: &mut Context<'_>| {
+            if signal.is_aborted() {
+                return Poll::Ready(());
+            }
+            {
+                let mut wakers = signal.inner.wakers.lock();
+                if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    wakers.push(cx.waker().clone());
+                }
+            }
+            // Re-check: `abort` may have drained the wakers before the push
+            if signal.is_aborted() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Whether both handles belong to the same signal
+    pub(crate) fn same_as(&self, other: &AbortSignal) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+#[cfg(feature = "async")]
+impl std::fmt::Debug for AbortSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbortSignal")
+            .field("aborted", &self.is_aborted())
+            .finish()
+    }
+}
 
 /// Cleanup function returned by onMount callbacks
 ///
@@ -164,6 +408,15 @@ pub type WriteFn<T> = Arc<dyn Fn(T) -> Result<()> + Send + Sync>;
 /// TODO: Phase 8.1 - Implement lifecycle management with proper once semantics
 pub type OnUnmount = Box<dyn Fn() + Send + Sync>;
 
+/// Mount callback, called when an atom gains its first subscriber
+///
+/// Reference: `jotai/src/vanilla/atom.ts:62` (onMount)
+///
+/// Receives a `StoreWriter` for seeding state (Jotai's `setAtom`); writes
+/// through it run the target's write function and are notified in the same
+/// flush. May return an `OnUnmount` cleanup, run once the atom is unmounted.
+pub type OnMount = Arc<dyn Fn(&StoreWriter<'_>) -> Option<OnUnmount> + Send + Sync>;
+
 /// Listener callback for subscriptions
 ///
 /// Reference: `jotai/src/vanilla/internals.ts` (listeners in Mounted)
@@ -174,8 +427,211 @@ pub type OnUnmount = Box<dyn Fn() + Send + Sync>;
 /// They should not accept parameters - they should call store.get()
 /// to read the new value if needed.
 ///
-/// TODO: Phase 3 - Implement subscription system
-pub type Listener = Box<dyn Fn() + Send + Sync>;
+/// Stored as an `Arc` so the store can snapshot the listeners of an atom
+/// and call them without holding any lock (a listener may call `set`).
+pub type Listener = Arc<dyn Fn() + Send + Sync>;
+
+/// Identifier of a registered listener
+///
+/// Boxed closures have no identity, so each listener is stored alongside a
+/// per-atom counter value that `unsubscribe` uses to remove exactly it.
+pub type ListenerId = u64;
+
+/// Notification delivered to bounded subscribers (see `Store::sub_bounded`)
+///
+/// **FP Pattern**: Immutable message value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notification {
+    /// Epoch of the atom when the change was queued
+    pub epoch: EpochNumber,
+
+    /// Notifications dropped since the previous delivery because the queue was full
+    pub dropped: usize,
+}
+
+/// Structured view of one mounted atom (see `Store::mounted_topology`)
+///
+/// Reference: `jotai/src/vanilla/internals.ts` (Mounted type)
+///
+/// Mirrors the store's internal `Mounted` entry as plain data, with edge
+/// lists sorted by ID so snapshots compare deterministically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountedInfo {
+    /// ID of the mounted atom
+    pub atom_id: AtomId,
+
+    /// Number of listeners subscribed directly to this atom
+    pub listener_count: usize,
+
+    /// Mounted atoms this atom reads
+    pub dependencies: Vec<AtomId>,
+
+    /// Mounted atoms that read this atom
+    pub dependents: Vec<AtomId>,
+
+    /// Whether an unmount cleanup is registered
+    pub has_cleanup: bool,
+}
+
+/// A stand-in value for one atom, used by `Store::get_with_overrides`
+///
+/// The value's type is erased so overrides for atoms of different types fit
+/// in one slice; `new` ties it to an atom of the matching type.
+#[derive(Clone)]
+pub struct ErasedOverride {
+    atom_id: AtomId,
+    value: Arc<dyn Any + Send + Sync>,
+    clone_value: fn(&(dyn Any + Send + Sync)) -> Box<dyn Any + Send + Sync>,
+}
+
+impl ErasedOverride {
+    /// Override `atom` with `value`
+    pub fn new<T: Clone + Send + Sync + 'static>(atom: &impl AsAtom<T>, value: T) -> Self {
+        ErasedOverride {
+            atom_id: atom.as_atom().id(),
+            value: Arc::new(value),
+            clone_value: |value| {
+                let value = value
+                    .downcast_ref::<T>()
+                    .expect("an override holds a value of its atom's type");
+                Box::new(value.clone())
+            },
+        }
+    }
+
+    /// ID of the overridden atom
+    pub fn atom_id(&self) -> AtomId {
+        self.atom_id
+    }
+
+    /// A fresh boxed copy of the override value
+    pub(crate) fn value_boxed(&self) -> Box<dyn Any + Send + Sync> {
+        (self.clone_value)(&*self.value)
+    }
+}
+
+impl std::fmt::Debug for ErasedOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErasedOverride")
+            .field("atom_id", &self.atom_id)
+            .finish()
+    }
+}
+
+/// Context passed through the set middleware chain (see `Store::use_middleware`)
+///
+/// Carries the atom being written and the type-erased value. Middleware may
+/// inspect or replace the value before handing the context to `next`.
+pub struct SetContext {
+    /// ID of the atom being set
+    pub atom_id: AtomId,
+
+    /// Debug label of the atom being set, if any
+    pub label: Option<String>,
+
+    /// The value about to be written
+    pub value: Box<dyn Any + Send + Sync>,
+}
+
+impl SetContext {
+    /// The value being written, if it has type `T`
+    pub fn value<T: 'static>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+
+    /// Mutable access to the value being written, if it has type `T`
+    pub fn value_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.value.downcast_mut::<T>()
+    }
+}
+
+impl std::fmt::Debug for SetContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SetContext")
+            .field("atom_id", &self.atom_id)
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+/// Continuation handed to a middleware: runs the rest of the chain
+pub type Next<'a> = &'a dyn Fn(SetContext) -> Result<()>;
+
+/// Set middleware
+///
+/// **FP Pattern**: Continuation-passing style (like Redux middleware)
+///
+/// Calling `next(ctx)` continues with the following middleware and finally
+/// the write itself; returning without calling it short-circuits the set.
+pub type Middleware = Arc<dyn Fn(SetContext, Next<'_>) -> Result<()> + Send + Sync>;
+
+/// Which kind of user closure panicked (see `PanicInfo`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicSite {
+    /// An atom's read function
+    Read,
+    /// An atom's write function (or a command atom's action handler)
+    Write,
+    /// A subscription listener
+    Listener,
+}
+
+/// A panic caught by the store, passed to its panic handler
+///
+/// See `Store::set_panic_handler`.
+#[derive(Debug, Clone)]
+pub struct PanicInfo {
+    /// The atom whose closure panicked
+    pub atom_id: AtomId,
+
+    /// Which closure panicked
+    pub site: PanicSite,
+
+    /// The panic message, when the payload was a string
+    pub message: String,
+}
+
+/// Panic handler (see `Store::set_panic_handler`)
+pub type PanicHandler = Arc<dyn Fn(PanicInfo) + Send + Sync>;
+
+/// One atom's value in a `StoreSnapshot`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    /// `std::any::type_name` of the value type, selecting its codec
+    pub type_name: String,
+
+    /// The value, encoded with the codec registered for its type
+    pub value: String,
+}
+
+/// Serialized values of a store's primitive atoms, by atom ID
+///
+/// Produced by `Store::snapshot` and reinstalled by `Store::restore`. The
+/// fields are plain strings, so the snapshot can be persisted in any
+/// format. Atom IDs are assigned in creation order, so a snapshot restores
+/// into the same program creating its atoms in the same order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreSnapshot {
+    /// Entries ordered by atom ID
+    pub entries: BTreeMap<AtomId, SnapshotEntry>,
+}
+
+/// One recorded change of an atom (see `Store::enable_history`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    /// ID of the atom that changed
+    pub atom_id: AtomId,
+
+    /// The atom's epoch after the change
+    pub epoch: EpochNumber,
+
+    /// When the change was recorded
+    pub at: Instant,
+
+    /// The new value, encoded when its type is registered with
+    /// `Store::register_snapshot_type` and the change produced a value
+    pub value: Option<String>,
+}
 
 /// Unsubscribe function returned by store.sub()
 ///
@@ -189,6 +645,131 @@ pub type Listener = Box<dyn Fn() + Send + Sync>;
 /// TODO: Phase 3.2 - Implement in store.sub() with proper once semantics
 pub type Unsubscribe = Box<dyn Fn() + Send + Sync>;
 
+/// A subscription that unsubscribes when dropped
+///
+/// Returned by `store.subscribe()`. Holds the same `Unsubscribe` function
+/// `store.sub()` returns and calls it exactly once, when the guard is
+/// dropped or `unsubscribe` is called.
+///
+/// **FP Pattern**: Resource scope (bracket / RAII)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// {
+///     let _subscription = store.subscribe(&count, render);
+///     store.set(&count, 1)?; // render runs
+/// }
+/// store.set(&count, 2)?; // the listener is gone
+/// ```
+#[must_use = "dropping a Subscription unsubscribes immediately"]
+pub struct Subscription {
+    unsubscribe: Option<Unsubscribe>,
+}
+
+impl Subscription {
+    /// Guard an unsubscribe function
+    pub fn new(unsubscribe: Unsubscribe) -> Self {
+        Subscription {
+            unsubscribe: Some(unsubscribe),
+        }
+    }
+
+    /// Unsubscribe now (same as dropping the guard)
+    pub fn unsubscribe(mut self) {
+        self.release();
+    }
+
+    /// Keep the subscription for the rest of the store's lifetime
+    pub fn detach(mut self) {
+        self.unsubscribe = None;
+    }
+
+    fn release(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("active", &self.unsubscribe.is_some())
+            .finish()
+    }
+}
+
+/// A collection of subscriptions torn down together
+///
+/// Holds the `Unsubscribe` functions of everything a component subscribed
+/// to. `clear` (or dropping the set) calls each of them once.
+///
+/// **FP Pattern**: Resource scope (bracket / RAII)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut subscriptions = SubscriptionSet::new();
+/// subscriptions.add(store.sub(&title, render));
+/// subscriptions.add(store.sub(&count, render));
+/// // ... later, when the component goes away
+/// subscriptions.clear();
+/// ```
+#[derive(Default)]
+pub struct SubscriptionSet {
+    unsubscribes: Vec<Unsubscribe>,
+}
+
+impl SubscriptionSet {
+    /// An empty set
+    pub fn new() -> Self {
+        SubscriptionSet::default()
+    }
+
+    /// Take ownership of a subscription's unsubscribe function
+    pub fn add(&mut self, unsubscribe: Unsubscribe) {
+        self.unsubscribes.push(unsubscribe);
+    }
+
+    /// Number of subscriptions held
+    pub fn len(&self) -> usize {
+        self.unsubscribes.len()
+    }
+
+    /// Whether the set holds no subscriptions
+    pub fn is_empty(&self) -> bool {
+        self.unsubscribes.is_empty()
+    }
+
+    /// Unsubscribe everything, in the order the subscriptions were added
+    pub fn clear(&mut self) {
+        for unsubscribe in self.unsubscribes.drain(..) {
+            unsubscribe();
+        }
+    }
+}
+
+impl Drop for SubscriptionSet {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl std::fmt::Debug for SubscriptionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubscriptionSet")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,5 +794,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_subscription_set_clear_unsubscribes_all() {
+        use crate::atom::{atom, atom_derived};
+        use crate::store::Store;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let store = Store::new();
+        let count = atom(0);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut subscriptions = SubscriptionSet::new();
+        for target in [count.as_atom(), &doubled, count.as_atom()] {
+            let calls = calls.clone();
+            subscriptions.add(store.sub(target, move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+        assert_eq!(subscriptions.len(), 3);
+
+        store.set(&count, 1).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        subscriptions.clear();
+        assert!(subscriptions.is_empty());
+        store.set(&count, 2).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(!store.mounted.contains_key(&count.id()));
+    }
+
     // TODO: Add tests for Getter and Setter traits once implemented
 }
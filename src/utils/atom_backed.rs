@@ -0,0 +1,95 @@
+//! Atoms that read through to a persistent backend
+//!
+//! A backed atom asks its backend for a value the first time it is read in
+//! a store, falling back to the initial value when the backend has none.
+//! Writes stay in the store: nothing is written back to the backend.
+//!
+//! ## Functional Programming Patterns
+//! - Lazy initialization (the backend is consulted once per store)
+//! - Strategy pattern (the backend is pluggable)
+
+use crate::atom::{atom_computed_default, PrimitiveAtom};
+
+/// A source of stored values, looked up by key
+///
+/// Implemented for any `Fn(&str) -> Option<T>`, so a closure over a map or
+/// a hydration payload works as a backend.
+pub trait AtomBackend<T>: Send + Sync {
+    /// The stored value for `key`, if any
+    fn load(&self, key: &str) -> Option<T>;
+}
+
+impl<T, F> AtomBackend<T> for F
+where
+    F: Fn(&str) -> Option<T> + Send + Sync,
+{
+    fn load(&self, key: &str) -> Option<T> {
+        self(key)
+    }
+}
+
+/// Create a primitive atom whose first read loads from `backend`
+///
+/// On the first read in a store, `backend.load(key)` supplies the value; if
+/// it returns `None`, `initial` is used instead. Afterwards the atom behaves
+/// like a primitive atom in that store. Unlike a storage-synced atom, `set`
+/// does not write back to the backend.
+///
+/// **FP Pattern**: Read-through cache
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let payload = hydration_payload.clone();
+/// let theme = atom_backed("theme", "light".to_string(), move |key: &str| {
+///     payload.get(key).cloned()
+/// });
+/// ```
+pub fn atom_backed<T, B>(key: impl Into<String>, initial: T, backend: B) -> PrimitiveAtom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    B: AtomBackend<T> + 'static,
+{
+    let key = key.into();
+    atom_computed_default(move |_get| Ok(backend.load(&key).unwrap_or_else(|| initial.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_atom_backed_loads_from_backend_on_first_read() {
+        let stored = HashMap::from([("theme".to_string(), "dark".to_string())]);
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = loads.clone();
+        let backend = move |key: &str| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            stored.get(key).cloned()
+        };
+
+        let store = Store::new();
+        let theme = atom_backed("theme", "light".to_string(), backend);
+
+        assert_eq!(store.get(theme.as_atom()).unwrap(), "dark");
+        assert_eq!(store.get(theme.as_atom()).unwrap(), "dark");
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // Writes stay in the store
+        store.set(&theme, "solarized".to_string()).unwrap();
+        assert_eq!(store.get(theme.as_atom()).unwrap(), "solarized");
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_atom_backed_falls_back_to_initial() {
+        let store = Store::new();
+        let volume = atom_backed("volume", 7, |_key: &str| None);
+
+        assert_eq!(store.get(volume.as_atom()).unwrap(), 7);
+    }
+}
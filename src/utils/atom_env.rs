@@ -0,0 +1,135 @@
+//! Atoms mirroring environment variables
+//!
+//! An env atom reads its variable the first time it is read in a store and
+//! parses it, falling back to a default when the variable is unset or does
+//! not parse. `Store::reload_env` re-reads the variable later, so derived
+//! configuration reacts to changes made at runtime.
+//!
+//! ## Functional Programming Patterns
+//! - Lazy initialization (the variable is read once per store)
+//! - Explicit effects (re-reading the environment is a separate, named step)
+
+use crate::atom::{atom_computed_default, Atom, PrimitiveAtom};
+use crate::error::Result;
+use crate::store::Store;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// An atom holding the parsed value of an environment variable
+///
+/// Created with `atom_env`. Read it through `as_atom()`; refresh it with
+/// `Store::reload_env`. It can also be `set` like a primitive atom, which
+/// overrides the value until the next reload.
+#[derive(Clone)]
+pub struct EnvAtom<T: Clone + Send + Sync + 'static> {
+    /// Name of the mirrored variable
+    var_name: Arc<str>,
+
+    /// Value used when the variable is unset or unparsable
+    default: T,
+
+    /// The atom holding the current value
+    atom: PrimitiveAtom<T>,
+}
+
+impl<T> EnvAtom<T>
+where
+    T: FromStr + Clone + PartialEq + Send + Sync + 'static,
+{
+    /// The atom holding the variable's value
+    pub fn as_atom(&self) -> &Atom<T> {
+        self.atom.as_atom()
+    }
+
+    /// Name of the mirrored variable
+    pub fn var_name(&self) -> &str {
+        &self.var_name
+    }
+
+    /// Re-read the variable into `store`, returning whether the value changed
+    ///
+    /// Only writes when the parsed value differs, so dependents are not
+    /// invalidated by a reload that finds the same value.
+    pub(crate) fn reload(&self, store: &Store) -> Result<bool> {
+        let value = read_var(&self.var_name, &self.default);
+        if store.get(self.as_atom())? == value {
+            return Ok(false);
+        }
+        store.set(&self.atom, value)?;
+        Ok(true)
+    }
+}
+
+/// Parse `var_name` from the environment, or fall back to `default`
+fn read_var<T: FromStr + Clone>(var_name: &str, default: &T) -> T {
+    std::env::var(var_name)
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or_else(|| default.clone())
+}
+
+/// Create an atom mirroring the environment variable `var_name`
+///
+/// On the first read in a store, the variable is parsed with `FromStr`;
+/// `default` is used if it is unset, not valid Unicode, or fails to parse.
+/// Later changes to the environment are picked up by `Store::reload_env`.
+///
+/// **FP Pattern**: Read-through cache with explicit refresh
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let port = atom_env("PORT", 8080u16);
+/// let address = atom_derived(move |get| Ok(format!("0.0.0.0:{}", get.get(port.as_atom())?)));
+///
+/// store.reload_env(&port)?; // after the environment changes
+/// ```
+pub fn atom_env<T>(var_name: impl Into<String>, default: T) -> EnvAtom<T>
+where
+    T: FromStr + Clone + PartialEq + Send + Sync + 'static,
+{
+    let var_name: Arc<str> = Arc::from(var_name.into());
+    let (name, fallback) = (var_name.clone(), default.clone());
+    let atom = atom_computed_default(move |_get| Ok(read_var(&name, &fallback)))
+        .with_label(format!("env:{}", var_name));
+
+    EnvAtom {
+        var_name,
+        default,
+        atom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom_derived;
+
+    #[test]
+    fn test_atom_env_reload_updates_dependents() {
+        const VAR: &str = "JOTAI_RS_TEST_ATOM_ENV_PORT";
+        std::env::remove_var(VAR);
+
+        let store = Store::new();
+        let port = atom_env(VAR, 8080u16);
+        let source = port.clone();
+        let address =
+            atom_derived(move |get| Ok(format!("localhost:{}", get.get(source.as_atom())?)));
+
+        assert_eq!(store.get(&address).unwrap(), "localhost:8080");
+
+        std::env::set_var(VAR, "3000");
+        // Not re-read until reloaded
+        assert_eq!(store.get(&address).unwrap(), "localhost:8080");
+        assert!(store.reload_env(&port).unwrap());
+        assert_eq!(store.get(&address).unwrap(), "localhost:3000");
+        assert!(!store.reload_env(&port).unwrap());
+
+        // Unparsable values fall back to the default
+        std::env::set_var(VAR, "not a port");
+        assert!(store.reload_env(&port).unwrap());
+        assert_eq!(store.get(port.as_atom()).unwrap(), 8080);
+
+        std::env::remove_var(VAR);
+    }
+}
@@ -11,10 +11,119 @@
 //! - Closures (captures state in returned function)
 //! - Factory pattern
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::hash::Hash;
+use std::time::Instant;
 use crate::atom::Atom;
+use crate::types::Unsubscribe;
+
+/// Current time in milliseconds, standing in for `Date.now()` in Jotai
+///
+/// Measured on a monotonic clock from the first call in the process, so
+/// creation times never go backwards when the wall clock is adjusted.
+fn now_millis() -> i64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_millis() as i64
+}
+
+/// Access-order bookkeeping for bounded (LRU) atom families
+///
+/// Each access stamps the parameter with a monotonically increasing tick.
+/// `order` maps ticks back to parameters so the least-recently-used entry
+/// is always the first key.
+///
+/// **FP Pattern**: Memoization with bounded cache size
+struct LruIndex<P> {
+    /// Maximum number of cached members
+    capacity: usize,
+
+    /// Next access tick to hand out
+    tick: u64,
+
+    /// Last access tick for each cached parameter
+    last_access: HashMap<P, u64>,
+
+    /// Parameters ordered by last access (oldest first)
+    order: BTreeMap<u64, P>,
+}
+
+impl<P: Clone + Eq + Hash> LruIndex<P> {
+    fn new(capacity: usize) -> Self {
+        LruIndex {
+            capacity,
+            tick: 0,
+            last_access: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Mark a parameter as most recently used
+    fn touch(&mut self, param: &P) {
+        if let Some(prev) = self.last_access.insert(param.clone(), self.tick) {
+            self.order.remove(&prev);
+        }
+        self.order.insert(self.tick, param.clone());
+        self.tick += 1;
+    }
+
+    /// Forget a parameter (after removal from the cache)
+    fn forget(&mut self, param: &P) {
+        if let Some(prev) = self.last_access.remove(param) {
+            self.order.remove(&prev);
+        }
+    }
+
+    /// Parameters that must be evicted to get back within capacity
+    fn overflow(&self) -> Vec<P> {
+        let excess = self.last_access.len().saturating_sub(self.capacity);
+        self.order.values().take(excess).cloned().collect()
+    }
+}
+
+/// Change to a family's cached members, delivered to `AtomFamily::listen`
+///
+/// Reference: `jotai/src/vanilla/utils/atomFamily.ts` (`Callback`)
+///
+/// ```typescript
+/// type Callback<Param, AtomType> = (event: {
+///   type: 'CREATE' | 'REMOVE'
+///   param: Param
+///   atom: AtomType
+/// }) => void
+/// ```
+#[derive(Clone, Debug)]
+pub enum FamilyEvent<P, T: Clone + Send + Sync + 'static> {
+    /// A member was created and cached
+    Create {
+        /// Parameter of the new member
+        param: P,
+        /// The new atom
+        atom: Atom<T>,
+    },
+    /// A member was dropped from the cache
+    Remove {
+        /// Parameter of the removed member
+        param: P,
+        /// The removed atom
+        atom: Atom<T>,
+    },
+}
+
+/// Family listener callback
+type FamilyListener<P, T> = Arc<dyn Fn(&FamilyEvent<P, T>) + Send + Sync>;
+
+/// Registered family listeners, keyed by registration
+type FamilyListeners<P, T> = Arc<Mutex<Vec<(usize, FamilyListener<P, T>)>>>;
+
+/// Cached members with their creation time, keyed by parameter
+type FamilyCache<P, T> = HashMap<P, (Atom<T>, i64)>;
+
+/// Custom parameter equality
+type ParamEquality<P> = Arc<dyn Fn(&P, &P) -> bool + Send + Sync>;
+
+/// Expiry predicate over a member's creation time and parameter
+type ShouldRemove<P> = Arc<dyn Fn(i64, &P) -> bool + Send + Sync>;
 
 /// Atom family function type
 ///
@@ -30,8 +139,6 @@ use crate::atom::Atom;
 /// ```
 ///
 /// **FP Pattern**: Function with attached methods (closure with state)
-///
-/// TODO: Phase 7.1 - Implement atom family
 pub struct AtomFamily<P, T>
 where
     P: Clone + Eq + Hash + Send + Sync + 'static,
@@ -45,14 +152,10 @@ where
     /// Cache of created atoms, keyed by parameter
     ///
     /// **FP Pattern**: Memoization with HashMap
-    ///
-    /// TODO: Phase 7.1 - Use for atom caching
-    cache: Arc<Mutex<HashMap<P, (Atom<T>, i64)>>>,
+    cache: Arc<Mutex<FamilyCache<P, T>>>,
 
     /// Optional custom equality function
-    ///
-    /// TODO: Phase 7.1 - Support custom equality
-    are_equal: Option<Arc<dyn Fn(&P, &P) -> bool + Send + Sync>>,
+    are_equal: Option<ParamEquality<P>>,
 
     /// Optional function to determine if cached atoms should be removed
     ///
@@ -62,8 +165,21 @@ where
     /// type ShouldRemove<Param> = (createdAt: CreatedAt, param: Param) => boolean
     /// ```
     ///
-    /// TODO: Phase 7.1 - Support automatic cleanup
-    should_remove: Arc<Mutex<Option<Arc<dyn Fn(i64, &P) -> bool + Send + Sync>>>>,
+    /// `createdAt` comes from a monotonic clock (see `now_millis`).
+    should_remove: Arc<Mutex<Option<ShouldRemove<P>>>>,
+
+    /// Optional access-order index bounding the cache size
+    ///
+    /// Only present for families created with `atom_family_lru`.
+    lru: Option<Arc<Mutex<LruIndex<P>>>>,
+
+    /// Listeners for member creation and removal, keyed by registration
+    ///
+    /// Reference: `jotai/src/vanilla/utils/atomFamily.ts` (`listeners`)
+    listeners: FamilyListeners<P, T>,
+
+    /// Next listener registration key
+    next_listener: Arc<Mutex<usize>>,
 }
 
 impl<P, T> AtomFamily<P, T>
@@ -91,16 +207,77 @@ where
     /// }
     /// ```
     ///
-    /// **FP Pattern**: Memoization, lazy initialization
+    /// For bounded families, every call also refreshes the parameter's recency
+    /// and evicts the least-recently-used members that exceed the capacity.
     ///
-    /// TODO: Phase 7.1 - Implement with caching logic
+    /// **FP Pattern**: Memoization, lazy initialization
     pub fn get(&self, param: P) -> Atom<T> {
-        // TODO: Check cache for existing atom
-        // TODO: If exists and not expired, return it
-        // TODO: Otherwise, call initialize_atom
-        // TODO: Cache the new atom with timestamp
-        // TODO: Return the atom
-        todo!("AtomFamily::get - Phase 7.1")
+        self.evict_if_expired(&param);
+
+        let (key, atom, created) = {
+            let mut cache = self.cache.lock().unwrap();
+            match self.find_key(&cache, &param) {
+                Some(key) => {
+                    let atom = cache[&key].0.clone();
+                    (key, atom, false)
+                }
+                None => {
+                    let atom = (self.initialize_atom)(param.clone());
+                    cache.insert(param.clone(), (atom.clone(), now_millis()));
+                    (param, atom, true)
+                }
+            }
+        };
+
+        if created {
+            self.notify(&FamilyEvent::Create {
+                param: key.clone(),
+                atom: atom.clone(),
+            });
+        }
+
+        if let Some(lru) = &self.lru {
+            let evicted = {
+                let mut lru = lru.lock().unwrap();
+                lru.touch(&key);
+                lru.overflow()
+            };
+            for param in evicted {
+                self.remove(&param);
+            }
+        }
+
+        atom
+    }
+
+    /// Remove the cached member for `param` if `should_remove` says so
+    ///
+    /// The predicate runs outside the cache lock, so it may call back into
+    /// the family.
+    fn evict_if_expired(&self, param: &P) {
+        let Some(should_remove) = self.should_remove.lock().unwrap().clone() else {
+            return;
+        };
+        let cached = {
+            let cache = self.cache.lock().unwrap();
+            self.find_key(&cache, param).map(|key| (cache[&key].1, key))
+        };
+        if let Some((created_at, key)) = cached {
+            if should_remove(created_at, &key) {
+                self.remove(&key);
+            }
+        }
+    }
+
+    /// Find the cached key matching `param`
+    ///
+    /// Uses the custom equality function when one was provided, mirroring
+    /// the linear scan Jotai performs with `areEqual`.
+    fn find_key(&self, cache: &FamilyCache<P, T>, param: &P) -> Option<P> {
+        match &self.are_equal {
+            Some(are_equal) => cache.keys().find(|key| are_equal(key, param)).cloned(),
+            None => cache.contains_key(param).then(|| param.clone()),
+        }
     }
 
     /// Get all parameters that have atoms created
@@ -111,10 +288,8 @@ where
     /// createAtom.getParams = () => atoms.keys()
     /// ```
     ///
-    /// TODO: Phase 7.1 - Return iterator over cached params
     pub fn get_params(&self) -> Vec<P> {
-        // TODO: Get all keys from cache
-        todo!("AtomFamily::get_params - Phase 7.1")
+        self.cache.lock().unwrap().keys().cloned().collect()
     }
 
     /// Remove an atom from the family
@@ -130,11 +305,109 @@ where
     /// }
     /// ```
     ///
-    /// TODO: Phase 7.1 - Implement removal from cache
+    /// LRU eviction goes through this method as well, so evicted members are
+    /// reported exactly like explicit removals.
     pub fn remove(&self, param: &P) {
-        // TODO: Remove from cache
-        // TODO: Notify listeners if implemented
-        todo!("AtomFamily::remove - Phase 7.1")
+        let Some((atom, _)) = self.cache.lock().unwrap().remove(param) else {
+            return;
+        };
+        if let Some(lru) = &self.lru {
+            lru.lock().unwrap().forget(param);
+        }
+        self.notify(&FamilyEvent::Remove {
+            param: param.clone(),
+            atom,
+        });
+    }
+
+    /// Recreate every cached member with the family's initializer
+    ///
+    /// Each cached parameter gets a fresh atom (with a new ID) from
+    /// `initialize_atom`, which matters when the initializer reads outside
+    /// configuration that has since changed. Listeners see a `Remove` for
+    /// the old atom followed by a `Create` for the new one.
+    ///
+    /// Values held in a store belong to the old atoms. `migrate`, when
+    /// given, runs for every member as `migrate(param, old, new)` before
+    /// the `Create` notification, so it can carry values over.
+    ///
+    /// **FP Pattern**: Memo invalidation with an explicit migration step
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// todos.reinitialize(Some(|_id: &u32, old: &Atom<Todo>, new: &Atom<Todo>| {
+    ///     if let Ok(todo) = store.get(old) {
+    ///         seed_todo(&store, new, todo);
+    ///     }
+    /// }));
+    /// ```
+    pub fn reinitialize<F>(&self, migrate: Option<F>)
+    where
+        F: Fn(&P, &Atom<T>, &Atom<T>),
+    {
+        let params = self.get_params();
+        for param in params {
+            let old = match self.cache.lock().unwrap().get(&param) {
+                Some((atom, _)) => atom.clone(),
+                None => continue,
+            };
+            let new = (self.initialize_atom)(param.clone());
+            self.cache
+                .lock()
+                .unwrap()
+                .insert(param.clone(), (new.clone(), now_millis()));
+
+            self.notify(&FamilyEvent::Remove {
+                param: param.clone(),
+                atom: old.clone(),
+            });
+            if let Some(migrate) = &migrate {
+                migrate(&param, &old, &new);
+            }
+            self.notify(&FamilyEvent::Create { param, atom: new });
+        }
+    }
+
+    /// Listen for members being created or removed
+    ///
+    /// Reference: `jotai/src/vanilla/utils/atomFamily.ts` (`unstable_listen`)
+    ///
+    /// ```typescript
+    /// createAtom.unstable_listen = (callback: Callback<Param, AtomType>) => {
+    ///   listeners.add(callback)
+    ///   return () => { listeners.delete(callback) }
+    /// }
+    /// ```
+    pub fn listen<F>(&self, listener: F) -> Unsubscribe
+    where
+        F: Fn(&FamilyEvent<P, T>) + Send + Sync + 'static,
+    {
+        let key = {
+            let mut next = self.next_listener.lock().unwrap();
+            *next += 1;
+            *next
+        };
+        self.listeners.lock().unwrap().push((key, Arc::new(listener)));
+
+        let listeners = self.listeners.clone();
+        Box::new(move || {
+            listeners.lock().unwrap().retain(|(id, _)| *id != key);
+        })
+    }
+
+    /// Deliver an event to every listener, outside the listener lock
+    fn notify(&self, event: &FamilyEvent<P, T>) {
+        let listeners: Vec<FamilyListener<P, T>> = self
+            .listeners
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener(event);
+        }
     }
 
     /// Set the function that determines if atoms should be auto-removed
@@ -154,14 +427,32 @@ where
     /// }
     /// ```
     ///
-    /// TODO: Phase 7.1 - Implement with automatic cleanup
+    /// The existing cache is swept immediately, and later `get` calls
+    /// re-create a member the predicate considers expired. Removals are
+    /// reported to listeners like explicit ones.
     pub fn set_should_remove<F>(&self, should_remove: Option<F>)
     where
         F: Fn(i64, &P) -> bool + Send + Sync + 'static,
     {
-        // TODO: Store the should_remove function
-        // TODO: Immediately run cleanup on existing atoms
-        todo!("AtomFamily::set_should_remove - Phase 7.1")
+        let should_remove = should_remove.map(|f| Arc::new(f) as ShouldRemove<P>);
+        *self.should_remove.lock().unwrap() = should_remove.clone();
+        let Some(should_remove) = should_remove else {
+            return;
+        };
+
+        // Snapshot first so the predicate runs outside the cache lock
+        let entries: Vec<(P, i64)> = self
+            .cache
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(param, (_, created_at))| (param.clone(), *created_at))
+            .collect();
+        for (param, created_at) in entries {
+            if should_remove(created_at, &param) {
+                self.remove(&param);
+            }
+        }
     }
 }
 
@@ -194,36 +485,66 @@ where
 /// let counter1_again = counter_family.get(1); // Returns cached atom
 /// ```
 ///
-/// TODO: Phase 7.1 - Implement atom_family
 pub fn atom_family<P, T, F>(initialize_atom: F) -> AtomFamily<P, T>
 where
     P: Clone + Eq + Hash + Send + Sync + 'static,
     T: Clone + Send + Sync + 'static,
     F: Fn(P) -> Atom<T> + Send + Sync + 'static,
 {
-    // TODO: Create AtomFamily with:
-    // - initialize_atom function
-    // - Empty cache
-    // - No custom equality
-    // - No should_remove
-    todo!("atom_family - Phase 7.1")
+    AtomFamily {
+        initialize_atom: Arc::new(initialize_atom),
+        cache: Arc::new(Mutex::new(HashMap::new())),
+        are_equal: None,
+        should_remove: Arc::new(Mutex::new(None)),
+        lru: None,
+        listeners: Arc::new(Mutex::new(Vec::new())),
+        next_listener: Arc::new(Mutex::new(0)),
+    }
 }
 
 /// Create an atom family with custom equality
 ///
-/// TODO: Phase 7.1 - Support custom equality for complex parameter types
-pub fn atom_family_with_equality<P, T, F, E>(
-    initialize_atom: F,
-    are_equal: E,
-) -> AtomFamily<P, T>
+/// Lookups scan the cached parameters with `are_equal`, so two distinct
+/// parameters that compare equal share a single atom.
+pub fn atom_family_with_equality<P, T, F, E>(initialize_atom: F, are_equal: E) -> AtomFamily<P, T>
 where
     P: Clone + Eq + Hash + Send + Sync + 'static,
     T: Clone + Send + Sync + 'static,
     F: Fn(P) -> Atom<T> + Send + Sync + 'static,
     E: Fn(&P, &P) -> bool + Send + Sync + 'static,
 {
-    // TODO: Similar to atom_family but with custom equality
-    todo!("atom_family_with_equality - Phase 7.1")
+    AtomFamily {
+        are_equal: Some(Arc::new(are_equal)),
+        ..atom_family(initialize_atom)
+    }
+}
+
+/// Create an atom family holding at most `capacity` members
+///
+/// When a new member would push the cache past `capacity`, the
+/// least-recently-accessed member is evicted through `AtomFamily::remove`.
+/// Every `get` counts as an access, whether it hits the cache or not.
+///
+/// **FP Pattern**: Memoization with bounded memory (LRU eviction)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom, atom_family_lru};
+///
+/// let rows = atom_family_lru(100, |id: u32| atom(id).as_atom().clone());
+/// let row = rows.get(7);
+/// ```
+pub fn atom_family_lru<P, T, F>(capacity: usize, initialize_atom: F) -> AtomFamily<P, T>
+where
+    P: Clone + Eq + Hash + Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+    F: Fn(P) -> Atom<T> + Send + Sync + 'static,
+{
+    AtomFamily {
+        lru: Some(Arc::new(Mutex::new(LruIndex::new(capacity)))),
+        ..atom_family(initialize_atom)
+    }
 }
 
 #[cfg(test)]
@@ -231,21 +552,176 @@ mod tests {
     use super::*;
     use crate::atom::atom;
 
-    // TODO: Phase 7.1 - Add tests for atom family
-    //
-    // #[test]
-    // fn test_atom_family_caching() {
-    //     let family = atom_family(|id: i32| atom(id * 10));
-    //     let a1 = family.get(1);
-    //     let a2 = family.get(1);
-    //     assert_eq!(a1.id(), a2.id()); // Same atom returned
-    // }
-    //
-    // #[test]
-    // fn test_atom_family_different_params() {
-    //     let family = atom_family(|id: i32| atom(id));
-    //     let a1 = family.get(1);
-    //     let a2 = family.get(2);
-    //     assert_ne!(a1.id(), a2.id()); // Different atoms
-    // }
+    #[test]
+    fn test_atom_family_lru_evicts_least_recently_used() {
+        let family = atom_family_lru(2, |id: i32| atom(id).as_atom().clone());
+
+        let first = family.get(1);
+        family.get(2);
+
+        // Touch 1 so that 2 becomes the least recently used member
+        assert_eq!(family.get(1).id(), first.id());
+
+        family.get(3);
+
+        let mut params = family.get_params();
+        params.sort();
+        assert_eq!(params, vec![1, 3]);
+
+        // The surviving member is still the cached atom
+        assert_eq!(family.get(1).id(), first.id());
+    }
+
+    #[test]
+    fn test_atom_family_lru_recreates_evicted_member() {
+        let family = atom_family_lru(1, |id: i32| atom(id).as_atom().clone());
+
+        let first = family.get(1);
+        family.get(2);
+
+        assert_eq!(family.get_params(), vec![2]);
+        assert_ne!(family.get(1).id(), first.id());
+    }
+
+    #[test]
+    fn test_atom_family_reinitialize_migrates_values() {
+        use crate::atom::PrimitiveAtom;
+        use crate::store::Store;
+
+        // Writable handles for the family's atoms, so migration can seed them
+        let handles: Arc<Mutex<HashMap<crate::types::AtomId, PrimitiveAtom<i32>>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let registry = handles.clone();
+        let family = atom_family(move |id: i32| {
+            let member = atom(id * 10);
+            registry.lock().unwrap().insert(member.id(), member.clone());
+            member.as_atom().clone()
+        });
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        let _unsubscribe = family.listen(move |event| {
+            let entry = match event {
+                FamilyEvent::Create { param, .. } => ("create", *param),
+                FamilyEvent::Remove { param, .. } => ("remove", *param),
+            };
+            log.lock().unwrap().push(entry);
+        });
+
+        let store = Store::new();
+        let old_one = family.get(1);
+        let old_two = family.get(2);
+        let writable_one = handles.lock().unwrap()[&old_one.id()].clone();
+        store.set(&writable_one, 11).unwrap();
+
+        family.reinitialize(Some(|_param: &i32, old: &Atom<i32>, new: &Atom<i32>| {
+            let value = store.get(old).unwrap();
+            let writable = handles.lock().unwrap()[&new.id()].clone();
+            store.set(&writable, value).unwrap();
+        }));
+
+        let new_one = family.get(1);
+        let new_two = family.get(2);
+        assert_ne!(new_one.id(), old_one.id());
+        assert_ne!(new_two.id(), old_two.id());
+        assert_eq!(store.get(&new_one).unwrap(), 11);
+        assert_eq!(store.get(&new_two).unwrap(), 20);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[..2], [("create", 1), ("create", 2)]);
+        let mut reinit: Vec<_> = events[2..].to_vec();
+        reinit.sort();
+        assert_eq!(
+            reinit,
+            vec![("create", 1), ("create", 2), ("remove", 1), ("remove", 2)]
+        );
+    }
+
+    #[test]
+    fn test_atom_family_listen_reports_create_and_remove() {
+        let family = atom_family(|id: i32| atom(id).as_atom().clone());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let log = events.clone();
+        let unsubscribe = family.listen(move |event| {
+            let entry = match event {
+                FamilyEvent::Create { param, atom } => ("create", *param, atom.id()),
+                FamilyEvent::Remove { param, atom } => ("remove", *param, atom.id()),
+            };
+            log.lock().unwrap().push(entry);
+        });
+
+        let one = family.get(1);
+        let two = family.get(2);
+        // Cache hits and unknown params are silent
+        family.get(1);
+        family.remove(&1);
+        family.remove(&1);
+
+        unsubscribe();
+        family.remove(&2);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ("create", 1, one.id()),
+                ("create", 2, two.id()),
+                ("remove", 1, one.id()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_atom_family_reinitialize_without_migration() {
+        let family = atom_family(|id: i32| atom(id).as_atom().clone());
+        let first = family.get(1);
+
+        family.reinitialize(None::<fn(&i32, &Atom<i32>, &Atom<i32>)>);
+
+        assert_eq!(family.get_params(), vec![1]);
+        assert_ne!(family.get(1).id(), first.id());
+    }
+
+    #[test]
+    fn test_atom_family_should_remove_sweeps_old_members() {
+        let family = atom_family(|id: i32| atom(id).as_atom().clone());
+        family.get(1);
+        family.get(2);
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let threshold = now_millis();
+        let recent = family.get(3);
+
+        family.set_should_remove(Some(move |created_at, _: &i32| created_at < threshold));
+        assert_eq!(family.get_params(), vec![3]);
+        assert_eq!(family.get(3).id(), recent.id());
+
+        // A re-created member is newer than the threshold and survives
+        let recreated = family.get(1);
+        assert_eq!(family.get(1).id(), recreated.id());
+        assert_eq!(family.get_params().len(), 2);
+    }
+
+    #[test]
+    fn test_atom_family_caching() {
+        let family = atom_family(|id: i32| atom(id * 10).as_atom().clone());
+        let a1 = family.get(1);
+        let a2 = family.get(1);
+        assert_eq!(a1.id(), a2.id()); // Same atom returned
+    }
+
+    #[test]
+    fn test_atom_family_different_params() {
+        let family = atom_family(|id: i32| atom(id).as_atom().clone());
+        let a1 = family.get(1);
+        let a2 = family.get(2);
+        assert_ne!(a1.id(), a2.id()); // Different atoms
+
+        let mut params = family.get_params();
+        params.sort();
+        assert_eq!(params, vec![1, 2]);
+
+        family.remove(&1);
+        assert_eq!(family.get_params(), vec![2]);
+        assert_ne!(family.get(1).id(), a1.id());
+    }
 }
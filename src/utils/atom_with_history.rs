@@ -0,0 +1,158 @@
+//! Atoms that remember their recent values
+//!
+//! A history atom behaves like a primitive atom, and additionally records
+//! every written value, tagged with the epoch it was stored at, in a ring
+//! buffer of bounded size. The buffer lives in the store, so each store
+//! keeps its own history. `Store::history_iter` replays it.
+//!
+//! ## Functional Programming Patterns
+//! - Event log (values as an append-only sequence)
+//! - Bounded buffer (oldest entries are dropped)
+
+use crate::atom::{atom, atom_writable, Atom, PrimitiveAtom, WritableAtom};
+use crate::store::Store;
+use crate::types::EpochNumber;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Recorded values, oldest first
+type HistoryLog<T> = Arc<VecDeque<(EpochNumber, T)>>;
+
+/// A writable atom recording the values written to it
+///
+/// Created with `atom_with_history`. Read it through `as_atom()` and write
+/// it through `as_writable()`.
+#[derive(Clone)]
+pub struct HistoryAtom<T: Clone + Send + Sync + 'static> {
+    /// The atom users read and write
+    value: WritableAtom<T>,
+
+    /// The recorded values
+    log: PrimitiveAtom<HistoryLog<T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> HistoryAtom<T> {
+    /// The atom holding the current value
+    pub fn as_atom(&self) -> &Atom<T> {
+        self.value.as_atom()
+    }
+
+    /// The atom to `set` (writes are recorded)
+    pub fn as_writable(&self) -> &WritableAtom<T> {
+        &self.value
+    }
+
+    /// Iterate over the entries recorded in `store` after `since_epoch`
+    pub(crate) fn iter(&self, store: &Store, since_epoch: EpochNumber) -> HistoryIter<T> {
+        let log = store.get(self.log.as_atom()).unwrap_or_default();
+        // Epochs wrap, so find the entry by equality rather than ordering
+        let next = log
+            .iter()
+            .position(|(epoch, _)| *epoch == since_epoch)
+            .map_or(0, |index| index + 1);
+        HistoryIter { log, next }
+    }
+}
+
+/// Iterator over a history atom's recorded `(epoch, value)` entries
+///
+/// Returned by `Store::history_iter`. Iterates over a snapshot: writes made
+/// while iterating are not seen.
+pub struct HistoryIter<T> {
+    log: HistoryLog<T>,
+    next: usize,
+}
+
+impl<T: Clone> Iterator for HistoryIter<T> {
+    type Item = (EpochNumber, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.log.get(self.next)?.clone();
+        self.next += 1;
+        Some(entry)
+    }
+}
+
+/// Create an atom recording its last `capacity` written values
+///
+/// Each `set` that changes the value appends `(epoch, value)` to the
+/// store's history for this atom, evicting the oldest entry beyond
+/// `capacity`. The initial value is not recorded. A `capacity` of 0 is
+/// treated as 1.
+///
+/// **FP Pattern**: Event sourcing (bounded)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let price = atom_with_history(100, 64);
+/// store.set(price.as_writable(), 105)?;
+/// for (epoch, value) in store.history_iter(&price, 0) {
+///     println!("{}: {}", epoch, value);
+/// }
+/// ```
+pub fn atom_with_history<T>(initial_value: T, capacity: usize) -> HistoryAtom<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let capacity = capacity.max(1);
+    let current = atom(initial_value);
+    let log: PrimitiveAtom<HistoryLog<T>> = atom(Arc::new(VecDeque::new()));
+
+    let (source, target, history) = (current.clone(), current, log.clone());
+    let value = atom_writable(
+        move |get| get.get(source.as_atom()),
+        move |writer, value: T| {
+            writer.set(&target, value.clone())?;
+            let epoch = writer.epoch_of(target.id()).unwrap_or(0);
+            writer.update(&history, |mut entries| {
+                // An equal value (see `Store::register_eq`) keeps its epoch
+                if entries.back().map(|(last, _)| *last) != Some(epoch) {
+                    let entries_mut = Arc::make_mut(&mut entries);
+                    entries_mut.push_back((epoch, value));
+                    while entries_mut.len() > capacity {
+                        entries_mut.pop_front();
+                    }
+                }
+                entries
+            })
+        },
+    );
+
+    HistoryAtom { value, log }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_iter_since_epoch() {
+        let store = Store::new();
+        let price = atom_with_history(100, 3);
+
+        for value in [101, 102, 103, 104] {
+            store.set(price.as_writable(), value).unwrap();
+        }
+        assert_eq!(store.get(price.as_atom()).unwrap(), 104);
+
+        // Only the last three writes are kept
+        let all: Vec<(EpochNumber, i32)> = store.history_iter(&price, 0).collect();
+        let values: Vec<i32> = all.iter().map(|(_, value)| *value).collect();
+        assert_eq!(values, vec![102, 103, 104]);
+
+        // Entries recorded after the first kept one
+        let since = all[0].0;
+        let later: Vec<i32> = store
+            .history_iter(&price, since)
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(later, vec![103, 104]);
+
+        // Nothing after the latest entry
+        assert_eq!(store.history_iter(&price, all[2].0).count(), 0);
+
+        // Each store keeps its own history
+        assert_eq!(Store::new().history_iter(&price, 0).count(), 0);
+    }
+}
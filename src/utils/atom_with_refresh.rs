@@ -0,0 +1,132 @@
+//! Derived atoms that can be recomputed on demand
+//!
+//! Reference: `jotai/src/vanilla/utils/atomWithRefresh.ts`
+//!
+//! A refreshable atom is a derived atom that also reads a hidden refresh
+//! counter. `Store::refresh` bumps the counter, so the atom recomputes even
+//! though none of its tracked dependencies changed: useful when the read
+//! function consults something the store cannot track (a clock, a file, a
+//! request).
+//!
+//! ## Functional Programming Patterns
+//! - Explicit effects (re-running the read is a separate, named step)
+//! - Invalidation through a synthetic dependency
+
+use crate::atom::{atom, atom_derived, Atom, PrimitiveAtom};
+use crate::error::Result;
+use crate::store::Store;
+use crate::types::Getter;
+
+/// A derived atom that `Store::refresh` forces to recompute
+///
+/// Created with `atom_with_refresh`. Read it through `as_atom()`.
+#[derive(Clone)]
+pub struct RefreshAtom<T: Clone + Send + Sync + 'static> {
+    /// Counter read by `atom`, bumped on every refresh
+    refresh_count: PrimitiveAtom<u64>,
+
+    /// The derived atom holding the current value
+    atom: Atom<T>,
+}
+
+impl<T: Clone + Send + Sync + 'static> RefreshAtom<T> {
+    /// The derived atom holding the current value
+    pub fn as_atom(&self) -> &Atom<T> {
+        &self.atom
+    }
+
+    /// Invalidate the atom in `store`
+    ///
+    /// Mounted, it recomputes (and notifies) right away; otherwise on its
+    /// next read.
+    pub(crate) fn refresh(&self, store: &Store) -> Result<()> {
+        store.set_with(&self.refresh_count, |count| count.wrapping_add(1))
+    }
+}
+
+/// Create a derived atom that can be recomputed with `Store::refresh`
+///
+/// Reference: `jotai/src/vanilla/utils/atomWithRefresh.ts`
+///
+/// ```typescript
+/// export function atomWithRefresh<Value>(
+///   read: Read<Value>,
+/// ): WritableAtom<Value, [], void>
+/// ```
+///
+/// `read` behaves as in `atom_derived`: its dependencies are tracked and
+/// recompute it as usual. Jotai refreshes by writing the atom with no
+/// arguments; here the write is the named `Store::refresh` instead.
+///
+/// **FP Pattern**: Memoized computation with manual invalidation
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let now = atom_with_refresh(|_get| Ok(SystemTime::now()));
+/// let first = store.get(now.as_atom())?;
+/// store.refresh(&now)?;
+/// assert!(store.get(now.as_atom())? >= first);
+/// ```
+pub fn atom_with_refresh<T, F>(read: F) -> RefreshAtom<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Fn(&dyn Getter) -> Result<T> + Send + Sync + 'static,
+{
+    let refresh_count = atom(0u64);
+    let counter = refresh_count.clone();
+    let atom = atom_derived(move |get| {
+        get.get(counter.as_atom())?;
+        read(get)
+    });
+
+    RefreshAtom {
+        refresh_count,
+        atom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_atom_with_refresh_reruns_read() {
+        let store = Store::new();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let latest = atom_with_refresh(move |_get| Ok(counter.fetch_add(1, Ordering::SeqCst)));
+
+        assert_eq!(store.get(latest.as_atom()).unwrap(), 0);
+        // Cached: nothing it tracks has changed
+        assert_eq!(store.get(latest.as_atom()).unwrap(), 0);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        store.refresh(&latest).unwrap();
+        assert_eq!(store.get(latest.as_atom()).unwrap(), 1);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_atom_with_refresh_notifies_when_mounted() {
+        let store = Store::new();
+        let base = atom(10);
+        let source = base.clone();
+        let reads = Arc::new(AtomicUsize::new(0));
+        let counter = reads.clone();
+        let total = atom_with_refresh(move |get| {
+            let n = counter.fetch_add(1, Ordering::SeqCst) as i32;
+            Ok(get.get(source.as_atom())? + n)
+        });
+        let values = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = values.clone();
+        let _unsub = store.sub_with_value(total.as_atom(), move |value| seen.lock().push(value));
+
+        store.refresh(&total).unwrap();
+        // Tracked dependencies still work
+        store.set(&base, 20).unwrap();
+        assert_eq!(*values.lock(), vec![11, 22]);
+    }
+}
@@ -0,0 +1,188 @@
+//! Atoms persisted through a pluggable storage backend
+//!
+//! Reference: `jotai/src/vanilla/utils/atomWithStorage.ts`
+//!
+//! A storage atom reads its persisted value the first time it is read in a
+//! store, falling back to the initial value when nothing is stored, and
+//! writes every new value back to the storage. Values are stored as JSON.
+//!
+//! ## Functional Programming Patterns
+//! - Strategy pattern (the storage is a trait object)
+//! - Lazy initialization (storage is consulted once per store)
+//! - Side effects isolated in the write function
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::atom::{atom_computed_default, atom_writable, WritableAtom};
+use crate::error::{AtomError, Result};
+use crate::types::AtomId;
+
+/// A key-value store for serialized atom values
+///
+/// Reference: `jotai/src/vanilla/utils/atomWithStorage.ts` (`SyncStringStorage`)
+///
+/// Mirrors the `localStorage` API: keys and values are strings, and the
+/// atom handles (de)serialization. Implement it for sled, files, or a wasm
+/// `localStorage` binding and pass it as `Arc<dyn Storage>`.
+pub trait Storage: Send + Sync {
+    /// The stored value for `key`, if any
+    fn get_item(&self, key: &str) -> Option<String>;
+
+    /// Store `value` under `key`, replacing any previous value
+    fn set_item(&self, key: &str, value: String) -> Result<()>;
+
+    /// Delete the value stored under `key`
+    fn remove_item(&self, key: &str) -> Result<()>;
+}
+
+/// In-memory `Storage`, useful for tests and as a default
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    items: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn get_item(&self, key: &str) -> Option<String> {
+        self.items.lock().get(key).cloned()
+    }
+
+    fn set_item(&self, key: &str, value: String) -> Result<()> {
+        self.items.lock().insert(key.to_string(), value);
+        Ok(())
+    }
+
+    fn remove_item(&self, key: &str) -> Result<()> {
+        self.items.lock().remove(key);
+        Ok(())
+    }
+}
+
+/// Create an atom persisted in `storage` under `key`
+///
+/// Reference: `jotai/src/vanilla/utils/atomWithStorage.ts`
+///
+/// ```typescript
+/// export function atomWithStorage<Value>(
+///   key: string,
+///   initialValue: Value,
+///   storage: SyncStorage<Value>,
+/// ): WritableAtom<Value, [SetStateActionWithReset<Value>], void>
+/// ```
+///
+/// The first read in a store deserializes the stored value, or uses
+/// `initial` if the key is missing. A stored value that fails to
+/// deserialize is reported as `AtomError::ReadError`. Every write stores
+/// the value in the atom and persists it; a failed serialization or
+/// storage write is reported as `AtomError::WriteError`.
+///
+/// **FP Pattern**: Write-through cache over an injected backend
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom_with_storage, MemoryStorage};
+///
+/// let storage = Arc::new(MemoryStorage::new());
+/// let theme = atom_with_storage("theme", "light".to_string(), storage);
+/// store.set(&theme, "dark".to_string())?;
+/// ```
+pub fn atom_with_storage<T>(
+    key: impl Into<String>,
+    initial: T,
+    storage: Arc<dyn Storage>,
+) -> WritableAtom<T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    let key = key.into();
+    // Errors should name the atom users hold, which exists only after the
+    // closures that report them
+    let atom_id: Arc<OnceLock<AtomId>> = Arc::new(OnceLock::new());
+
+    let stored = {
+        let (key, storage, atom_id) = (key.clone(), storage.clone(), atom_id.clone());
+        atom_computed_default(move |_get| match storage.get_item(&key) {
+            Some(raw) => serde_json::from_str(&raw).map_err(|error| {
+                AtomError::read_error(atom_id.get().copied().unwrap_or_default(), error)
+            }),
+            None => Ok(initial.clone()),
+        })
+    };
+
+    let persisted = {
+        let source = stored.clone();
+        let target = stored.clone();
+        let atom_id = atom_id.clone();
+        atom_writable(
+            move |get| get.get(source.as_atom()),
+            move |set, value: T| {
+                let id = atom_id.get().copied().unwrap_or_default();
+                let raw = serde_json::to_string(&value)
+                    .map_err(|error| AtomError::write_error(id, error))?;
+                set.set(&target, value)?;
+                storage.set_item(&key, raw)
+            },
+        )
+    };
+    let _ = atom_id.set(persisted.id());
+    persisted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+
+    #[test]
+    fn test_atom_with_storage_reads_and_persists() {
+        let storage = Arc::new(MemoryStorage::new());
+        storage
+            .set_item("todos", r#"["write docs"]"#.to_string())
+            .unwrap();
+
+        let store = Store::new();
+        let todos = atom_with_storage("todos", Vec::<String>::new(), storage.clone());
+        assert_eq!(store.get(todos.as_atom()).unwrap(), vec!["write docs"]);
+
+        store
+            .set(&todos, vec!["write docs".to_string(), "ship".to_string()])
+            .unwrap();
+        assert_eq!(
+            storage.get_item("todos").as_deref(),
+            Some(r#"["write docs","ship"]"#)
+        );
+
+        // A fresh store picks the persisted value up
+        let other = Store::new();
+        assert_eq!(other.get(todos.as_atom()).unwrap().len(), 2);
+
+        // A missing key falls back to the initial value
+        let count = atom_with_storage("count", 3, storage);
+        assert_eq!(store.get(count.as_atom()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_atom_with_storage_reports_corrupt_value() {
+        let storage = Arc::new(MemoryStorage::new());
+        storage.set_item("count", "not json".to_string()).unwrap();
+
+        let store = Store::new();
+        let count = atom_with_storage("count", 0, storage);
+        match store.get(count.as_atom()) {
+            Err(AtomError::ReadError { atom_id, .. }) => assert_eq!(atom_id, count.id()),
+            other => panic!("expected a read error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! "Unsaved changes" tracking for a group of atoms
+//!
+//! A dirty atom compares several atoms against baseline values and is `true`
+//! while any of them differs. The baseline itself lives in a primitive atom,
+//! so it is per store and resetting it recomputes the flag reactively.
+//!
+//! ## Functional Programming Patterns
+//! - Derived state (the flag is a pure function of values and baseline)
+//! - Snapshots (the baseline is an immutable copy of past values)
+
+use crate::atom::{Atom, PrimitiveAtom, atom, atom_derived};
+use crate::error::Result;
+use crate::store::Store;
+
+/// A boolean atom reporting whether tracked atoms differ from their baseline
+///
+/// Created with `dirty_atom`. Read the flag through `as_atom()`.
+#[derive(Clone)]
+pub struct DirtyAtom<T: Clone + Send + Sync + 'static> {
+    /// Atoms being tracked
+    tracked: Vec<Atom<T>>,
+
+    /// Baseline values, one per tracked atom
+    baseline: PrimitiveAtom<Vec<T>>,
+
+    /// The derived flag
+    dirty: Atom<bool>,
+}
+
+impl<T: Clone + PartialEq + Send + Sync + 'static> DirtyAtom<T> {
+    /// The derived `Atom<bool>` (true while any tracked atom differs)
+    pub fn as_atom(&self) -> &Atom<bool> {
+        &self.dirty
+    }
+
+    /// Snapshot the tracked atoms' current values as the new baseline
+    ///
+    /// Only affects `store`; other stores keep their own baseline.
+    pub fn reset_baseline(&self, store: &Store) -> Result<()> {
+        let current = self
+            .tracked
+            .iter()
+            .map(|atom| store.get(atom))
+            .collect::<Result<Vec<T>>>()?;
+        store.set(&self.baseline, current)
+    }
+}
+
+/// Create a dirty flag for `atoms`, compared against `baselines`
+///
+/// The flag recomputes whenever a tracked atom or the baseline changes, and
+/// only notifies subscribers when it flips.
+///
+/// # Panics
+///
+/// Panics if `atoms` and `baselines` have different lengths.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let name = atom("Ada".to_string());
+/// let email = atom("ada@example.com".to_string());
+/// let unsaved = dirty_atom(
+///     vec![name.as_atom().clone(), email.as_atom().clone()],
+///     vec!["Ada".to_string(), "ada@example.com".to_string()],
+/// );
+///
+/// store.set(&name, "Grace".to_string())?;
+/// assert!(store.get(unsaved.as_atom())?);
+/// unsaved.reset_baseline(&store)?;
+/// ```
+pub fn dirty_atom<T>(atoms: Vec<Atom<T>>, baselines: Vec<T>) -> DirtyAtom<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    assert_eq!(
+        atoms.len(),
+        baselines.len(),
+        "dirty_atom needs one baseline per tracked atom"
+    );
+
+    let baseline = atom(baselines);
+    let dirty = {
+        let tracked = atoms.clone();
+        let baseline = baseline.clone();
+        atom_derived(move |get| {
+            let baseline = get.get(baseline.as_atom())?;
+            for (atom, original) in tracked.iter().zip(&baseline) {
+                if get.get(atom)? != *original {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+        .with_equality(|previous, next| previous == next)
+    };
+
+    DirtyAtom {
+        tracked: atoms,
+        baseline,
+        dirty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dirty_atom_tracks_changes_and_reset() {
+        let store = Store::new();
+        let name = atom("Ada".to_string());
+        let age = atom("36".to_string());
+        let unsaved = dirty_atom(
+            vec![name.as_atom().clone(), age.as_atom().clone()],
+            vec!["Ada".to_string(), "36".to_string()],
+        );
+
+        assert!(!store.get(unsaved.as_atom()).unwrap());
+
+        store.set(&name, "Grace".to_string()).unwrap();
+        assert!(store.get(unsaved.as_atom()).unwrap());
+
+        // Changing the value back clears the flag
+        store.set(&name, "Ada".to_string()).unwrap();
+        assert!(!store.get(unsaved.as_atom()).unwrap());
+
+        store.set(&age, "37".to_string()).unwrap();
+        assert!(store.get(unsaved.as_atom()).unwrap());
+
+        unsaved.reset_baseline(&store).unwrap();
+        assert!(!store.get(unsaved.as_atom()).unwrap());
+
+        // The old value now counts as a change
+        store.set(&age, "36".to_string()).unwrap();
+        assert!(store.get(unsaved.as_atom()).unwrap());
+    }
+}
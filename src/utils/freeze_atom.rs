@@ -0,0 +1,139 @@
+//! Debug check against mutating an atom's value behind the store's back
+//!
+//! Reference: `jotai/src/vanilla/utils/freezeAtom.ts`
+//!
+//! Jotai deep-freezes values so mutating them throws. Rust values handed
+//! out by the store are clones, so they can only be mutated through shared
+//! interior mutability (`Arc<Mutex<_>>`, atomics, cells), which changes the
+//! atom's cached value too without bumping its epoch. A frozen atom
+//! remembers the hash of each value it returns and reports a mismatch.
+//!
+//! ## Functional Programming Patterns
+//! - Immutability (enforced at runtime, in debug builds)
+//! - Decorator (same value, extra check)
+
+use crate::atom::Atom;
+#[cfg(debug_assertions)]
+use crate::atom::{atom_derived, hash_value};
+#[cfg(debug_assertions)]
+use crate::error::AtomError;
+#[cfg(debug_assertions)]
+use parking_lot::Mutex;
+use std::hash::Hash;
+#[cfg(debug_assertions)]
+use std::sync::Arc;
+
+/// Wrap `source_atom` so in-place mutation of its value is detected
+///
+/// Reference: `jotai/src/vanilla/utils/freezeAtom.ts`
+///
+/// ```typescript
+/// export function freezeAtom<AtomType extends Atom<unknown>>(
+///   anAtom: AtomType,
+/// ): AtomType
+/// ```
+///
+/// In debug builds the returned atom passes `source_atom`'s values through
+/// and records the hash of each one. When it next recomputes (after the
+/// source changes), it hashes the value it returned last: if the hash
+/// moved, that value was mutated through shared interior mutability, and
+/// the read fails with `AtomError::ReadError`. In release builds
+/// `source_atom` is returned as is.
+///
+/// The check runs only when the frozen atom recomputes, i.e. after
+/// `source_atom` changes. Reads served from the cache are not hashed, so a
+/// mutation goes unreported until the next change of the source.
+///
+/// The record is shared by every store reading the atom, so the check is
+/// meant for debugging a single store.
+///
+/// **FP Pattern**: Immutability check as a decorator
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let frozen = freeze_atom(settings.as_atom().clone());
+/// let current = store.get(&frozen)?;
+/// current.theme.lock().push_str("-dark"); // mutates the store's copy
+/// store.set(&settings, defaults())?;
+/// assert!(store.get(&frozen).is_err());
+/// ```
+pub fn freeze_atom<T>(source_atom: Atom<T>) -> Atom<T>
+where
+    T: Clone + Hash + Send + Sync + 'static,
+{
+    #[cfg(debug_assertions)]
+    {
+        // Last value handed out, with its hash at that time
+        let last: Arc<Mutex<Option<(T, u64)>>> = Arc::new(Mutex::new(None));
+        atom_derived(move |get| {
+            let previous = last.lock().take();
+            if let Some((value, hash)) = previous {
+                if hash_value(&value) != hash {
+                    return Err(AtomError::read_error(
+                        source_atom.id(),
+                        "value was mutated after it was read",
+                    ));
+                }
+            }
+
+            let value = get.get(&source_atom)?;
+            *last.lock() = Some((value.clone(), hash_value(&value)));
+            Ok(value)
+        })
+    }
+
+    #[cfg(not(debug_assertions))]
+    {
+        source_atom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+
+    #[test]
+    fn test_freeze_atom_passes_values_through() {
+        let store = Store::new();
+        let items = atom(vec![1, 2]);
+        let frozen = freeze_atom(items.as_atom().clone());
+
+        assert_eq!(store.get(&frozen).unwrap(), vec![1, 2]);
+        store.set(&items, vec![3]).unwrap();
+        assert_eq!(store.get(&frozen).unwrap(), vec![3]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_freeze_atom_detects_mutation() {
+        use crate::error::AtomError;
+
+        // Clones share the counter, like any `Arc<Mutex<_>>` field would
+        #[derive(Clone)]
+        struct Shared(Arc<Mutex<i32>>);
+
+        impl Hash for Shared {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.lock().hash(state);
+            }
+        }
+
+        let store = Store::new();
+        let counter = atom(Shared(Arc::new(Mutex::new(0))));
+        let frozen = freeze_atom(counter.as_atom().clone());
+
+        let value = store.get(&frozen).unwrap();
+        *value.0.lock() += 1;
+
+        store
+            .set(&counter, Shared(Arc::new(Mutex::new(5))))
+            .unwrap();
+        assert!(matches!(
+            store.get(&frozen),
+            Err(AtomError::ReadError { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+//! Join two async atoms, combining their results
+//!
+//! Like `Promise.all` over a pair of async atoms: the joined atom awaits
+//! both inputs concurrently and combines their values once both resolve.
+//!
+//! ## Functional Programming Patterns
+//! - Applicative combination (`map2` / `liftA2`) over deferred computations
+//! - Cancellation by dropping unfinished futures
+
+use crate::atom::{atom_async, Atom};
+use crate::error::AtomError;
+use futures::future::{self, Either};
+
+/// Create an async atom combining the values of `a` and `b` with `combine`
+///
+/// Both inputs are dependencies and are awaited concurrently. When both
+/// resolve, the joined atom resolves to `combine(a, b)`. If either fails
+/// (including being cancelled), the joined atom fails with that error and
+/// the other input's future is dropped. Aborting the joined atom abandons
+/// both inputs.
+///
+/// **FP Pattern**: Applicative `map2` (`Promise.all` + `then`)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{join_async, Store};
+///
+/// let profile = join_async(user.clone(), settings.clone(), |user, settings| Profile { user, settings });
+/// let profile = store.get_async(&profile).await?;
+/// ```
+pub fn join_async<A, B, C, F>(a: Atom<A>, b: Atom<B>, combine: F) -> Atom<C>
+where
+    A: Clone + Send + Sync + 'static,
+    B: Clone + Send + Sync + 'static,
+    C: Clone + Send + Sync + 'static,
+    F: Fn(A, B) -> C + Send + Sync + 'static,
+{
+    let combine = std::sync::Arc::new(combine);
+    atom_async(move |get, signal| {
+        let both = future::try_join(get.get_async(&a), get.get_async(&b));
+        let combine = combine.clone();
+        async move {
+            match future::select(Box::pin(both), Box::pin(signal.aborted())).await {
+                Either::Left((result, _)) => result.map(|(a, b)| combine(a, b)),
+                // The store reports the abort; this result is discarded
+                Either::Right(_) => Err(AtomError::Generic("join aborted".to_string())),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Async atom resolving to `value` after `delay` of (mock) time
+    fn delayed<T: Clone + Send + Sync + 'static>(
+        value: T,
+        delay: Duration,
+        finished: Arc<AtomicBool>,
+    ) -> Atom<T> {
+        atom_async(move |_get, _signal| {
+            let (value, finished) = (value.clone(), finished.clone());
+            async move {
+                tokio::time::sleep(delay).await;
+                finished.store(true, Ordering::SeqCst);
+                Ok(value)
+            }
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_async_combines_after_both_resolve() {
+        let store = Store::new();
+        let fast_done = Arc::new(AtomicBool::new(false));
+        let slow_done = Arc::new(AtomicBool::new(false));
+        let fast = delayed(2, Duration::from_millis(10), fast_done.clone());
+        let slow = delayed(
+            "items".to_string(),
+            Duration::from_millis(50),
+            slow_done.clone(),
+        );
+        let joined = join_async(fast, slow, |count, label| format!("{} {}", count, label));
+
+        let started = tokio::time::Instant::now();
+        assert_eq!(store.get_async(&joined).await.unwrap(), "2 items");
+        // Awaited concurrently: the slower input sets the pace
+        assert_eq!(started.elapsed(), Duration::from_millis(50));
+        assert!(fast_done.load(Ordering::SeqCst));
+        assert!(slow_done.load(Ordering::SeqCst));
+        assert_eq!(store.get(&joined).unwrap(), "2 items");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_join_async_fails_when_an_input_fails() {
+        let store = Store::new();
+        let slow_done = Arc::new(AtomicBool::new(false));
+        let failing: Atom<i32> = atom_async(|_get, _signal| async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err(AtomError::Generic("offline".to_string()))
+        });
+        let slow = delayed(1, Duration::from_millis(100), slow_done.clone());
+        let joined = join_async(failing, slow, |a, b| a + b);
+
+        assert!(matches!(
+            store.get_async(&joined).await,
+            Err(AtomError::Generic(ref message)) if message == "offline"
+        ));
+        // The other input was dropped before it finished
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!slow_done.load(Ordering::SeqCst));
+    }
+}
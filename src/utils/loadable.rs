@@ -0,0 +1,115 @@
+//! Loadable wrapper exposing an atom's status as a value
+//!
+//! Reference: `jotai/src/vanilla/utils/loadable.ts`
+//!
+//! Reading a loadable atom never fails: the wrapped atom's value, error or
+//! pending state is turned into a `Loadable`, so callers can match on the
+//! status instead of unwrapping.
+//!
+//! ## Functional Programming Patterns
+//! - Algebraic data type for the three states
+//! - Errors as values (Result lifted into an enum)
+
+use crate::atom::{atom_derived, Atom};
+use crate::error::AtomError;
+
+/// Status of an atom's value
+///
+/// Reference: `jotai/src/vanilla/utils/loadable.ts`
+///
+/// ```typescript
+/// type Loadable<Value> =
+///   | { state: 'loading' }
+///   | { state: 'hasError'; error: unknown }
+///   | { state: 'hasData'; data: Awaited<Value> }
+/// ```
+#[derive(Debug, Clone)]
+pub enum Loadable<T> {
+    /// An async atom has not resolved yet
+    Loading,
+    /// The atom has a value
+    Data(T),
+    /// Reading the atom failed
+    Error(AtomError),
+}
+
+/// Wrap `atom` so reading it yields a `Loadable` instead of a `Result`
+///
+/// Reference: `jotai/src/vanilla/utils/loadable.ts`
+///
+/// ```typescript
+/// export function loadable<Value>(anAtom: Atom<Value>): Atom<Loadable<Value>>
+/// ```
+///
+/// `AtomError::Pending` (an unresolved async atom) maps to
+/// `Loadable::Loading`; every other error maps to `Loadable::Error`. The
+/// loadable depends on `atom`, so it updates whenever `atom` does.
+///
+/// **FP Pattern**: Total function over a partial one
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let status = loadable(user_profile.clone());
+/// match store.get(&status)? {
+///     Loadable::Loading => show_spinner(),
+///     Loadable::Data(profile) => render(profile),
+///     Loadable::Error(error) => show_error(error),
+/// }
+/// ```
+pub fn loadable<T>(atom: Atom<T>) -> Atom<Loadable<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    atom_derived(move |get| {
+        Ok(match get.get(&atom) {
+            Ok(value) => Loadable::Data(value),
+            Err(AtomError::Pending { .. }) => Loadable::Loading,
+            Err(error) => Loadable::Error(error),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+
+    #[test]
+    fn test_loadable_maps_data() {
+        let store = Store::new();
+        let count = atom(1);
+        let source = count.clone();
+        let doubled = atom_derived(move |get| Ok(get.get(source.as_atom())? * 2));
+        let status = loadable(doubled);
+
+        assert!(matches!(store.get(&status).unwrap(), Loadable::Data(2)));
+
+        store.set(&count, 5).unwrap();
+        assert!(matches!(store.get(&status).unwrap(), Loadable::Data(10)));
+    }
+
+    #[test]
+    fn test_loadable_maps_read_error() {
+        let store = Store::new();
+        let input = atom("12x".to_string());
+        let source = input.clone();
+        let parsed = atom_derived(move |get| {
+            get.get(source.as_atom())?
+                .parse::<i32>()
+                .map_err(|error| AtomError::read_error(0, error))
+        });
+        let status = loadable(parsed);
+
+        match store.get(&status).unwrap() {
+            Loadable::Error(AtomError::ReadError { message, .. }) => {
+                assert_eq!(message, "invalid digit found in string");
+            }
+            other => panic!("expected a read error, got {:?}", other),
+        }
+
+        store.set(&input, "12".to_string()).unwrap();
+        assert!(matches!(store.get(&status).unwrap(), Loadable::Data(12)));
+    }
+}
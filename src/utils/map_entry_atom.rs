@@ -0,0 +1,108 @@
+//! Keyed selector over a map atom
+//!
+//! Reference: `jotai/src/vanilla/utils/selectAtom.ts` (selecting a slice with an equality check)
+//!
+//! A map entry atom reads one key of a `HashMap` atom. It recomputes whenever
+//! the map changes, but only reports a change when that key's value does, so
+//! atoms and listeners depending on the entry ignore edits to other keys.
+//!
+//! ## Functional Programming Patterns
+//! - Lenses (focus on one entry of a larger structure)
+//! - Memoization (equality-based change detection)
+
+use crate::atom::{Atom, atom_derived};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Create a derived atom focused on a single key of a map atom
+///
+/// The entry atom's value is `Some(value)` while the key is present and
+/// `None` otherwise. A recomputation that yields the same entry as before
+/// (compared with `PartialEq`) is not a change: the entry keeps its epoch and
+/// its dependents are not recomputed.
+///
+/// **FP Pattern**: Lens, memoization
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom, map_entry_atom, Store};
+///
+/// let scores = atom(HashMap::from([("alice", 1), ("bob", 2)]));
+/// let alice = map_entry_atom(scores.as_atom().clone(), "alice");
+///
+/// let store = Store::new();
+/// assert_eq!(store.get(&alice).unwrap(), Some(1));
+/// ```
+pub fn map_entry_atom<K, V>(map_atom: Atom<HashMap<K, V>>, key: K) -> Atom<Option<V>>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'static,
+    V: Clone + PartialEq + Send + Sync + 'static,
+{
+    atom_derived(move |get| Ok(get.get(&map_atom)?.get(&key).cloned()))
+        .with_equality(|previous, next| previous == next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_map_entry_ignores_unrelated_keys() {
+        let store = Store::new();
+        let scores = atom(HashMap::from([("alice", 1), ("bob", 2)]));
+        let alice = map_entry_atom(scores.as_atom().clone(), "alice");
+
+        let recomputes = Arc::new(AtomicUsize::new(0));
+        let alice_label = {
+            let alice = alice.clone();
+            let recomputes = recomputes.clone();
+            atom_derived(move |get| {
+                recomputes.fetch_add(1, Ordering::SeqCst);
+                Ok(format!("alice: {:?}", get.get(&alice)?))
+            })
+        };
+
+        assert_eq!(store.get(&alice_label).unwrap(), "alice: Some(1)");
+        assert_eq!(recomputes.load(Ordering::SeqCst), 1);
+
+        // Changing another key leaves the entry (and its dependents) alone
+        store
+            .set(&scores, HashMap::from([("alice", 1), ("bob", 20)]))
+            .unwrap();
+        assert_eq!(store.get(&alice_label).unwrap(), "alice: Some(1)");
+        assert_eq!(recomputes.load(Ordering::SeqCst), 1);
+
+        // Changing the key itself propagates
+        store.set(&scores, HashMap::from([("bob", 20)])).unwrap();
+        assert_eq!(store.get(&alice_label).unwrap(), "alice: None");
+        assert_eq!(recomputes.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_map_entry_does_not_notify_for_unrelated_keys() {
+        let store = Store::new();
+        let scores = atom(HashMap::from([("alice", 1), ("bob", 2)]));
+        let alice = map_entry_atom(scores.as_atom().clone(), "alice");
+
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let counter = notifications.clone();
+        let _unsub = store.sub(&alice, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        store
+            .set(&scores, HashMap::from([("alice", 1), ("bob", 3)]))
+            .unwrap();
+        assert_eq!(notifications.load(Ordering::SeqCst), 0);
+
+        store
+            .set(&scores, HashMap::from([("alice", 5), ("bob", 3)]))
+            .unwrap();
+        assert_eq!(notifications.load(Ordering::SeqCst), 1);
+    }
+}
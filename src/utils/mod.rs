@@ -11,12 +11,26 @@
 //! - Higher-order functions (functions returning atoms)
 //! - Composition patterns
 
+pub mod atom_backed;
+pub mod atom_env;
 pub mod atom_family;
+pub mod atom_with_history;
+pub mod atom_with_refresh;
+#[cfg(feature = "storage")]
+pub mod atom_with_storage;
+pub mod dirty_atom;
+pub mod freeze_atom;
+#[cfg(feature = "async")]
+pub mod join_async;
+pub mod loadable;
+pub mod map_entry_atom;
+#[cfg(feature = "async")]
+pub mod race_atom;
+pub mod reduce_list_atom;
 pub mod select_atom;
+pub mod split_atom;
+pub mod zip_atoms;
 
 // TODO: Phase 7 - Add more utility modules
 // pub mod atom_with_reducer;
 // pub mod atom_with_default;
-// pub mod atom_with_storage;
-// pub mod loadable;
-// pub mod split_atom;
@@ -0,0 +1,118 @@
+//! Race several async atoms, keeping the first result
+//!
+//! Like `Promise.race` over async atoms: the raced atom resolves with
+//! whichever input settles first, and the others are cancelled.
+//!
+//! ## Functional Programming Patterns
+//! - Combinator over deferred computations
+//! - Cancellation by dropping unfinished futures
+
+use crate::atom::{atom_async, Atom};
+use crate::error::AtomError;
+use futures::future::{self, Either};
+
+/// Create an async atom resolving to the first of `atoms` to settle
+///
+/// Every input is a dependency. When one input resolves (with a value or
+/// an error), the futures of the others are dropped, which aborts their
+/// `AbortSignal`s. Aborting the raced atom itself abandons all inputs.
+///
+/// **FP Pattern**: Combinator (`Promise.race`)
+///
+/// # Panics
+///
+/// Panics if `atoms` is empty, since such a race could never settle.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{race_atom, Store};
+///
+/// let fastest = race_atom(vec![primary_mirror, backup_mirror]);
+/// let response = store.get_async(&fastest).await?;
+/// ```
+pub fn race_atom<T>(atoms: Vec<Atom<T>>) -> Atom<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    assert!(!atoms.is_empty(), "race_atom needs at least one atom");
+
+    atom_async(move |get, signal| {
+        let reads: Vec<_> = atoms.iter().map(|atom| get.get_async(atom)).collect();
+        async move {
+            match future::select(future::select_all(reads), Box::pin(signal.aborted())).await {
+                // Dropping `rest` cancels the slower inputs
+                Either::Left(((first, _index, rest), _)) => {
+                    drop(rest);
+                    first
+                }
+                // The store reports the abort; this result is discarded
+                Either::Right(_) => Err(AtomError::Generic("race aborted".to_string())),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+    use crate::types::AbortSignal;
+    use parking_lot::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Async atom resolving to `value` after `delay` of (mock) time
+    fn delayed(
+        value: i32,
+        delay: Duration,
+        seen_signal: Arc<Mutex<Option<AbortSignal>>>,
+        finished: Arc<AtomicBool>,
+    ) -> Atom<i32> {
+        atom_async(move |_get, signal| {
+            *seen_signal.lock() = Some(signal);
+            let finished = finished.clone();
+            async move {
+                tokio::time::sleep(delay).await;
+                finished.store(true, Ordering::SeqCst);
+                Ok(value)
+            }
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_race_atom_yields_fastest_and_cancels_rest() {
+        let store = Store::new();
+        let fast_signal = Arc::new(Mutex::new(None));
+        let slow_signal = Arc::new(Mutex::new(None));
+        let slow_finished = Arc::new(AtomicBool::new(false));
+
+        let fast = delayed(
+            1,
+            Duration::from_millis(10),
+            fast_signal.clone(),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let slow = delayed(
+            2,
+            Duration::from_millis(100),
+            slow_signal.clone(),
+            slow_finished.clone(),
+        );
+        let race = race_atom(vec![slow.clone(), fast.clone()]);
+
+        assert_eq!(store.get_async(&race).await.unwrap(), 1);
+        assert_eq!(store.get(&race).unwrap(), 1);
+
+        let slow_signal = slow_signal.lock().clone().unwrap();
+        assert!(slow_signal.is_aborted());
+        assert!(!fast_signal.lock().as_ref().unwrap().is_aborted());
+
+        // The slower atom never completes or caches a value
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!slow_finished.load(Ordering::SeqCst));
+        assert!(matches!(store.get(&slow), Err(AtomError::Pending { .. })));
+        assert_eq!(store.get(&fast).unwrap(), 1);
+    }
+}
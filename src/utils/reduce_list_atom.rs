@@ -0,0 +1,81 @@
+//! Reduce a list of item atoms into one value
+//!
+//! Works on the shape `splitAtom` (`jotai/src/vanilla/utils/splitAtom.ts`)
+//! produces: an atom holding a list of item atoms. The reduction depends on
+//! both the list itself and every item, so it follows items being added,
+//! removed or reordered as well as individual items changing.
+//!
+//! ## Functional Programming Patterns
+//! - Fold over a reactive collection
+//! - Function composition
+
+use crate::atom::{Atom, atom_derived};
+
+/// Create a derived atom folding the items of `list` with `reduce`
+///
+/// Starting from `init`, `reduce` is applied to every item in list order.
+/// The result recomputes when the list atom changes or when any item atom
+/// in it changes. If the list or an item fails to read, the reduced atom
+/// fails with that error.
+///
+/// **FP Pattern**: Fold (`reduce`)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom, reduce_list_atom};
+///
+/// let scores = atom(vec![alice.as_atom().clone(), bob.as_atom().clone()]);
+/// let best = reduce_list_atom(scores.as_atom().clone(), None, |best: Option<u32>, score: &u32| {
+///     Some(best.map_or(*score, |best| best.max(*score)))
+/// });
+/// ```
+pub fn reduce_list_atom<T, A, F>(list: Atom<Vec<Atom<T>>>, init: A, reduce: F) -> Atom<A>
+where
+    T: Clone + Send + Sync + 'static,
+    A: Clone + Send + Sync + 'static,
+    F: Fn(A, &T) -> A + Send + Sync + 'static,
+{
+    atom_derived(move |get| {
+        get.get(&list)?
+            .iter()
+            .try_fold(init.clone(), |acc, item| Ok(reduce(acc, &get.get(item)?)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+
+    #[test]
+    fn test_reduce_list_atom_tracks_items_and_list() {
+        let store = Store::new();
+        let a = atom(3);
+        let b = atom(7);
+        let c = atom(5);
+        let list = atom(vec![a.as_atom().clone(), b.as_atom().clone()]);
+        let max = reduce_list_atom(list.as_atom().clone(), i32::MIN, |max, item: &i32| {
+            max.max(*item)
+        });
+
+        assert_eq!(store.get(&max).unwrap(), 7);
+
+        // An item changes
+        store.set(&a, 12).unwrap();
+        assert_eq!(store.get(&max).unwrap(), 12);
+
+        // The list changes: a new item joins, an old one leaves
+        store
+            .set(&list, vec![b.as_atom().clone(), c.as_atom().clone()])
+            .unwrap();
+        assert_eq!(store.get(&max).unwrap(), 7);
+        store.set(&c, 9).unwrap();
+        assert_eq!(store.get(&max).unwrap(), 9);
+
+        // Removed items no longer matter
+        store.set(&a, 100).unwrap();
+        assert_eq!(store.get(&max).unwrap(), 9);
+    }
+}
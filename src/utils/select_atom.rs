@@ -11,10 +11,11 @@
 //! - Higher-order functions
 //! - Pure functions (selectors should be pure)
 
-use std::sync::Arc;
-use crate::atom::{Atom, atom_derived};
-use crate::types::Getter;
+#[cfg(feature = "async")]
+use crate::atom::atom_async;
+use crate::atom::{atom_derived, Atom};
 use crate::error::Result;
+use std::sync::Arc;
 
 /// Create a derived atom that selects and memoizes a slice of another atom
 ///
@@ -61,89 +62,175 @@ use crate::error::Result;
 /// );
 /// ```
 ///
-/// TODO: Phase 7.2 - Implement select_atom
-pub fn select_atom<T, S, F, E>(
-    source_atom: Atom<T>,
-    selector: F,
-    equality_fn: E,
-) -> Atom<S>
+/// The selector only sees the source value; see `select_atom_with_prev` for
+/// a selector that also receives the previous slice.
+pub fn select_atom<T, S, F, E>(source_atom: Atom<T>, selector: F, equality_fn: E) -> Atom<S>
 where
     T: Clone + Send + Sync + 'static,
     S: Clone + Send + Sync + 'static,
     F: Fn(&T) -> S + Send + Sync + 'static,
     E: Fn(&S, &S) -> bool + Send + Sync + 'static,
 {
-    // Reference: Implementation approach from selectAtom.ts
-    //
-    // The trick is to create a derived atom that:
-    // 1. Reads its own previous value
-    // 2. Reads the source atom
-    // 3. Applies the selector
-    // 4. Compares with previous using equality_fn
-    // 5. Returns previous if equal, new if different
-    //
-    // This requires a self-referential atom, which is tricky.
-    //
-    // Jotai uses a hack: `derivedAtom.init = EMPTY`
-    // to allow reading the atom before it's initialized.
-
-    // TODO: Phase 7.2 - Implement with memoization
-    // Challenges:
-    // - Need to store previous value somehow
-    // - Need self-reference in read function
-    // - Need to use equality_fn for comparison
-
-    todo!("select_atom - Phase 7.2")
+    select_atom_with_prev(source_atom, move |value, _| selector(value), equality_fn)
 }
 
-/// Select atom with default Object.is equality
+/// Create a select atom whose selector also receives the previous slice
+///
+/// Reference: `jotai/src/vanilla/utils/selectAtom.ts` (`selector(v, prevSlice?)`)
+///
+/// Selection and comparison happen in one pass: the selector is called with
+/// the source value and the slice this atom last produced in the store
+/// (`None` on the first read), and the result is compared with that same
+/// previous slice. When `equality_fn` reports them equal, the previous slice
+/// is kept and the atom's epoch does not change, so dependents are not
+/// recomputed.
 ///
-/// TODO: Phase 7.2 - Convenience wrapper
-pub fn select_atom_default<T, S, F>(
+/// Passing the previous slice lets the selector reuse it instead of
+/// building an equal value again.
+///
+/// **FP Pattern**: Scan (previous output feeds the next step), memoization
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let names = select_atom_with_prev(
+///     users,
+///     |users: &Vec<User>, prev: Option<&Arc<Vec<String>>>| {
+///         let names: Vec<String> = users.iter().map(|u| u.name.clone()).collect();
+///         match prev {
+///             Some(prev) if **prev == names => prev.clone(),
+///             _ => Arc::new(names),
+///         }
+///     },
+///     Arc::ptr_eq,
+/// );
+/// ```
+pub fn select_atom_with_prev<T, S, F, E>(
     source_atom: Atom<T>,
     selector: F,
+    equality_fn: E,
 ) -> Atom<S>
 where
     T: Clone + Send + Sync + 'static,
-    S: Clone + PartialEq + Send + Sync + 'static,
-    F: Fn(&T) -> S + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    F: Fn(&T, Option<&S>) -> S + Send + Sync + 'static,
+    E: Fn(&S, &S) -> bool + Send + Sync + 'static,
 {
-    select_atom(source_atom, selector, |a, b| a == b)
+    select_with(
+        source_atom,
+        move |value, previous| Ok(selector(value, previous)),
+        equality_fn,
+    )
 }
 
-/// Memoization helper for select_atom
+/// Create a select atom whose selector may fail
 ///
-/// Reference: `jotai/src/vanilla/utils/selectAtom.ts:4-16`
+/// Like `select_atom`, but the selector returns a `Result` (e.g. when the
+/// slice has to be parsed out of the source). A failure becomes the select
+/// atom's error and stays cached until the source changes; the next
+/// successful selection is compared against the last successful slice.
 ///
-/// ```typescript
-/// const getCached = <T>(c: () => T, m: WeakMap<object, T>, k: object): T =>
-///   (m.has(k) ? m : m.set(k, c())).get(k) as T
-///
-/// const cache1 = new WeakMap()
-/// const memo3 = <T>(
-///   create: () => T,
-///   dep1: object,
-///   dep2: object,
-///   dep3: object,
-/// ): T => {
-///   const cache2 = getCached(() => new WeakMap(), cache1, dep1)
-///   const cache3 = getCached(() => new WeakMap(), cache2, dep2)
-///   return getCached(create, cache3, dep3)
-/// }
+/// **FP Pattern**: Kleisli composition (selector returning `Result`)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let port = try_select_atom(
+///     config_text,
+///     |text: &String| text.trim().parse::<u16>().map_err(|e| AtomError::Generic(e.to_string())),
+///     |a, b| a == b,
+/// );
 /// ```
+pub fn try_select_atom<T, S, F, E>(source_atom: Atom<T>, selector: F, equality_fn: E) -> Atom<S>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    F: Fn(&T) -> Result<S> + Send + Sync + 'static,
+    E: Fn(&S, &S) -> bool + Send + Sync + 'static,
+{
+    select_with(source_atom, move |value, _| selector(value), equality_fn)
+}
+
+/// Shared select machinery: a fallible selector seeing the previous slice
+fn select_with<T, S, F, E>(source_atom: Atom<T>, selector: F, equality_fn: E) -> Atom<S>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    F: Fn(&T, Option<&S>) -> Result<S> + Send + Sync + 'static,
+    E: Fn(&S, &S) -> bool + Send + Sync + 'static,
+{
+    // Jotai reads the atom's own previous value through a self-reference
+    // (`derivedAtom.init = EMPTY`); here the store hands it to the read
+    // function through `Getter::previous`.
+    let equality_fn = Arc::new(equality_fn);
+    let is_equal = equality_fn.clone();
+
+    atom_derived(move |get| {
+        let value = get.get(&source_atom)?;
+        let previous = get.previous::<S>();
+        let slice = selector(&value, previous.as_ref())?;
+        Ok(match previous {
+            Some(previous) if equality_fn(&previous, &slice) => previous,
+            _ => slice,
+        })
+    })
+    .with_equality(move |previous, next| is_equal(previous, next))
+}
+
+/// Create an async atom selecting a slice of an async source
 ///
-/// Jotai uses nested WeakMaps for multi-key memoization.
-/// In Rust, we might use a different approach (e.g., Arc<Mutex<HashMap>>).
+/// Reference: `jotai/src/vanilla/utils/selectAtom.ts` (with a promise-valued source)
 ///
-/// **FP Pattern**: Memoization with multiple keys
+/// Resolving the select atom (`Store::get_async`) awaits the source and
+/// applies `selector` to its resolved value. As with `select_atom`, a slice
+/// equal to the previous one per `equality_fn` is kept, so the atom's epoch
+/// does not move and its dependents keep their cached values.
 ///
-/// TODO: Phase 7.2 - Implement memoization helper if needed
-struct MemoCache {
-    // TODO: Design cache structure for Rust
-    // Options:
-    // 1. HashMap with tuple keys
-    // 2. Nested HashMaps
-    // 3. LRU cache
+/// **FP Pattern**: Function composition over deferred values (`map` on a future)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let profile = atom_async(move |get, _| fetch_profile(get.get(user_id.as_atom())));
+/// let display_name = select_atom_async(profile, |p: &Profile| p.name.clone(), |a, b| a == b);
+/// let name = store.get_async(&display_name).await?;
+/// ```
+#[cfg(feature = "async")]
+pub fn select_atom_async<T, S, F, E>(source_atom: Atom<T>, selector: F, equality_fn: E) -> Atom<S>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Clone + Send + Sync + 'static,
+    F: Fn(&T) -> S + Send + Sync + 'static,
+    E: Fn(&S, &S) -> bool + Send + Sync + 'static,
+{
+    let selector = Arc::new(selector);
+    let equality_fn = Arc::new(equality_fn);
+    let is_equal = equality_fn.clone();
+
+    atom_async(move |get, _signal| {
+        let source = get.get_async(&source_atom);
+        let previous = get.previous::<S>();
+        let selector = selector.clone();
+        let equality_fn = equality_fn.clone();
+        async move {
+            let slice = selector(&source.await?);
+            Ok(match previous {
+                Some(previous) if equality_fn(&previous, &slice) => previous,
+                _ => slice,
+            })
+        }
+    })
+    .with_equality(move |previous, next| is_equal(previous, next))
+}
+
+/// Select atom with default Object.is equality
+pub fn select_atom_default<T, S, F>(source_atom: Atom<T>, selector: F) -> Atom<S>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Clone + PartialEq + Send + Sync + 'static,
+    F: Fn(&T) -> S + Send + Sync + 'static,
+{
+    select_atom(source_atom, selector, |a, b| a == b)
 }
 
 #[cfg(test)]
@@ -151,28 +238,198 @@ mod tests {
     use super::*;
     use crate::atom::atom;
     use crate::store::Store;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_select_atom_basic() {
+        let store = Store::new();
+        let source = atom((1, 2));
+        let first = select_atom(source.as_atom().clone(), |(a, _)| *a, |x, y| x == y);
+
+        assert_eq!(store.get(&first).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_select_atom_memoization() {
+        let store = Store::new();
+        let source = atom((1, 2));
+        let first = select_atom_default(source.as_atom().clone(), |(a, _)| *a);
+
+        let recomputes = Arc::new(AtomicUsize::new(0));
+        let dependent = {
+            let first = first.clone();
+            let recomputes = recomputes.clone();
+            atom_derived(move |get| {
+                recomputes.fetch_add(1, Ordering::SeqCst);
+                Ok(get.get(&first)? * 10)
+            })
+        };
+        assert_eq!(store.get(&dependent).unwrap(), 10);
+
+        // Change second element: the slice is equal, the dependent stays cached
+        store.set(&source, (1, 3)).unwrap();
+        assert_eq!(store.get(&dependent).unwrap(), 10);
+        assert_eq!(recomputes.load(Ordering::SeqCst), 1);
+
+        store.set(&source, (4, 3)).unwrap();
+        assert_eq!(store.get(&dependent).unwrap(), 40);
+        assert_eq!(recomputes.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_select_atom_with_prev_reuses_previous_slice() {
+        let store = Store::new();
+        let users = atom(vec![("ada", 36), ("grace", 45)]);
+        let allocations = Arc::new(AtomicUsize::new(0));
+
+        let counter = allocations.clone();
+        let names = select_atom_with_prev(
+            users.as_atom().clone(),
+            move |users: &Vec<(&str, i32)>, prev: Option<&Arc<Vec<String>>>| {
+                let unchanged = prev.is_some_and(|prev| {
+                    prev.len() == users.len()
+                        && prev.iter().zip(users).all(|(name, user)| name == user.0)
+                });
+                match prev {
+                    Some(prev) if unchanged => prev.clone(),
+                    _ => {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Arc::new(users.iter().map(|user| user.0.to_string()).collect())
+                    }
+                }
+            },
+            Arc::ptr_eq,
+        );
+
+        let before = store.get(&names).unwrap();
+        assert_eq!(*before, vec!["ada".to_string(), "grace".to_string()]);
+
+        // Only ages change: the selector hands back the same allocation
+        store.set(&users, vec![("ada", 37), ("grace", 46)]).unwrap();
+        let after = store.get(&names).unwrap();
+        assert!(Arc::ptr_eq(&before, &after));
+        assert_eq!(allocations.load(Ordering::SeqCst), 1);
+
+        store.set(&users, vec![("ada", 37)]).unwrap();
+        assert_eq!(*store.get(&names).unwrap(), vec!["ada".to_string()]);
+        assert_eq!(allocations.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_select_atom_with_prev_accumulates() {
+        let store = Store::new();
+        let latest = atom(1);
+        let first_reads = Arc::new(AtomicUsize::new(0));
+
+        let counter = first_reads.clone();
+        let seen = select_atom_with_prev(
+            latest.as_atom().clone(),
+            move |value: &i32, prev: Option<&Vec<i32>>| match prev {
+                Some(prev) => prev.iter().copied().chain([*value]).collect(),
+                None => {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    vec![*value]
+                }
+            },
+            |a, b| a == b,
+        );
+
+        assert_eq!(store.get(&seen).unwrap(), vec![1]);
+        store.set(&latest, 2).unwrap();
+        assert_eq!(store.get(&seen).unwrap(), vec![1, 2]);
+        store.set(&latest, 3).unwrap();
+        assert_eq!(store.get(&seen).unwrap(), vec![1, 2, 3]);
+        // Only the very first read had no previous slice
+        assert_eq!(first_reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_try_select_atom_caches_failure_until_source_changes() {
+        use crate::error::AtomError;
+
+        let store = Store::new();
+        let text = atom("8080".to_string());
+        let parses = Arc::new(AtomicUsize::new(0));
+
+        let counter = parses.clone();
+        let port = try_select_atom(
+            text.as_atom().clone(),
+            move |text: &String| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                text.parse::<u16>()
+                    .map_err(|e| AtomError::Generic(e.to_string()))
+            },
+            |a, b| a == b,
+        );
+        assert_eq!(store.get(&port).unwrap(), 8080);
+
+        store.set(&text, "eighty".to_string()).unwrap();
+        assert!(matches!(store.get(&port), Err(AtomError::Generic(_))));
+        assert!(store.get(&port).is_err());
+        assert_eq!(parses.load(Ordering::SeqCst), 2);
+
+        store.set(&text, "9090".to_string()).unwrap();
+        assert_eq!(store.get(&port).unwrap(), 9090);
+        assert_eq!(parses.load(Ordering::SeqCst), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_select_atom_async_resolves_and_memoizes() {
+        #[derive(Clone)]
+        struct Profile {
+            name: String,
+            visits: u32,
+        }
+
+        let store = Store::new();
+        let name = atom("ada".to_string());
+        let visits = atom(1);
+        let profile = {
+            let (name, visits) = (name.clone(), visits.clone());
+            atom_async(move |get, _| {
+                let profile = get.get(name.as_atom()).and_then(|name| {
+                    Ok(Profile {
+                        name,
+                        visits: get.get(visits.as_atom())?,
+                    })
+                });
+                async move {
+                    // Resolve on a later poll, like a real fetch
+                    tokio::task::yield_now().await;
+                    profile
+                }
+            })
+        };
+        let display_name =
+            select_atom_async(profile.clone(), |p: &Profile| p.name.clone(), |a, b| a == b);
+        let visit_count = select_atom_async(profile, |p: &Profile| p.visits, |a, b| a == b);
+
+        let recomputes = Arc::new(AtomicUsize::new(0));
+        let shouted = {
+            let display_name = display_name.clone();
+            let recomputes = recomputes.clone();
+            atom_derived(move |get| {
+                recomputes.fetch_add(1, Ordering::SeqCst);
+                Ok(get.get(&display_name)?.to_uppercase())
+            })
+        };
+
+        assert_eq!(store.get_async(&display_name).await.unwrap(), "ada");
+        assert_eq!(store.get(&shouted).unwrap(), "ADA");
+        let epoch = store.epoch_of(display_name.id());
+
+        // Another field changes: same slice, same epoch, dependent stays cached
+        store.set(&visits, 2).unwrap();
+        assert_eq!(store.get_async(&visit_count).await.unwrap(), 2);
+        assert_eq!(store.get_async(&display_name).await.unwrap(), "ada");
+        assert_eq!(store.epoch_of(display_name.id()), epoch);
+        assert_eq!(store.get(&shouted).unwrap(), "ADA");
+        assert_eq!(recomputes.load(Ordering::SeqCst), 1);
 
-    // TODO: Phase 7.2 - Add tests for select_atom
-    //
-    // #[test]
-    // fn test_select_atom_basic() {
-    //     let store = Store::new();
-    //     let source = atom((1, 2));
-    //     let first = select_atom(source, |(a, _)| *a, |x, y| x == y);
-    //
-    //     assert_eq!(store.get(&first).unwrap(), 1);
-    // }
-    //
-    // #[test]
-    // fn test_select_atom_memoization() {
-    //     let store = Store::new();
-    //     let source = atom((1, 2));
-    //     let first = select_atom(source, |(a, _)| *a, |x, y| x == y);
-    //
-    //     // Change second element
-    //     store.set(&source, (1, 3)).unwrap();
-    //
-    //     // First should not recompute (value didn't change)
-    //     // TODO: How to verify recomputation didn't happen?
-    // }
+        store.set(&name, "grace".to_string()).unwrap();
+        assert_eq!(store.get_async(&display_name).await.unwrap(), "grace");
+        assert_eq!(store.get(&shouted).unwrap(), "GRACE");
+        assert_eq!(recomputes.load(Ordering::SeqCst), 2);
+    }
 }
@@ -0,0 +1,150 @@
+//! Split a list atom into one writable atom per element
+//!
+//! Reference: `jotai/src/vanilla/utils/splitAtom.ts`
+//!
+//! The split atom holds a list of element atoms, one per index of the
+//! parent list. Each element atom reads and writes a single slot, so rows
+//! of a list can be read, written and subscribed to independently.
+//!
+//! ## Functional Programming Patterns
+//! - Lenses (each element atom focuses on one slot)
+//! - Memoization (element atoms are cached by index)
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::atom::{atom_derived, atom_writable, Atom, WritableAtom};
+use crate::error::AtomError;
+
+/// Create an atom holding one writable atom per element of `list_atom`
+///
+/// Reference: `jotai/src/vanilla/utils/splitAtom.ts`
+///
+/// ```typescript
+/// export function splitAtom<Item>(
+///   arrAtom: WritableAtom<Item[], [Item[]], void>,
+/// ): WritableAtom<PrimitiveAtom<Item>[], [SplitAtomAction<Item>], void>
+/// ```
+///
+/// Element atoms are memoized by index: the atom for index `i` is the same
+/// (same ID) for as long as the split atom exists, and the split atom only
+/// changes when the parent's length does. Reading an element reads its slot
+/// of the parent; writing it splices the value back into the parent.
+/// Reading or writing an index past the parent's current length fails with
+/// `AtomError::ReadError` / `AtomError::WriteError`.
+///
+/// Unlike Jotai's `splitAtom`, elements are keyed by index only, and the
+/// split atom itself is read-only (no insert/remove/move actions).
+///
+/// **FP Pattern**: Lens per element, memoized factory
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom, split_atom};
+///
+/// let todos = atom(vec!["write docs".to_string(), "ship".to_string()]);
+/// let rows = split_atom(todos.clone());
+/// let first = store.get(&rows)?[0].clone();
+/// store.set(&first, "write more docs".to_string())?;
+/// ```
+pub fn split_atom<T>(list_atom: WritableAtom<Vec<T>>) -> Atom<Vec<WritableAtom<T>>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let elements: Arc<Mutex<Vec<WritableAtom<T>>>> = Arc::new(Mutex::new(Vec::new()));
+
+    atom_derived(move |get| {
+        let len = get.get(list_atom.as_atom())?.len();
+        let mut elements = elements.lock();
+        for index in elements.len()..len {
+            elements.push(element_atom(list_atom.clone(), index));
+        }
+        Ok(elements[..len].to_vec())
+    })
+    .with_equality(|previous, next| {
+        previous.len() == next.len() && previous.iter().zip(next).all(|(a, b)| a.id() == b.id())
+    })
+}
+
+/// Writable atom reading and writing slot `index` of `list_atom`
+fn element_atom<T>(list_atom: WritableAtom<Vec<T>>, index: usize) -> WritableAtom<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let source = list_atom.clone();
+    atom_writable(
+        move |get| {
+            get.get(source.as_atom())?
+                .get(index)
+                .cloned()
+                .ok_or_else(|| {
+                    AtomError::read_error(source.id(), format!("index {} out of range", index))
+                })
+        },
+        move |set, value: T| {
+            let mut items = set.get(list_atom.as_atom())?;
+            let slot = items.get_mut(index).ok_or_else(|| {
+                AtomError::write_error(list_atom.id(), format!("index {} out of range", index))
+            })?;
+            *slot = value;
+            set.set(&list_atom, items)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+
+    #[test]
+    fn test_split_atom_reads_and_writes_elements() {
+        let store = Store::new();
+        let todos = atom(vec!["a".to_string(), "b".to_string()]);
+        let rows = split_atom(todos.clone());
+
+        let elements = store.get(&rows).unwrap();
+        assert_eq!(elements.len(), 2);
+        assert_eq!(store.get(elements[1].as_atom()).unwrap(), "b");
+
+        // Writing an element splices it back into the parent
+        store.set(&elements[0], "A".to_string()).unwrap();
+        assert_eq!(store.get(todos.as_atom()).unwrap(), vec!["A", "b"]);
+
+        // Same length: same element atoms
+        let again = store.get(&rows).unwrap();
+        assert_eq!(again[0].id(), elements[0].id());
+        assert_eq!(again[1].id(), elements[1].id());
+    }
+
+    #[test]
+    fn test_split_atom_follows_parent_length() {
+        let store = Store::new();
+        let list = atom(vec![1, 2]);
+        let rows = split_atom(list.clone());
+        let before = store.get(&rows).unwrap();
+
+        store.set(&list, vec![1, 2, 3]).unwrap();
+        let grown = store.get(&rows).unwrap();
+        assert_eq!(grown.len(), 3);
+        assert_eq!(grown[0].id(), before[0].id());
+        assert_eq!(store.get(grown[2].as_atom()).unwrap(), 3);
+
+        store.set(&list, vec![7]).unwrap();
+        assert_eq!(store.get(&rows).unwrap().len(), 1);
+
+        // A removed element no longer has a slot
+        let stale = &grown[2];
+        assert!(matches!(
+            store.get(stale.as_atom()),
+            Err(AtomError::ReadError { .. })
+        ));
+        assert!(matches!(
+            store.set(stale, 9),
+            Err(AtomError::WriteError { .. })
+        ));
+    }
+}
@@ -0,0 +1,70 @@
+//! Zip a list of atoms into one atom of a list
+//!
+//! This is the inverse of `splitAtom` (`jotai/src/vanilla/utils/splitAtom.ts`):
+//! instead of splitting an array atom into item atoms, it collects the values
+//! of several atoms into a single `Vec`.
+//!
+//! ## Functional Programming Patterns
+//! - Traversal (`Vec<Atom<T>>` to `Atom<Vec<T>>`, like `sequence`)
+//! - Function composition
+
+use crate::atom::{Atom, atom_derived};
+
+/// Create a derived atom collecting the values of `atoms`, in order
+///
+/// Every input atom is a dependency, so the zipped `Vec` is recomputed when
+/// any of them changes. If an input fails to read, the zipped atom fails
+/// with that error.
+///
+/// **FP Pattern**: Traversal / sequence
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use jotai_rs::{atom, zip_atoms, Store};
+///
+/// let x = atom(1);
+/// let y = atom(2);
+/// let both = zip_atoms(vec![x.as_atom().clone(), y.as_atom().clone()]);
+///
+/// let store = Store::new();
+/// assert_eq!(store.get(&both).unwrap(), vec![1, 2]);
+/// ```
+pub fn zip_atoms<T>(atoms: Vec<Atom<T>>) -> Atom<Vec<T>>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    atom_derived(move |get| atoms.iter().map(|atom| get.get(atom)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atom::atom;
+    use crate::store::Store;
+
+    #[test]
+    fn test_zip_atoms_updates_changed_index() {
+        let store = Store::new();
+        let a = atom(1);
+        let b = atom(2);
+        let c = atom(3);
+
+        let zipped = zip_atoms(vec![
+            a.as_atom().clone(),
+            b.as_atom().clone(),
+            c.as_atom().clone(),
+        ]);
+        assert_eq!(store.get(&zipped).unwrap(), vec![1, 2, 3]);
+
+        store.set(&b, 20).unwrap();
+        assert_eq!(store.get(&zipped).unwrap(), vec![1, 20, 3]);
+    }
+
+    #[test]
+    fn test_zip_atoms_empty() {
+        let store = Store::new();
+        let zipped = zip_atoms(Vec::<Atom<i32>>::new());
+        assert_eq!(store.get(&zipped).unwrap(), Vec::<i32>::new());
+    }
+}
@@ -165,20 +165,19 @@ fn test_write_multiple_independent_atoms() {
 // ============================================================================
 
 #[test]
-#[ignore = "Phase 1.4 - Implement SetStateAction"]
 fn test_set_with_updater_function() {
-    // TODO: Phase 1.4 - Support updater functions
+    // Each updater receives the value left by the previous write
     // Reference: `jotai/tests/vanilla/basic.test.tsx` line 35
 
     let store = Store::new();
     let count = atom(0);
 
     // Using a closure to update based on previous value
-    // store.set_with_updater(&count, |prev| prev + 1).unwrap();
-    // assert_eq!(store.get(&count.as_atom()).unwrap(), 1);
-    //
-    // store.set_with_updater(&count, |prev| prev * 2).unwrap();
-    // assert_eq!(store.get(&count.as_atom()).unwrap(), 2);
+    store.set_with(&count, |prev| prev + 1).unwrap();
+    assert_eq!(store.get(count.as_atom()).unwrap(), 1);
+
+    store.set_with(&count, |prev| prev * 2).unwrap();
+    assert_eq!(store.get(count.as_atom()).unwrap(), 2);
 }
 
 // ============================================================================
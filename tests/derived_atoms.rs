@@ -6,40 +6,48 @@
 //! - Automatic recomputation
 //! - Epoch-based caching
 
-use jotai_rs::{atom, atom_derived, Store};
+use jotai_rs::{
+    atom, atom_computed_default, atom_derived, atom_derived_explicit, atom_derived_hashed,
+    atom_derived_stateful, Getter, PrimitiveAtom, Result, Store,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 // ============================================================================
 // PHASE 2.2: Derived Atom Creation
 // ============================================================================
 
 #[test]
-#[ignore = "Phase 2.2 - Implement derived atoms"]
 fn test_simple_derived_atom() {
-    // TODO: Phase 2.2 - Basic derived atom
     // Reference: `jotai/tests/vanilla/derived-atom.test.tsx` line 10
 
     let store = Store::new();
     let count = atom(3);
 
-    let doubled = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c * 2)
-    });
+    let doubled = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c * 2)
+        })
+    };
 
     assert_eq!(store.get(&doubled).unwrap(), 6);
 }
 
 #[test]
-#[ignore = "Phase 2.2 - Test derived atom updates"]
 fn test_derived_atom_updates_with_dependency() {
-    // TODO: Phase 2.2 - Derived atoms recompute when dependencies change
+    // Derived atoms recompute when dependencies change
 
     let store = Store::new();
     let count = atom(3);
-    let doubled = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c * 2)
-    });
+    let doubled = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c * 2)
+        })
+    };
 
     assert_eq!(store.get(&doubled).unwrap(), 6);
 
@@ -51,23 +59,28 @@ fn test_derived_atom_updates_with_dependency() {
 }
 
 #[test]
-#[ignore = "Phase 2.2 - Test chained derived atoms"]
 fn test_chained_derived_atoms() {
-    // TODO: Phase 2.2 - Derived atoms depending on other derived atoms
+    // Derived atoms depending on other derived atoms
     // Reference: `jotai/tests/vanilla/derived-atom.test.tsx` line 38
 
     let store = Store::new();
     let count = atom(1);
 
-    let doubled = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c * 2)
-    });
-
-    let quadrupled = atom_derived(move |get| {
-        let d = get(&doubled)?;
-        Ok(d * 2)
-    });
+    let doubled = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c * 2)
+        })
+    };
+
+    let quadrupled = {
+        let doubled = doubled.clone();
+        atom_derived(move |get| {
+            let d = get.get(&doubled)?;
+            Ok(d * 2)
+        })
+    };
 
     assert_eq!(store.get(&quadrupled).unwrap(), 4);
 
@@ -77,9 +90,30 @@ fn test_chained_derived_atoms() {
 }
 
 #[test]
-#[ignore = "Phase 2.2 - Test diamond dependency"]
+fn test_derived_string_from_two_sources() {
+    let store = Store::new();
+    let first = atom("Ada".to_string());
+    let last = atom("Lovelace".to_string());
+
+    let full_name = {
+        let first = first.clone();
+        let last = last.clone();
+        atom_derived(move |get| {
+            let first = get.get(first.as_atom())?;
+            let last = get.get(last.as_atom())?;
+            Ok(format!("{} {}", first, last))
+        })
+    };
+
+    assert_eq!(store.get(&full_name).unwrap(), "Ada Lovelace");
+
+    store.set(&last, "Byron".to_string()).unwrap();
+    assert_eq!(store.get(&full_name).unwrap(), "Ada Byron");
+}
+
+#[test]
 fn test_diamond_dependency_pattern() {
-    // TODO: Phase 2.2 - Multiple paths to same atom
+    // Multiple paths to same atom; the shared node recomputes once per change
     //
     // Dependency graph:
     //     count
@@ -91,28 +125,49 @@ fn test_diamond_dependency_pattern() {
     let store = Store::new();
     let count = atom(10);
 
-    let plus_one = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c + 1)
-    });
-
-    let plus_two = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c + 2)
-    });
-
-    let sum = atom_derived(move |get| {
-        let a = get(&plus_one)?;
-        let b = get(&plus_two)?;
-        Ok(a + b)
-    });
+    let plus_one = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c + 1)
+        })
+    };
+
+    let plus_two = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c + 2)
+        })
+    };
+
+    let sum_computations = Arc::new(AtomicUsize::new(0));
+    let sum = {
+        let plus_one = plus_one.clone();
+        let plus_two = plus_two.clone();
+        let sum_computations = sum_computations.clone();
+        atom_derived(move |get| {
+            sum_computations.fetch_add(1, Ordering::SeqCst);
+            let a = get.get(&plus_one)?;
+            let b = get.get(&plus_two)?;
+            Ok(a + b)
+        })
+    };
 
     // 10 + 1 + 10 + 2 = 23
     assert_eq!(store.get(&sum).unwrap(), 23);
+    assert_eq!(sum_computations.load(Ordering::SeqCst), 1);
 
     store.set(&count, 5).unwrap();
     // 5 + 1 + 5 + 2 = 13
     assert_eq!(store.get(&sum).unwrap(), 13);
+    assert_eq!(sum_computations.load(Ordering::SeqCst), 2);
+
+    // Mounted: the eager recompute pass must also visit `sum` only once
+    let _unsub = store.sub(&sum, || {});
+    store.set(&count, 7).unwrap();
+    assert_eq!(store.get(&sum).unwrap(), 17);
+    assert_eq!(sum_computations.load(Ordering::SeqCst), 3);
 }
 
 // ============================================================================
@@ -128,11 +183,15 @@ fn test_dependency_tracking() {
     let a = atom(1);
     let b = atom(2);
 
-    let sum = atom_derived(move |get| {
-        let av = get(&a.as_atom())?;
-        let bv = get(&b.as_atom())?;
-        Ok(av + bv)
-    });
+    let sum = {
+        let a = a.clone();
+        let b = b.clone();
+        atom_derived(move |get| {
+            let av = get.get(a.as_atom())?;
+            let bv = get.get(b.as_atom())?;
+            Ok(av + bv)
+        })
+    };
 
     // Read the derived atom
     store.get(&sum).unwrap();
@@ -148,24 +207,32 @@ fn test_dependency_tracking() {
 // ============================================================================
 
 #[test]
-#[ignore = "Phase 2.3 - Test invalidation propagation"]
 fn test_invalidation_cascade() {
-    // TODO: Phase 2.3 - Changing one atom invalidates all dependents
+    // Changing one atom invalidates all dependents
 
     let store = Store::new();
     let base = atom(1);
-    let derived1 = atom_derived(move |get| {
-        let v = get(&base.as_atom())?;
-        Ok(v + 1)
-    });
-    let derived2 = atom_derived(move |get| {
-        let v = get(&derived1)?;
-        Ok(v + 1)
-    });
-    let derived3 = atom_derived(move |get| {
-        let v = get(&derived2)?;
-        Ok(v + 1)
-    });
+    let derived1 = {
+        let base = base.clone();
+        atom_derived(move |get| {
+            let v = get.get(base.as_atom())?;
+            Ok(v + 1)
+        })
+    };
+    let derived2 = {
+        let derived1 = derived1.clone();
+        atom_derived(move |get| {
+            let v = get.get(&derived1)?;
+            Ok(v + 1)
+        })
+    };
+    let derived3 = {
+        let derived2 = derived2.clone();
+        atom_derived(move |get| {
+            let v = get.get(&derived2)?;
+            Ok(v + 1)
+        })
+    };
 
     // Chain: base (1) -> derived1 (2) -> derived2 (3) -> derived3 (4)
     assert_eq!(store.get(&derived3).unwrap(), 4);
@@ -185,53 +252,58 @@ fn test_invalidation_cascade() {
 // ============================================================================
 
 #[test]
-#[ignore = "Phase 2.4 - Test cache invalidation with epochs"]
 fn test_epoch_based_caching() {
-    // TODO: Phase 2.4 - Verify atoms use epoch numbers for cache validation
-
     let store = Store::new();
     let a = atom(1);
     let b = atom(2);
-    let sum = atom_derived(move |get| {
-        let av = get(&a.as_atom())?;
-        let bv = get(&b.as_atom())?;
-        Ok(av + bv)
-    });
+    let sum = {
+        let a = a.clone();
+        let b = b.clone();
+        atom_derived(move |get| {
+            let av = get.get(a.as_atom())?;
+            let bv = get.get(b.as_atom())?;
+            Ok(av + bv)
+        })
+    };
 
     // First read - computes
     assert_eq!(store.get(&sum).unwrap(), 3);
 
     // Second read - should use cache (no dependencies changed)
     assert_eq!(store.get(&sum).unwrap(), 3);
-
-    // TODO: Verify internally that sum wasn't recomputed
-    // (e.g., by checking epoch number)
+    #[cfg(feature = "profiling")]
+    assert_eq!(store.recompute_count(sum.id()), 1);
 
     // Change dependency
     store.set(&a, 5).unwrap();
 
     // Should recompute because epoch changed
     assert_eq!(store.get(&sum).unwrap(), 7);
+    #[cfg(feature = "profiling")]
+    assert_eq!(store.recompute_count(sum.id()), 2);
 }
 
 #[test]
-#[ignore = "Phase 2.4 - Test selective recomputation"]
 fn test_only_affected_atoms_recompute() {
-    // TODO: Phase 2.4 - Only atoms depending on changed atoms recompute
-
     let store = Store::new();
     let a = atom(1);
     let b = atom(2);
 
-    let a_plus_10 = atom_derived(move |get| {
-        let v = get(&a.as_atom())?;
-        Ok(v + 10)
-    });
-
-    let b_plus_10 = atom_derived(move |get| {
-        let v = get(&b.as_atom())?;
-        Ok(v + 10)
-    });
+    let a_plus_10 = {
+        let a = a.clone();
+        atom_derived(move |get| {
+            let v = get.get(a.as_atom())?;
+            Ok(v + 10)
+        })
+    };
+
+    let b_plus_10 = {
+        let b = b.clone();
+        atom_derived(move |get| {
+            let v = get.get(b.as_atom())?;
+            Ok(v + 10)
+        })
+    };
 
     assert_eq!(store.get(&a_plus_10).unwrap(), 11);
     assert_eq!(store.get(&b_plus_10).unwrap(), 12);
@@ -243,7 +315,11 @@ fn test_only_affected_atoms_recompute() {
     assert_eq!(store.get(&a_plus_10).unwrap(), 15);
     assert_eq!(store.get(&b_plus_10).unwrap(), 12); // Still cached
 
-    // TODO: Verify b_plus_10 didn't recompute (check epoch)
+    #[cfg(feature = "profiling")]
+    {
+        assert_eq!(store.recompute_count(a_plus_10.id()), 2);
+        assert_eq!(store.recompute_count(b_plus_10.id()), 1);
+    }
 }
 
 // ============================================================================
@@ -259,16 +335,22 @@ fn test_function_composition_pattern() {
     let x = atom(5);
 
     // f(x) = x + 1
-    let f = atom_derived(move |get| {
-        let v = get(&x.as_atom())?;
-        Ok(v + 1)
-    });
+    let f = {
+        let x = x.clone();
+        atom_derived(move |get| {
+            let v = get.get(x.as_atom())?;
+            Ok(v + 1)
+        })
+    };
 
     // g(x) = x * 2
-    let g = atom_derived(move |get| {
-        let v = get(&f)?;
-        Ok(v * 2)
-    });
+    let g = {
+        let f = f.clone();
+        atom_derived(move |get| {
+            let v = get.get(&f)?;
+            Ok(v * 2)
+        })
+    };
 
     // g(f(x)) = (x + 1) * 2
     assert_eq!(store.get(&g).unwrap(), 12); // (5 + 1) * 2 = 12
@@ -283,10 +365,13 @@ fn test_pure_functions_in_derivation() {
     let count = atom(5);
 
     // Pure: same inputs always produce same output
-    let doubled = atom_derived(move |get| {
-        let c = get(&count.as_atom())?;
-        Ok(c * 2)
-    });
+    let doubled = {
+        let count = count.clone();
+        atom_derived(move |get| {
+            let c = get.get(count.as_atom())?;
+            Ok(c * 2)
+        })
+    };
 
     let v1 = store.get(&doubled).unwrap();
     let v2 = store.get(&doubled).unwrap();
@@ -308,14 +393,19 @@ fn test_conditional_dependencies() {
     let a = atom(10);
     let b = atom(20);
 
-    let conditional = atom_derived(move |get| {
-        let should_use_a = get(&use_a.as_atom())?;
-        if should_use_a {
-            get(&a.as_atom())
-        } else {
-            get(&b.as_atom())
-        }
-    });
+    let conditional = {
+        let use_a = use_a.clone();
+        let a = a.clone();
+        let b = b.clone();
+        atom_derived(move |get| {
+            let should_use_a = get.get(use_a.as_atom())?;
+            if should_use_a {
+                get.get(a.as_atom())
+            } else {
+                get.get(b.as_atom())
+            }
+        })
+    };
 
     assert_eq!(store.get(&conditional).unwrap(), 10);
 
@@ -327,3 +417,136 @@ fn test_conditional_dependencies() {
     store.set(&a, 999).unwrap();
     assert_eq!(store.get(&conditional).unwrap(), 20); // Still 20, not affected
 }
+
+// ============================================================================
+// Stateful derived atoms
+// ============================================================================
+
+#[test]
+fn test_stateful_derived_running_max() {
+    let store = Store::new();
+    let input = atom(3);
+
+    let running_max = {
+        let input = input.clone();
+        atom_derived_stateful(i32::MIN, move |get, max| {
+            Ok((*max).max(get.get(input.as_atom())?))
+        })
+    };
+
+    assert_eq!(store.get(&running_max).unwrap(), 3);
+
+    for (value, expected) in [(7, 7), (2, 7), (9, 9), (-4, 9)] {
+        store.set(&input, value).unwrap();
+        assert_eq!(store.get(&running_max).unwrap(), expected);
+    }
+
+    // Another store starts from the initial value
+    let other = Store::new();
+    assert_eq!(other.get(&running_max).unwrap(), 3);
+}
+
+#[test]
+fn test_computed_default_is_captured_once() {
+    let store = Store::new();
+    let base = atom(2);
+
+    let snapshot = {
+        let base = base.clone();
+        atom_computed_default(move |get| Ok(get.get(base.as_atom())? * 10))
+    };
+    let doubled = {
+        let snapshot = snapshot.clone();
+        atom_derived(move |get| Ok(get.get(snapshot.as_atom())? * 2))
+    };
+
+    assert_eq!(store.get(snapshot.as_atom()).unwrap(), 20);
+    assert_eq!(store.get(&doubled).unwrap(), 40);
+
+    // The default was fixed at first read
+    store.set(&base, 5).unwrap();
+    assert_eq!(store.get(snapshot.as_atom()).unwrap(), 20);
+    assert_eq!(store.get(&doubled).unwrap(), 40);
+
+    // Afterwards it is an ordinary primitive
+    store.set(&snapshot, 7).unwrap();
+    assert_eq!(store.get(snapshot.as_atom()).unwrap(), 7);
+    assert_eq!(store.get(&doubled).unwrap(), 14);
+    store.set(&base, 6).unwrap();
+    assert_eq!(store.get(snapshot.as_atom()).unwrap(), 7);
+
+    // Another store computes its own default from its own base
+    let other = Store::new();
+    other.set(&base, 3).unwrap();
+    assert_eq!(other.get(snapshot.as_atom()).unwrap(), 30);
+}
+
+#[test]
+fn test_explicit_dependencies_cover_untracked_choice() {
+    let store = Store::new();
+    let sources = vec![atom(1), atom(10)];
+    // Which source to read lives outside the store, so tracking cannot see it
+    let selected = Arc::new(AtomicUsize::new(0));
+
+    fn read_selected(
+        sources: Vec<PrimitiveAtom<i32>>,
+        selected: Arc<AtomicUsize>,
+    ) -> impl Fn(&dyn Getter) -> Result<i32> + Send + Sync {
+        move |get| get.get(sources[selected.load(Ordering::SeqCst)].as_atom())
+    }
+    let tracked = atom_derived(read_selected(sources.clone(), selected.clone()));
+    let declared = atom_derived_explicit(
+        sources.iter().map(|source| source.id()).collect(),
+        read_selected(sources.clone(), selected.clone()),
+    );
+
+    assert_eq!(store.get(&tracked).unwrap(), 1);
+    assert_eq!(store.get(&declared).unwrap(), 1);
+
+    // Switch to the second source, then change it
+    selected.store(1, Ordering::SeqCst);
+    store.set(&sources[1], 20).unwrap();
+
+    // Tracking only saw the first source: the tracked atom is stale
+    assert_eq!(store.get(&tracked).unwrap(), 1);
+    assert_eq!(store.get(&declared).unwrap(), 20);
+}
+
+#[test]
+fn test_hashed_derived_atom_skips_identical_recompute() {
+    let store = Store::new();
+    let size = atom(10_000usize);
+    let revision = atom(0);
+    let (len, rev) = (size.clone(), revision.clone());
+    // Depends on `revision` without its value affecting the result
+    let rows = atom_derived_hashed(move |get| {
+        get.get(rev.as_atom())?;
+        Ok((0..get.get(len.as_atom())?).collect::<Vec<usize>>())
+    });
+    let source = rows.clone();
+    let downstream_runs = Arc::new(AtomicUsize::new(0));
+    let runs = downstream_runs.clone();
+    let total = atom_derived(move |get| {
+        runs.fetch_add(1, Ordering::SeqCst);
+        Ok(get.get(&source)?.len())
+    });
+
+    let notified = Arc::new(AtomicUsize::new(0));
+    let counter = notified.clone();
+    let _unsub = store.sub(&rows, move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    assert_eq!(store.get(&total).unwrap(), 10_000);
+    assert_eq!(downstream_runs.load(Ordering::SeqCst), 1);
+
+    // Recomputes to an identical vector: same hash, no change
+    store.set(&revision, 1).unwrap();
+    assert_eq!(store.get(&total).unwrap(), 10_000);
+    assert_eq!(downstream_runs.load(Ordering::SeqCst), 1);
+    assert_eq!(notified.load(Ordering::SeqCst), 0);
+
+    store.set(&size, 5).unwrap();
+    assert_eq!(store.get(&total).unwrap(), 5);
+    assert_eq!(downstream_runs.load(Ordering::SeqCst), 2);
+    assert_eq!(notified.load(Ordering::SeqCst), 1);
+}